@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE activity DROP COLUMN average_watts;
+ALTER TABLE activity DROP COLUMN weighted_average_watts;
+ALTER TABLE activity DROP COLUMN kilojoules;
+ALTER TABLE activity DROP COLUMN device_watts;
+ALTER TABLE activity DROP COLUMN power_curve;
+ALTER TABLE raw_activity DROP COLUMN streams;
@@ -0,0 +1,6 @@
+ALTER TABLE activity ADD COLUMN average_watts FLOAT;
+ALTER TABLE activity ADD COLUMN weighted_average_watts FLOAT;
+ALTER TABLE activity ADD COLUMN kilojoules FLOAT;
+ALTER TABLE activity ADD COLUMN device_watts BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE activity ADD COLUMN power_curve TEXT;
+ALTER TABLE raw_activity ADD COLUMN streams TEXT;
@@ -0,0 +1,14 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE activity ADD COLUMN segment_efforts TEXT;
+UPDATE activity SET segment_efforts = (
+    SELECT json_group_array(json_object(
+        'segment_id', segment_id,
+        'elapsed_time', elapsed_time,
+        'moving_time', moving_time,
+        'pr_rank', pr_rank,
+        'kom_rank', kom_rank
+    ))
+    FROM activity_segment_effort
+    WHERE activity_segment_effort.activity_id = activity.id
+);
+DROP TABLE activity_segment_effort;
@@ -0,0 +1,30 @@
+CREATE TABLE activity_segment_effort (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    activity_id BIGINT NOT NULL,
+    segment_id BIGINT NOT NULL,
+    elapsed_time INT NOT NULL,
+    moving_time INT NOT NULL,
+    pr_rank INT,
+    kom_rank INT
+);
+CREATE INDEX activity_segment_effort_activity_id ON activity_segment_effort (activity_id);
+CREATE INDEX activity_segment_effort_segment_id ON activity_segment_effort (segment_id);
+
+INSERT INTO activity_segment_effort (
+    activity_id,
+    segment_id,
+    elapsed_time,
+    moving_time,
+    pr_rank,
+    kom_rank
+) SELECT
+    activity.id,
+    json_extract(effort.value, '$.segment_id'),
+    json_extract(effort.value, '$.elapsed_time'),
+    json_extract(effort.value, '$.moving_time'),
+    json_extract(effort.value, '$.pr_rank'),
+    json_extract(effort.value, '$.kom_rank')
+FROM activity, json_each(activity.segment_efforts) AS effort
+WHERE activity.segment_efforts IS NOT NULL;
+
+ALTER TABLE activity DROP COLUMN segment_efforts;
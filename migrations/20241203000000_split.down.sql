@@ -0,0 +1,16 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE activity ADD COLUMN activity_splits TEXT;
+UPDATE activity SET activity_splits = (
+    SELECT json_group_array(json_object(
+        'distance', distance,
+        'moving_time', moving_time,
+        'elapsed_time', elapsed_time,
+        'average_speed', average_speed,
+        'elevation_difference', elevation_difference,
+        'split', split
+    ))
+    FROM activity_split
+    WHERE activity_split.activity_id = activity.id
+    AND activity_split.unit_system = 'imperial'
+);
+DROP TABLE activity_split;
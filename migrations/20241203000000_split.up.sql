@@ -0,0 +1,58 @@
+CREATE TABLE activity_split (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    activity_id BIGINT NOT NULL,
+    unit_system VARCHAR(255) NOT NULL,
+    split INT NOT NULL,
+    distance FLOAT NOT NULL,
+    moving_time INT NOT NULL,
+    elapsed_time INT NOT NULL,
+    average_speed FLOAT NOT NULL,
+    elevation_difference FLOAT NOT NULL
+);
+CREATE INDEX activity_split_activity_id ON activity_split (activity_id);
+
+INSERT INTO activity_split (
+    activity_id,
+    unit_system,
+    split,
+    distance,
+    moving_time,
+    elapsed_time,
+    average_speed,
+    elevation_difference
+) SELECT
+    raw_activity.id,
+    'metric',
+    json_extract(split.value, '$.split'),
+    json_extract(split.value, '$.distance'),
+    json_extract(split.value, '$.moving_time'),
+    json_extract(split.value, '$.elapsed_time'),
+    json_extract(split.value, '$.average_speed'),
+    COALESCE(json_extract(split.value, '$.elevation_difference'), 0)
+FROM raw_activity, json_each(raw_activity.activity, '$.splits_metric') AS split
+WHERE raw_activity.activity IS NOT NULL
+AND raw_activity.id IN (SELECT id FROM activity);
+
+INSERT INTO activity_split (
+    activity_id,
+    unit_system,
+    split,
+    distance,
+    moving_time,
+    elapsed_time,
+    average_speed,
+    elevation_difference
+) SELECT
+    raw_activity.id,
+    'imperial',
+    json_extract(split.value, '$.split'),
+    json_extract(split.value, '$.distance'),
+    json_extract(split.value, '$.moving_time'),
+    json_extract(split.value, '$.elapsed_time'),
+    json_extract(split.value, '$.average_speed'),
+    COALESCE(json_extract(split.value, '$.elevation_difference'), 0)
+FROM raw_activity, json_each(raw_activity.activity, '$.splits_standard') AS split
+WHERE raw_activity.activity IS NOT NULL
+AND raw_activity.id IN (SELECT id FROM activity);
+
+ALTER TABLE activity DROP COLUMN activity_splits;
@@ -0,0 +1,6 @@
+ALTER TABLE activity ADD COLUMN gear_id TEXT;
+UPDATE activity SET gear_id = (
+    SELECT json_extract(COALESCE(raw_activity.activity, raw_activity.listed), '$.gear_id')
+    FROM raw_activity
+    WHERE raw_activity.id = activity.id
+);
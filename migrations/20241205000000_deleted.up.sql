@@ -0,0 +1 @@
+ALTER TABLE activity ADD COLUMN deleted BOOLEAN NOT NULL DEFAULT false;
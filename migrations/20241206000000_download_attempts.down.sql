@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE raw_activity DROP COLUMN attempts;
+ALTER TABLE raw_activity DROP COLUMN last_error;
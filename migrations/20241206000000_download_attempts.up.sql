@@ -0,0 +1,2 @@
+ALTER TABLE raw_activity ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE raw_activity ADD COLUMN last_error TEXT;
@@ -0,0 +1,24 @@
+CREATE TABLE athlete (
+    id BIGINT PRIMARY KEY NOT NULL,
+    username VARCHAR(255),
+    firstname VARCHAR(255) NOT NULL,
+    lastname VARCHAR(255) NOT NULL,
+    city VARCHAR(255),
+    state VARCHAR(255),
+    country VARCHAR(255),
+    weight FLOAT,
+    ftp INT,
+    stats TEXT,
+    updated_at DATETIME NOT NULL
+);
+
+CREATE TABLE gear (
+    id VARCHAR(255) PRIMARY KEY NOT NULL,
+    gear_type VARCHAR(255) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    brand_name VARCHAR(255),
+    model_name VARCHAR(255),
+    distance FLOAT NOT NULL,
+    is_primary BOOLEAN NOT NULL DEFAULT false,
+    retired BOOLEAN NOT NULL DEFAULT false
+);
@@ -0,0 +1,43 @@
+CREATE TABLE activity_lap (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    activity_id BIGINT NOT NULL,
+    lap_index INT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    distance FLOAT NOT NULL,
+    moving_time INT NOT NULL,
+    elapsed_time INT NOT NULL,
+    average_speed FLOAT NOT NULL,
+    average_heartrate FLOAT,
+    max_heartrate FLOAT,
+    average_cadence FLOAT,
+    total_elevation_gain FLOAT NOT NULL
+);
+CREATE INDEX activity_lap_activity_id ON activity_lap (activity_id);
+
+INSERT INTO activity_lap (
+    activity_id,
+    lap_index,
+    name,
+    distance,
+    moving_time,
+    elapsed_time,
+    average_speed,
+    average_heartrate,
+    max_heartrate,
+    average_cadence,
+    total_elevation_gain
+) SELECT
+    raw_activity.id,
+    json_extract(lap.value, '$.lap_index'),
+    json_extract(lap.value, '$.name'),
+    json_extract(lap.value, '$.distance'),
+    json_extract(lap.value, '$.moving_time'),
+    json_extract(lap.value, '$.elapsed_time'),
+    json_extract(lap.value, '$.average_speed'),
+    json_extract(lap.value, '$.average_heartrate'),
+    json_extract(lap.value, '$.max_heartrate'),
+    json_extract(lap.value, '$.average_cadence'),
+    COALESCE(json_extract(lap.value, '$.total_elevation_gain'), 0)
+FROM raw_activity, json_each(raw_activity.activity, '$.laps') AS lap
+WHERE raw_activity.activity IS NOT NULL
+AND raw_activity.id IN (SELECT id FROM activity);
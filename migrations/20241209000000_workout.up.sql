@@ -0,0 +1 @@
+ALTER TABLE activity ADD COLUMN workout TEXT;
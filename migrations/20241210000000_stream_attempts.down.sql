@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE raw_activity DROP COLUMN stream_attempts;
+ALTER TABLE raw_activity DROP COLUMN stream_error;
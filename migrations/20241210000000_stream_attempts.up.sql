@@ -0,0 +1,2 @@
+ALTER TABLE raw_activity ADD COLUMN stream_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE raw_activity ADD COLUMN stream_error TEXT;
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+-- the backfilled values are kept, the columns are dropped by 20241201000000_power
@@ -0,0 +1,19 @@
+-- power fields of the activities converted before 20241201000000_power
+UPDATE activity SET
+    average_watts = (
+        SELECT json_extract(COALESCE(raw_activity.activity, raw_activity.listed), '$.average_watts')
+        FROM raw_activity WHERE raw_activity.id = activity.id
+    ),
+    weighted_average_watts = (
+        SELECT json_extract(COALESCE(raw_activity.activity, raw_activity.listed), '$.weighted_average_watts')
+        FROM raw_activity WHERE raw_activity.id = activity.id
+    ),
+    kilojoules = (
+        SELECT json_extract(COALESCE(raw_activity.activity, raw_activity.listed), '$.kilojoules')
+        FROM raw_activity WHERE raw_activity.id = activity.id
+    ),
+    device_watts = COALESCE((
+        SELECT json_extract(COALESCE(raw_activity.activity, raw_activity.listed), '$.device_watts')
+        FROM raw_activity WHERE raw_activity.id = activity.id
+    ), false)
+WHERE id IN (SELECT id FROM raw_activity);
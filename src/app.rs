@@ -1,7 +1,8 @@
 use std::{
-    collections::HashMap, fmt::Display, io, time::{Duration, SystemTime}
+    collections::HashMap, fmt::Display, fs::{File, OpenOptions}, io, path::PathBuf, time::{Duration, Instant, SystemTime}
 };
 
+use chrono::Local;
 use log::info;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tui::{
@@ -11,20 +12,21 @@ use tui_input::Input;
 use tui_logger::TuiWidgetState;
 
 use crate::{
-    component::{activity_list, activity_segments::ActivitySegments, log_view::LogView, unit_formatter::UnitFormatter}, event::keymap::KeyMap, expr::evaluator::Evaluator, store::activity::{Activity, Segment}, ui
+    component::{activity_list, activity_segments::ActivitySegments, comparison::ComparisonView, gear_view::{GearView, GearViewState}, log_view::LogView, polyline::MapViewport, power_curve_view::PowerCurveView, segment_leaderboard::{SegmentLeaderboard, SegmentLeaderboardState}, stats_view::{StatsView, StatsViewState}, unit_formatter::UnitFormatter}, event::keymap::{KeyMap, MappedKey, StravaEvent}, export::{activities::ExportColumn, route::{export_route, route_points, RouteFormat}}, expr::evaluator::Evaluator, store::{activity::{Activity, Segment}, gear::{Athlete, Gear, RetirementThresholds}, goal::{Goal, GoalProgress}, session::Session}, sync::{status::SyncStatus, SyncRequest}, ui::{self, color::Theme}
 };
 use crate::{
     component::{
-        activity_list::{ActivityList, ActivityListMode, ActivityListState, ActivityViewState},
+        activity_list::{columns::ListColumns, ActivityList, ActivityListMode, ActivityListState, ActivityViewState},
         activity_view::ActivityView,
         View,
     },
     event::{
         input::EventSender,
+        mouse::MouseTracker,
         util::{table_state_next, table_state_prev},
     },
     input::InputEvent,
-    store::activity::{Activities, ActivityStore, SortBy, SortOrder},
+    store::activity::{Activities, ActivityStore, Sections, SortBy, SortOrder},
 };
 
 pub struct ActivityFilters {
@@ -39,7 +41,52 @@ pub struct RankOptions {
     pub rank_order: SortOrder,
 }
 
+impl Default for ActivityFilters {
+    fn default() -> Self {
+        Self {
+            sort_by: SortBy::Date,
+            sort_order: SortOrder::Desc,
+            filter: "".to_string(),
+            anchor_tolerance: 0.005,
+        }
+    }
+}
+
+impl Default for RankOptions {
+    fn default() -> Self {
+        Self {
+            rank_by: SortBy::Pace,
+            rank_order: SortOrder::Desc,
+        }
+    }
+}
+
 impl ActivityFilters {
+    /// Filter, rank and sort the activities as they are shown in the activity list
+    pub fn apply(
+        &self,
+        mut activities: Activities,
+        ranking: &RankOptions,
+        activity_type: &Option<String>,
+        anchored: &Option<Activity>,
+    ) -> Activities {
+        let mut evaluator = Evaluator::new();
+        activities = match evaluator.parse(self.filter.as_str()) {
+            Ok(expr) => activities.by_expr(&evaluator, &expr),
+            Err(_) => activities.where_title_contains(self.filter.as_str()),
+        };
+
+        if let Some(activity_type) = activity_type.clone() {
+            activities = activities.having_activity_type(activity_type);
+        }
+        if let Some(anchored) = anchored {
+            activities = activities.withing_distance_of(anchored, self.anchor_tolerance);
+        }
+        activities
+            .rank(&ranking.rank_by, &ranking.rank_order)
+            .sort(&self.sort_by, &self.sort_order)
+    }
+
     pub fn anchor_tolerance_add(&mut self, delta: f64) {
         self.anchor_tolerance += delta;
         if self.anchor_tolerance < 0.0 {
@@ -48,6 +95,9 @@ impl ActivityFilters {
     }
 }
 
+/// Number of completed periods shown in the history of each goal
+const GOAL_HISTORY: usize = 12;
+
 pub struct Notification {
     text: String,
     created: SystemTime,
@@ -77,20 +127,40 @@ pub struct App<'a> {
     pub unit_formatter: UnitFormatter,
     pub activity_list: ActivityListState,
     pub activity_view_state: ActivityViewState,
+    pub segment_leaderboard: SegmentLeaderboardState,
+    pub stats_view: StatsViewState,
+    pub gear_view: GearViewState,
     pub filters: ActivityFilters,
     pub ranking: RankOptions,
 
     pub activity_type: Option<String>,
+    pub ftp: Option<f64>,
+    pub export_columns: Vec<ExportColumn>,
+    pub export_dir: PathBuf,
+    pub goals: Vec<Goal>,
+    pub goal_progress: Vec<GoalProgress>,
     pub activity: Option<Activity>,
     pub activity_anchored: Option<Activity>,
+    pub activity_marked: Option<Activity>,
     pub activities: Activities,
     pub segments: HashMap<i64,Segment>,
+    pub gear: Vec<Gear>,
+    pub athlete: Option<Athlete>,
+    pub gear_retirement: RetirementThresholds,
 
     pub log_view_state: TuiWidgetState,
 
     pub info_message: Option<Notification>,
     pub error_message: Option<Notification>,
     pub key_map: KeyMap,
+    pub theme: Theme,
+    pub list_columns: ListColumns,
+    pub mouse: MouseTracker,
+    pub help_visible: bool,
+    pub help_scroll: u16,
+    pub sync_status: SyncStatus,
+    /// Anchor and selection of the restored session, applied on the first reload
+    restored_session: Option<Session>,
 
     pub store: &'a mut ActivityStore<'a>,
     event_receiver: Receiver<InputEvent>,
@@ -98,7 +168,7 @@ pub struct App<'a> {
 
     event_queue: Vec<InputEvent>,
     active_page: ActivePage,
-    sync_sender: Sender<bool>,
+    sync_sender: Sender<SyncRequest>,
 }
 
 #[derive(Clone, Copy)]
@@ -107,6 +177,28 @@ pub enum ActivePage {
     ActivitySegments,
     Activity,
     LogView,
+    PowerCurve,
+    Comparison,
+    Segments,
+    Stats,
+    Gear,
+}
+
+impl ActivePage {
+    /// Name of the view used for view specific key bindings
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActivePage::ActivityList => "list",
+            ActivePage::ActivitySegments => "segments",
+            ActivePage::Activity => "activity",
+            ActivePage::LogView => "log",
+            ActivePage::PowerCurve => "power",
+            ActivePage::Comparison => "comparison",
+            ActivePage::Segments => "leaderboard",
+            ActivePage::Stats => "stats",
+            ActivePage::Gear => "gear",
+        }
+    }
 }
 
 impl App<'_> {
@@ -114,7 +206,7 @@ impl App<'_> {
         store: &'a mut ActivityStore<'a>,
         event_receiver: Receiver<InputEvent>,
         event_sender: EventSender,
-        sync_sender: Sender<bool>,
+        sync_sender: Sender<SyncRequest>,
     ) -> App<'a> {
         App {
             quit: false,
@@ -129,32 +221,45 @@ impl App<'_> {
                 filter_dialog: false,
                 sort_dialog: false,
                 rank_dialog: false,
+                export_dialog: false,
+                columns_dialog: false,
                 use_moving_time: true,
+                table_area: Rect::default(),
             },
             activity_view_state: ActivityViewState {
                 pace_table_state: TableState::default(),
                 segment_efforts_state: TableState::default(),
                 selected_split: None,
+                sections: Sections::Splits,
+                export_dialog: false,
+                map_viewport: MapViewport::default(),
+                map_area: Rect::default(),
+                splits_area: Rect::default(),
+                segment_efforts_area: Rect::default(),
             },
+            segment_leaderboard: SegmentLeaderboardState::new(),
+            stats_view: StatsViewState::new(),
+            gear_view: GearViewState::default(),
             log_view_state: TuiWidgetState::default()
                 .set_default_display_level(log::LevelFilter::Debug),
-            filters: ActivityFilters {
-                sort_by: SortBy::Date,
-                sort_order: SortOrder::Desc,
-                filter: "".to_string(),
-                anchor_tolerance: 0.005,
-            },
-            ranking: RankOptions {
-                rank_by: SortBy::Pace,
-                rank_order: SortOrder::Desc,
-            },
+            filters: ActivityFilters::default(),
+            ranking: RankOptions::default(),
             activity: None,
             activity_anchored: None,
+            activity_marked: None,
             activities: Activities::new(),
             segments: HashMap::new(),
+            gear: vec![],
+            athlete: None,
+            gear_retirement: RetirementThresholds::default(),
             store,
 
             activity_type: None,
+            ftp: None,
+            export_columns: ExportColumn::defaults(),
+            export_dir: PathBuf::from("."),
+            goals: vec![],
+            goal_progress: vec![],
             info_message: None,
             error_message: None,
             event_receiver,
@@ -162,6 +267,41 @@ impl App<'_> {
             event_queue: vec![],
             sync_sender,
             key_map: KeyMap::default(),
+            theme: Theme::default(),
+            list_columns: ListColumns::default(),
+            mouse: MouseTracker::default(),
+            help_visible: false,
+            help_scroll: 0,
+            sync_status: SyncStatus::default(),
+            restored_session: None,
+        }
+    }
+
+    /// Restore the sorting, ranking, filter, units, anchor and selection of a previous session
+    pub fn restore_session(&mut self, session: Session) {
+        self.filters.sort_by = session.sort_by.clone();
+        self.filters.sort_order = session.sort_order.clone();
+        self.filters.filter = session.filter.clone();
+        self.filters.anchor_tolerance = session.anchor_tolerance;
+        self.activity_list.filter_text_area = Input::new(session.filter.clone());
+        self.ranking.rank_by = session.rank_by.clone();
+        self.ranking.rank_order = session.rank_order.clone();
+        self.unit_formatter.system = session.unit_system.clone();
+        self.restored_session = Some(session);
+    }
+
+    /// The current state of the activity list to be restored in the next session
+    pub fn session(&mut self) -> Session {
+        Session {
+            sort_by: self.filters.sort_by.clone(),
+            sort_order: self.filters.sort_order.clone(),
+            rank_by: self.ranking.rank_by.clone(),
+            rank_order: self.ranking.rank_order.clone(),
+            filter: self.filters.filter.clone(),
+            anchor_tolerance: self.filters.anchor_tolerance,
+            unit_system: self.unit_formatter.system.clone(),
+            anchored: self.activity_anchored.as_ref().map(|a| a.id),
+            selected: self.activity_list.table_state().selected(),
         }
     }
     pub async fn run(
@@ -179,6 +319,11 @@ impl App<'_> {
                 ActivePage::ActivitySegments => Box::new(ActivitySegments::new()),
                 ActivePage::Activity => Box::new(ActivityView {}),
                 ActivePage::LogView => Box::new(LogView::new()),
+                ActivePage::PowerCurve => Box::new(PowerCurveView::new()),
+                ActivePage::Comparison => Box::new(ComparisonView::new()),
+                ActivePage::Segments => Box::new(SegmentLeaderboard::new()),
+                ActivePage::Stats => Box::new(StatsView::new()),
+                ActivePage::Gear => Box::new(GearView::new()),
             };
 
             if let Some(message) = &self.info_message {
@@ -192,8 +337,7 @@ impl App<'_> {
                 }
             }
 
-            while self.event_queue.len() > 1 {
-                let event = self.event_queue.pop().unwrap();
+            for event in self.event_queue.drain(..) {
                 info!("Sending event: {:?}", event);
                 self.event_sender.send(event).await?;
             }
@@ -205,8 +349,18 @@ impl App<'_> {
                 }
                 match event {
                     InputEvent::Input(k) => {
-                        let key = self.key_map.map_key(k);
-                        view.handle(self, key);
+                        let key = self.key_map.map_key(k, self.active_page.name());
+                        if !self.handle_help(&key) {
+                            view.handle(self, key);
+                        }
+                    }
+                    InputEvent::Mouse(m) => {
+                        if let Some((event, input)) = self.mouse.map(m, Instant::now()) {
+                            let key = MappedKey::from_mouse(event, input);
+                            if !self.handle_help(&key) {
+                                view.handle(self, key);
+                            }
+                        }
                     }
                     InputEvent::InfoMessage(message) => {
                         self.info_message = Some(Notification::new(message));
@@ -218,7 +372,15 @@ impl App<'_> {
                     InputEvent::Reload => {
                         self.reload().await;
                     }
-                    InputEvent::Sync => self.sync_sender.send(true).await?,
+                    InputEvent::Sync => self.sync_sender.send(SyncRequest::Sync).await?,
+                    InputEvent::Reconcile => self.sync_sender.send(SyncRequest::Reconcile).await?,
+                    InputEvent::SyncStatus(status) => self.sync_status = status,
+                    InputEvent::ExportRoute(activity_id, format) => {
+                        self.export_route(activity_id, format).await;
+                    }
+                    InputEvent::SegmentEfforts(segment_id) => {
+                        self.load_segment_efforts(segment_id).await;
+                    }
                 }
             }
             self.render(terminal, view.as_mut())?;
@@ -227,26 +389,105 @@ impl App<'_> {
     }
 
     pub async fn reload(&mut self) {
-        let mut activities = self.store.activities().await;
+        let activities = self.store.activities().await;
         self.segments = self.store.segments().await;
+        self.gear = self.store.gear().await;
+        self.athlete = self.store.athlete().await;
 
-        let mut evaluator = Evaluator::new();
-        activities = match evaluator.parse(self.filters.filter.as_str()) {
-            Ok(expr) => activities.by_expr(&evaluator, &expr),
-            Err(_) => activities.where_title_contains(self.filters.filter.as_str()),
-        };
-
-        if let Some(activity_type) = self.activity_type.clone() {
-            activities = activities.having_activity_type(activity_type);
+        info!("Reloaded");
+        let now = Local::now();
+        self.goal_progress = self
+            .goals
+            .iter()
+            .map(|goal| goal.progress(&activities, now, GOAL_HISTORY))
+            .collect();
+        let restored = self.restored_session.take();
+        if let Some(anchored) = restored.as_ref().and_then(|s| s.anchored) {
+            if let Some(activity) = activities.find(anchored) {
+                self.activity_anchored = Some(activity.clone());
+                self.activity_list.mode = ActivityListMode::Anchored;
+            }
+        }
+        self.activities = self.filters.apply(
+            activities,
+            &self.ranking,
+            &self.activity_type,
+            &self.activity_anchored,
+        );
+        if let Some(selected) = restored.and_then(|s| s.selected) {
+            if selected < self.activities.len() {
+                self.activity_list.table_state().select(Some(selected));
+            }
         }
-        if let Some(anchored) = &self.activity_anchored {
-            activities = activities.withing_distance_of(anchored, self.filters.anchor_tolerance);
+        if let Some(segment_id) = self.segment_leaderboard.selected_segment {
+            self.load_segment_efforts(segment_id).await;
         }
-        info!("Reloaded");
-        self.activities = activities
-            .rank(&self.ranking.rank_by, &self.ranking.rank_order)
-            .sort(&self.filters.sort_by, &self.filters.sort_order)
+    }
+
+    /// Load the efforts of the listed activities on the segment, fastest first
+    pub async fn load_segment_efforts(&mut self, segment_id: i64) {
+        let attempts = self.store.segment_efforts_for(segment_id).await;
+        self.segment_leaderboard.attempts = attempts
+            .into_iter()
+            .filter(|a| self.activities.find(a.activity_id).is_some())
+            .collect();
+    }
+
+    /// Show the efforts on the segment in the leaderboard, returning to the given page when leaving it
+    pub fn drill_into_segment(&mut self, segment_id: i64, return_to: Option<ActivePage>) {
+        self.segment_leaderboard.drill_into(segment_id, return_to);
+        self.send(InputEvent::SegmentEfforts(segment_id));
+    }
+
+    /// Export the route of the activity to a file in the current directory
+    pub async fn export_route(&mut self, activity_id: i64, format: RouteFormat) {
+        let activity = self
+            .activity
+            .iter()
+            .chain(self.activities.find(activity_id))
+            .find(|a| a.id == activity_id)
+            .cloned();
+        let activity = match activity {
+            Some(a) => a,
+            None => return,
+        };
+        let streams = self.store.streams(activity_id).await;
+        let points = route_points(&activity, streams.as_ref());
+        let name = format!("strava-activity-{}.{}", activity_id, format.extension());
+        let result = self
+            .create_export_file(&name)
+            .and_then(|(mut file, path)| {
+                export_route(&mut file, &activity, &points, &format)?;
+                Ok(path)
+            });
+        match result {
+            Ok(path) => {
+                info!("Exported route of activity {} to {}", activity_id, path.display());
+                self.info_message = Some(Notification::new(format!(
+                    "exported route to {}",
+                    path.display()
+                )))
+            }
+            Err(e) => {
+                self.error_message = Some(Notification::new(format!(
+                    "could not export route: {}",
+                    e
+                )))
+            }
+        }
+    }
 
+    /// Create a new file in the export directory, existing files are never overwritten
+    pub fn create_export_file(&self, name: &str) -> anyhow::Result<(File, PathBuf)> {
+        let path = self.export_dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => Ok((file, path)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Err(anyhow::Error::msg(format!(
+                "{} already exists, not overwriting it",
+                path.display()
+            ))),
+            Err(e) => Err(anyhow::Error::msg(format!("{}: {}", path.display(), e))),
+        }
     }
 
     pub fn activities(&self) -> Activities {
@@ -274,6 +515,35 @@ impl App<'_> {
         }
     }
 
+    pub(crate) fn selected_activity(&mut self) -> Option<Activity> {
+        let selected = self.activity_list.table_state().selected()?;
+        self.activities.get(selected).cloned()
+    }
+
+    /// Mark an activity to compare other activities against, marking the
+    /// already marked activity again unmarks it.
+    pub(crate) fn toggle_marked(&mut self, activity: Activity) {
+        if self.activity_marked.as_ref().is_some_and(|m| m.id == activity.id) {
+            self.activity_marked = None;
+            return;
+        }
+        self.info_message = Some(Notification::new(format!(
+            "marked \"{}\" for comparison",
+            activity.title
+        )));
+        self.activity_marked = Some(activity);
+    }
+
+    pub(crate) fn compare_to_marked(&mut self) {
+        if self.activity_marked.is_none() {
+            self.error_message = Some(Notification::new(
+                "mark an activity before comparing".to_string(),
+            ));
+            return;
+        }
+        self.switch_to(ActivePage::Comparison);
+    }
+
     pub(crate) fn previous_activity(&mut self) {
         table_state_prev(
             self.activity_list.table_state(),
@@ -343,6 +613,38 @@ impl App<'_> {
         Ok(())
     }
 
+    /// Open, scroll or close the help overlay, returns true if the key was handled
+    fn handle_help(&mut self, key: &MappedKey) -> bool {
+        if self.help_visible {
+            match key.strava_event {
+                StravaEvent::Down | StravaEvent::ScrollDown => {
+                    self.help_scroll = self.help_scroll.saturating_add(1)
+                }
+                StravaEvent::Up | StravaEvent::ScrollUp => {
+                    self.help_scroll = self.help_scroll.saturating_sub(1)
+                }
+                StravaEvent::PageDown => self.help_scroll = self.help_scroll.saturating_add(10),
+                StravaEvent::PageUp => self.help_scroll = self.help_scroll.saturating_sub(10),
+                StravaEvent::Help | StravaEvent::Escape | StravaEvent::Quit | StravaEvent::Enter => {
+                    self.help_visible = false
+                }
+                _ => (),
+            }
+            return true;
+        }
+        // the filter dialog takes text input
+        if key.strava_event == StravaEvent::Help && !self.activity_list.filter_dialog {
+            self.help_visible = true;
+            self.help_scroll = 0;
+            return true;
+        }
+        false
+    }
+
+    pub fn active_page(&self) -> ActivePage {
+        self.active_page
+    }
+
     pub(crate) fn switch_to(&mut self, view: ActivePage) {
         self.previous_page = Some(self.active_page);
         self.active_page = view;
@@ -5,6 +5,7 @@ use super::AuthResponse;
 
 pub struct AccessTokenFetcher {
     client: Client<HttpsConnector<HttpConnector>>,
+    oauth_url: String,
     client_id: String,
     client_secret: String,
 }
@@ -12,11 +13,13 @@ pub struct AccessTokenFetcher {
 impl AccessTokenFetcher {
     pub(crate) fn new(
         client: Client<HttpsConnector<HttpConnector>>,
+        oauth_url: String,
         client_id: String,
         client_secret: String,
     ) -> Self {
         Self {
             client,
+            oauth_url,
             client_id,
             client_secret,
         }
@@ -27,7 +30,7 @@ impl AccessTokenFetcher {
         code: String,
     ) -> Result<AuthResponse, anyhow::Error> {
         let req = Request::builder()
-            .uri("https://www.strava.com/oauth/token")
+            .uri(format!("{}/token", self.oauth_url))
             .method("POST")
             .body(Body::from(format!(
                 "client_id={}&client_secret={}&code={}&grant_type=authorization_code",
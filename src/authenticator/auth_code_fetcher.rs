@@ -10,14 +10,16 @@ use url::form_urlencoded;
 use crate::event::logger::Logger;
 
 pub struct AuthCodeFetcher {
+    oauth_url: String,
     client_id: String,
     addr: String,
     logger: Logger,
 }
 
 impl AuthCodeFetcher {
-    pub(crate) fn new(client_id: String, logger: Logger) -> Self {
+    pub(crate) fn new(oauth_url: String, client_id: String, logger: Logger) -> Self {
         Self {
+            oauth_url,
             client_id,
             addr: "127.0.0.1:8112".to_string(),
             logger,
@@ -30,7 +32,7 @@ impl AuthCodeFetcher {
 
         let addr = SocketAddr::from_str("127.0.0.1:8112").unwrap();
 
-        let auth_url = format!("https://www.strava.com/oauth/authorize?client_id={}&response_type=code&redirect_uri=http://{}/exchange_token&approval_prompt=force&scope=activity:read_all,read", self.client_id, self.addr);
+        let auth_url = format!("{}/authorize?client_id={}&response_type=code&redirect_uri=http://{}/exchange_token&approval_prompt=force&scope=activity:read_all,profile:read_all,read", self.oauth_url, self.client_id, self.addr);
 
         self.logger.info(format!("Trying to open URL: {}", auth_url)).await;
 
@@ -25,15 +25,16 @@ pub struct Authenticator {
 impl Authenticator {
     pub(crate) fn new(
         client: Client<HttpsConnector<HttpConnector>>,
+        oauth_url: String,
         client_id: String,
         client_secret: String,
         token_path: String,
         logger: Logger,
     ) -> Self {
         Authenticator {
-            token_fetch: AuthCodeFetcher::new(client_id.clone(), logger.clone()),
+            token_fetch: AuthCodeFetcher::new(oauth_url.clone(), client_id.clone(), logger.clone()),
             token_store: TokenStore::new(token_path),
-            access_token_fetcher: AccessTokenFetcher::new(client, client_id, client_secret),
+            access_token_fetcher: AccessTokenFetcher::new(client, oauth_url, client_id, client_secret),
             logger
         }
     }
@@ -54,6 +55,11 @@ impl Authenticator {
 
         Ok(access_token.access_token)
     }
+
+    /// Forget the access token, e.g. after the athlete revoked access
+    pub(crate) fn forget(&mut self) -> Result<(), anyhow::Error> {
+        self.token_store.delete()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
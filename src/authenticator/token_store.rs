@@ -1,4 +1,7 @@
-use std::{fs::File, path::Path};
+use std::{
+    fs::{self, File},
+    path::Path,
+};
 
 use super::AuthResponse;
 
@@ -30,4 +33,11 @@ impl TokenStore {
 
         Ok(())
     }
+
+    pub(crate) fn delete(&self) -> Result<(), anyhow::Error> {
+        if Path::new(&self.path).exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,55 @@
+use std::{fs::File, io};
+
+use sqlx::SqlitePool;
+
+use crate::{
+    component::unit_formatter::UnitSystem,
+    config::Config,
+    export::activities::{export_activities, ExportColumn, ExportFormat},
+};
+
+use super::{unit_formatter, QueryArgs};
+
+#[derive(clap::Args, Debug)]
+pub struct ExportArgs {
+    #[command(flatten)]
+    pub query: QueryArgs,
+    /// Output format: csv, json or ndjson
+    #[arg(short, long, default_value = "csv")]
+    pub format: ExportFormat,
+    /// Comma separated list of columns, e.g. date,title,distance
+    #[arg(long)]
+    pub columns: Option<String>,
+    /// Unit system: metric or imperial, defaults to the configured system
+    #[arg(long)]
+    pub units: Option<UnitSystem>,
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+pub async fn run(args: ExportArgs, pool: &SqlitePool, config: &Config) -> Result<(), anyhow::Error> {
+    let columns = match &args.columns {
+        Some(columns) => ExportColumn::parse_list(columns).map_err(anyhow::Error::msg)?,
+        None => config.export_columns.clone(),
+    };
+    let activities = args.query.activities(pool, config).await?;
+    let formatter = unit_formatter(config, &args.units);
+
+    match &args.output {
+        Some(path) => export_activities(
+            &mut File::create(path)?,
+            &activities,
+            &columns,
+            &args.format,
+            &formatter,
+        ),
+        None => export_activities(
+            &mut io::stdout(),
+            &activities,
+            &columns,
+            &args.format,
+            &formatter,
+        ),
+    }
+}
@@ -0,0 +1,37 @@
+use std::{fs::File, io};
+
+use sqlx::SqlitePool;
+
+use crate::{
+    export::route::{export_route, route_points, RouteFormat},
+    store::activity::ActivityStore,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct ExportRouteArgs {
+    /// Strava activity ID
+    pub id: i64,
+    /// Output format: gpx, geojson or kml
+    #[arg(short, long, default_value = "gpx")]
+    pub format: RouteFormat,
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+pub async fn run(args: ExportRouteArgs, pool: &SqlitePool) -> Result<(), anyhow::Error> {
+    let mut store = ActivityStore::new(pool);
+    let activity = store
+        .activities()
+        .await
+        .find(args.id)
+        .cloned()
+        .ok_or_else(|| anyhow::Error::msg(format!("activity {} not found", args.id)))?;
+    let streams = store.streams(args.id).await;
+    let points = route_points(&activity, streams.as_ref());
+
+    match &args.output {
+        Some(path) => export_route(&mut File::create(path)?, &activity, &points, &args.format),
+        None => export_route(&mut io::stdout(), &activity, &points, &args.format),
+    }
+}
@@ -0,0 +1,86 @@
+use std::io;
+
+use sqlx::SqlitePool;
+use strum::IntoEnumIterator;
+
+use crate::{
+    component::unit_formatter::{UnitFormatter, UnitSystem},
+    config::Config,
+    export::activities::{export_activities, ExportColumn, ExportFormat},
+    store::activity::Activities,
+};
+
+use super::{
+    table::{render_table, OutputFormat},
+    unit_formatter, QueryArgs,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct ListArgs {
+    #[command(flatten)]
+    pub query: QueryArgs,
+    /// Only show the first N activities
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+    /// Output format: table or json
+    #[arg(short, long, default_value = "table")]
+    pub format: OutputFormat,
+    /// Unit system: metric or imperial, defaults to the configured system
+    #[arg(long)]
+    pub units: Option<UnitSystem>,
+}
+
+pub async fn run(args: ListArgs, pool: &SqlitePool, config: &Config) -> Result<(), anyhow::Error> {
+    let activities = args.query.activities(pool, config).await?;
+    let activities = match args.limit {
+        Some(limit) => Activities::from(activities.to_vec().into_iter().take(limit).collect::<Vec<_>>()),
+        None => activities,
+    };
+    let formatter = unit_formatter(config, &args.units);
+
+    match args.format {
+        OutputFormat::Json => export_activities(
+            &mut io::stdout(),
+            &activities,
+            &ExportColumn::iter().collect::<Vec<ExportColumn>>(),
+            &ExportFormat::Json,
+            &formatter,
+        ),
+        OutputFormat::Table => {
+            print!("{}", activity_table(&activities, &formatter));
+            Ok(())
+        }
+    }
+}
+
+fn activity_table(activities: &Activities, formatter: &UnitFormatter) -> String {
+    let rows: Vec<Vec<String>> = activities
+        .to_vec()
+        .iter()
+        .map(|activity| {
+            vec![
+                format!("{}", activity.id),
+                match activity.start_date {
+                    Some(x) => x.format("%Y-%m-%d").to_string(),
+                    None => "".to_string(),
+                },
+                activity.activity_type.clone(),
+                activity.title.clone(),
+                formatter.distance(activity.distance),
+                formatter.stopwatch_time(activity.moving_time),
+                formatter.pace(activity.moving_time, activity.distance),
+                formatter.speed(activity.meters_per_hour()),
+                activity
+                    .average_heartrate
+                    .map_or_else(|| "n/a".to_string(), |v| format!("{:.0}", v)),
+                formatter.elevation(activity.total_elevation_gain),
+                format!("{}", activity.rank),
+            ]
+        })
+        .collect();
+
+    render_table(
+        &["ID", "Date", "Type", "Title", "Dst", "Time", "Pace", "Speed", "HR", "Elevation", "Rank"],
+        &rows,
+    )
+}
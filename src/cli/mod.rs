@@ -0,0 +1,111 @@
+pub mod export;
+pub mod export_route;
+pub mod list;
+pub mod show;
+pub mod stats;
+pub mod table;
+
+use clap::Subcommand;
+use sqlx::SqlitePool;
+
+use crate::{
+    app::{ActivityFilters, RankOptions},
+    component::unit_formatter::{UnitFormatter, UnitSystem},
+    config::Config,
+    expr::evaluator::Evaluator,
+    store::activity::{Activities, ActivityStore, SortBy, SortOrder},
+};
+
+use self::{export::ExportArgs, export_route::ExportRouteArgs, list::ListArgs, show::ShowArgs, stats::StatsArgs};
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// List the (filtered) activities
+    List(ListArgs),
+    /// Show the details of a single activity
+    Show(ShowArgs),
+    /// Totals and averages grouped by week, month, year, type or gear
+    Stats(StatsArgs),
+    /// Export the (filtered) activities as CSV, JSON or NDJSON
+    Export(ExportArgs),
+    /// Export the route of a single activity as GPX, GeoJSON or KML
+    ExportRoute(ExportRouteArgs),
+}
+
+/// Filter, sort and rank options shared by the commands which list activities
+#[derive(clap::Args, Debug)]
+pub struct QueryArgs {
+    /// Filter expression, e.g. 'type = "Run" and distance > 10k'
+    #[arg(long)]
+    pub filter: Option<String>,
+    /// Sort by date, distance, pace, heartrate or time
+    #[arg(long)]
+    pub sort: Option<SortBy>,
+    /// Sort order: asc or desc
+    #[arg(long)]
+    pub order: Option<SortOrder>,
+    /// Rank by date, distance, pace, heartrate or time
+    #[arg(long)]
+    pub rank: Option<SortBy>,
+}
+
+impl QueryArgs {
+    pub fn filters(&self) -> ActivityFilters {
+        let defaults = ActivityFilters::default();
+        ActivityFilters {
+            sort_by: self.sort.clone().unwrap_or(defaults.sort_by),
+            sort_order: self.order.clone().unwrap_or(defaults.sort_order),
+            filter: self.filter.clone().unwrap_or(defaults.filter),
+            ..defaults
+        }
+    }
+
+    pub fn ranking(&self) -> RankOptions {
+        match &self.rank {
+            Some(rank_by) => RankOptions {
+                rank_by: rank_by.clone(),
+                rank_order: match rank_by {
+                    SortBy::Time => SortOrder::Asc,
+                    _ => SortOrder::Desc,
+                },
+            },
+            None => RankOptions::default(),
+        }
+    }
+
+    /// Load the activities applying the filter, sort and rank in the same way as the TUI.
+    /// Unlike the TUI, a filter which can not be parsed is an error instead of a title search.
+    pub async fn activities(&self, pool: &SqlitePool, config: &Config) -> Result<Activities, anyhow::Error> {
+        if let Some(filter) = &self.filter {
+            Evaluator::new()
+                .parse(filter)
+                .map_err(|err| anyhow::Error::msg(format!("invalid filter `{}`: {}", filter, err)))?;
+        }
+        let mut store = ActivityStore::new(pool);
+        Ok(self.filters().apply(
+            store.activities().await,
+            &self.ranking(),
+            &config.activity_type,
+            &None,
+        ))
+    }
+}
+
+/// The configured unit formatter, using the unit system given on the command line if any
+pub fn unit_formatter(config: &Config, units: &Option<UnitSystem>) -> UnitFormatter {
+    let mut formatter = config.units.clone();
+    if let Some(system) = units {
+        formatter.system = system.clone();
+    }
+    formatter
+}
+
+pub async fn run(command: Command, pool: &SqlitePool, config: &Config) -> Result<(), anyhow::Error> {
+    match command {
+        Command::List(args) => list::run(args, pool, config).await,
+        Command::Show(args) => show::run(args, pool, config).await,
+        Command::Stats(args) => stats::run(args, pool, config).await,
+        Command::Export(args) => export::run(args, pool, config).await,
+        Command::ExportRoute(args) => export_route::run(args, pool).await,
+    }
+}
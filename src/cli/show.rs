@@ -0,0 +1,112 @@
+use std::io;
+
+use sqlx::SqlitePool;
+
+use crate::{
+    component::{
+        stats::power_stats,
+        unit_formatter::{UnitFormatter, UnitSystem},
+    },
+    config::Config,
+    store::activity::{Activity, ActivityStore, SportType},
+};
+
+use super::{
+    table::{render_table, OutputFormat},
+    unit_formatter,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct ShowArgs {
+    /// Strava activity ID
+    pub id: i64,
+    /// Output format: table or json
+    #[arg(short, long, default_value = "table")]
+    pub format: OutputFormat,
+    /// Unit system: metric or imperial, defaults to the configured system
+    #[arg(long)]
+    pub units: Option<UnitSystem>,
+}
+
+pub async fn run(args: ShowArgs, pool: &SqlitePool, config: &Config) -> Result<(), anyhow::Error> {
+    let activity = ActivityStore::new(pool)
+        .activities()
+        .await
+        .find(args.id)
+        .cloned()
+        .ok_or_else(|| anyhow::Error::msg(format!("activity {} not found", args.id)))?;
+    let formatter = unit_formatter(config, &args.units);
+
+    match args.format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(io::stdout(), &activity)?;
+            println!();
+        }
+        OutputFormat::Table => print!("{}", activity_details(&activity, &formatter, config.ftp)),
+    }
+    Ok(())
+}
+
+fn activity_details(activity: &Activity, formatter: &UnitFormatter, ftp: Option<f64>) -> String {
+    let optional = |v: Option<f64>| v.map_or_else(|| "n/a".to_string(), |v| format!("{:.0}", v));
+    let mut stats: Vec<(String, String)> = vec![
+        ("ID".to_string(), format!("{}", activity.id)),
+        (
+            "Date".to_string(),
+            match activity.start_date {
+                Some(d) => d.format("%Y-%m-%d %H:%M").to_string(),
+                None => "".to_string(),
+            },
+        ),
+        ("Activity Type".to_string(), activity.activity_type.clone()),
+        ("Sport Type".to_string(), activity.sport_type.clone()),
+        ("Distance".to_string(), formatter.distance(activity.distance)),
+        ("Moving Time".to_string(), formatter.stopwatch_time(activity.moving_time)),
+        ("Elapsed Time".to_string(), formatter.stopwatch_time(activity.elapsed_time)),
+        ("Pace".to_string(), formatter.pace(activity.moving_time, activity.distance)),
+        ("Speed".to_string(), formatter.speed(activity.meters_per_hour())),
+        ("Avg Heart Rate".to_string(), optional(activity.average_heartrate)),
+        ("Max Heart Rate".to_string(), optional(activity.max_heartrate)),
+        ("Elevation".to_string(), formatter.elevation(activity.total_elevation_gain)),
+        ("Cadence".to_string(), optional(activity.average_cadence)),
+        ("Kudos".to_string(), format!("{}", activity.kudos)),
+        (
+            "Location".to_string(),
+            [&activity.location_city, &activity.location_country]
+                .iter()
+                .filter_map(|l| l.as_ref())
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", "),
+        ),
+    ];
+    if let SportType::Ride = activity.activity_category() {
+        stats.append(&mut power_stats(ftp, activity));
+    }
+
+    let mut text = format!("{}\n{}\n\n", activity.title, "=".repeat(activity.title.chars().count()));
+    if !activity.description.is_empty() {
+        text.push_str(&format!("{}\n\n", activity.description));
+    }
+    let rows: Vec<Vec<String>> = stats.into_iter().map(|(k, v)| vec![k, v]).collect();
+    text.push_str(&render_table(&["Stat", "Value"], &rows));
+
+    let splits = activity.splits(&formatter.system);
+    if !splits.is_empty() {
+        let rows: Vec<Vec<String>> = splits
+            .iter()
+            .map(|split| {
+                vec![
+                    format!("{}", split.split),
+                    formatter.distance(split.distance),
+                    formatter.stopwatch_time(split.moving_time),
+                    formatter.pace(split.moving_time, split.distance),
+                    formatter.elevation(split.elevation_difference),
+                ]
+            })
+            .collect();
+        text.push_str("\nSplits\n\n");
+        text.push_str(&render_table(&["#", "Dst", "Time", "Pace", "Elevation"], &rows));
+    }
+    text
+}
@@ -0,0 +1,81 @@
+use std::io;
+
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+use crate::{
+    component::{
+        stats_view::{stats_row, STATS_HEADERS},
+        unit_formatter::{UnitFormatter, UnitSystem},
+    },
+    config::Config,
+    export::activities::round,
+    store::stats::{group_stats, GroupBy, GroupStats},
+};
+
+use super::{
+    table::{render_table, OutputFormat},
+    unit_formatter, QueryArgs,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct StatsArgs {
+    #[command(flatten)]
+    pub query: QueryArgs,
+    /// Group by week, month, year, type or gear
+    #[arg(short, long, default_value = "month")]
+    pub group_by: GroupBy,
+    /// Output format: table or json
+    #[arg(short, long, default_value = "table")]
+    pub format: OutputFormat,
+    /// Unit system: metric or imperial, defaults to the configured system
+    #[arg(long)]
+    pub units: Option<UnitSystem>,
+}
+
+pub async fn run(args: StatsArgs, pool: &SqlitePool, config: &Config) -> Result<(), anyhow::Error> {
+    let activities = args.query.activities(pool, config).await?;
+    let stats = group_stats(&activities, &args.group_by);
+    let formatter = unit_formatter(config, &args.units);
+
+    match args.format {
+        OutputFormat::Table => {
+            let rows: Vec<Vec<String>> = stats.iter().map(|s| stats_row(&formatter, s)).collect();
+            print!("{}", render_table(&STATS_HEADERS, &rows));
+        }
+        OutputFormat::Json => {
+            let records: Vec<Value> = stats
+                .iter()
+                .map(|s| {
+                    let mut record = group_json(&formatter, &s.group);
+                    record["previous_year"] = match &s.previous_year {
+                        Some(previous) => group_json(&formatter, previous),
+                        None => Value::Null,
+                    };
+                    record["distance_change_percent"] = json!(s.distance_change().map(round));
+                    record
+                })
+                .collect();
+            serde_json::to_writer_pretty(io::stdout(), &records)?;
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn group_json(formatter: &UnitFormatter, group: &GroupStats) -> Value {
+    let distance_unit = formatter.distance_unit();
+    let elevation_unit = formatter.elevation_unit();
+    let pace = formatter.pace_value(group.moving_time, group.distance);
+    json!({
+        "group": group.key,
+        "count": group.count,
+        format!("distance_{}", distance_unit): round(formatter.distance_value(group.distance)),
+        format!("average_distance_{}", distance_unit): round(formatter.distance_value(group.average_distance())),
+        "moving_time_s": group.moving_time,
+        "average_moving_time_s": group.average_time(),
+        format!("elevation_{}", elevation_unit): round(formatter.elevation_value(group.elevation)),
+        format!("pace_s_per_{}", distance_unit): if pace.is_finite() { Some(round(pace)) } else { None },
+        "heartrate": group.average_heartrate().map(round),
+    })
+}
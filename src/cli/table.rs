@@ -0,0 +1,66 @@
+use std::str::FromStr;
+
+use tui::text::Span;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("unknown format `{}`, expected table or json", s)),
+        }
+    }
+}
+
+/// Render rows as plain text columns padded to the widest cell
+pub fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let width = |text: &str| Span::raw(text).width();
+    let mut widths: Vec<usize> = headers.iter().map(|h| width(h)).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(width(cell));
+        }
+    }
+
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| format!("{}{}", cell, " ".repeat(widths[i] - width(cell))))
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+
+    let mut table = line(headers.to_vec());
+    for row in rows {
+        table.push_str(&line(row.iter().map(|c| c.as_str()).collect()));
+    }
+    table
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pads_columns() {
+        assert_eq!(
+            "Title        Dst\nMorning run  10km\n5k           5km\n",
+            render_table(
+                &["Title", "Dst"],
+                &[
+                    vec!["Morning run".to_string(), "10km".to_string()],
+                    vec!["5k".to_string(), "5km".to_string()],
+                ]
+            )
+        );
+    }
+}
@@ -1,28 +1,46 @@
 #![allow(dead_code)]
 
-use std::fmt::Display;
+use std::{fmt::Display, sync::Mutex};
 
 use chrono::{DateTime, NaiveDateTime, Utc};
-use hyper::{client::HttpConnector, Body, Client, Method, Request, Response};
+use hyper::{client::HttpConnector, Body, Client, HeaderMap, Method, Request, Response};
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::event::logger::Logger;
 
+pub const DEFAULT_API_URL: &str = "https://www.strava.com/api";
+pub const DEFAULT_OAUTH_URL: &str = "https://www.strava.com/oauth";
+
+/// Connector for requests to the URL, plain HTTP is only allowed for local
+/// servers (e.g. a fake Strava API used for testing)
+pub fn connector(url: &str) -> HttpsConnector<HttpConnector> {
+    let builder = HttpsConnectorBuilder::new().with_native_roots();
+    match is_local(url) {
+        true => builder.https_or_http().enable_http1().build(),
+        false => builder.https_only().enable_http1().build(),
+    }
+}
+
+fn is_local(url: &str) -> bool {
+    url::Url::parse(url).is_ok_and(|url| {
+        matches!(
+            url.host_str(),
+            Some("localhost") | Some("127.0.0.1") | Some("[::1]")
+        )
+    })
+}
+
 pub fn new_strava_client(config: StravaConfig, logger: Logger) -> StravaClient {
-    let connector = HttpsConnectorBuilder::new()
-        .with_native_roots()
-        .https_only()
-        .enable_http1()
-        .build();
-    let client = Client::builder().build(connector);
+    let client = Client::builder().build(connector(&config.base_url));
 
     StravaClient {
         config,
         client,
         access_token: None,
         logger,
+        rate_limit: Mutex::new(None),
     }
 }
 
@@ -37,6 +55,37 @@ pub struct StravaClient {
     config: StravaConfig,
     access_token: Option<String>,
     logger: Logger,
+    /// Rate limit reported by the last response
+    rate_limit: Mutex<Option<RateLimit>>,
+}
+
+/// Requests allowed and used in the current 15 minute and daily windows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub limit: (u32, u32),
+    pub usage: (u32, u32),
+}
+
+impl RateLimit {
+    /// Read the `X-RateLimit-Limit` and `X-RateLimit-Usage` headers, e.g. `100,1000` and `12,311`
+    pub fn from_headers(headers: &HeaderMap) -> Option<RateLimit> {
+        let pair = |name: &str| -> Option<(u32, u32)> {
+            let (short, long) = headers.get(name)?.to_str().ok()?.split_once(',')?;
+            Some((short.trim().parse().ok()?, long.trim().parse().ok()?))
+        };
+        Some(RateLimit {
+            limit: pair("X-RateLimit-Limit")?,
+            usage: pair("X-RateLimit-Usage")?,
+        })
+    }
+
+    /// Requests which can be made before either limit is reached
+    pub fn remaining(&self) -> u32 {
+        self.limit
+            .0
+            .saturating_sub(self.usage.0)
+            .min(self.limit.1.saturating_sub(self.usage.1))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -60,8 +109,15 @@ pub struct Activity {
     pub location_state: Option<String>,
     pub location_city: Option<String>,
     pub athlete_count: i64,
+    pub average_watts: Option<f64>,
+    pub weighted_average_watts: Option<f64>,
+    pub kilojoules: Option<f64>,
+    pub gear_id: Option<String>,
+    #[serde(default)]
+    pub device_watts: bool,
     pub splits_metric: Option<Vec<Split>>,
     pub splits_standard: Option<Vec<Split>>,
+    pub laps: Option<Vec<Lap>>,
     pub segment_efforts: Option<Vec<SegmentEffort>>,
 }
 
@@ -107,6 +163,99 @@ pub struct Split {
     pub split: i64,
 }
 
+/// Lap recorded by pressing the lap button or by the auto lap of the device
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Lap {
+    pub lap_index: i64,
+    pub name: String,
+    pub distance: f64,
+    pub moving_time: i64,
+    pub elapsed_time: i64,
+    pub average_speed: f64,
+    pub average_heartrate: Option<f64>,
+    pub max_heartrate: Option<f64>,
+    pub average_cadence: Option<f64>,
+    #[serde(default)]
+    pub total_elevation_gain: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Streams {
+    pub time: Option<Stream<i64>>,
+    pub distance: Option<Stream<f64>>,
+    pub latlng: Option<Stream<(f64, f64)>>,
+    pub altitude: Option<Stream<f64>>,
+    pub velocity_smooth: Option<Stream<f64>>,
+    pub heartrate: Option<Stream<f64>>,
+    pub cadence: Option<Stream<f64>>,
+    pub watts: Option<Stream<Option<f64>>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Stream<T> {
+    pub data: Vec<T>,
+}
+
+/// The authenticated athlete as returned by `/athlete`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Athlete {
+    pub id: i64,
+    pub username: Option<String>,
+    #[serde(default)]
+    pub firstname: String,
+    #[serde(default)]
+    pub lastname: String,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub country: Option<String>,
+    pub weight: Option<f64>,
+    pub ftp: Option<i64>,
+    #[serde(default)]
+    pub bikes: Vec<Gear>,
+    #[serde(default)]
+    pub shoes: Vec<Gear>,
+}
+
+/// A bike or a pair of shoes, the brand and model are only part of the
+/// response of `/gear/{id}`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Gear {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub primary: bool,
+    pub distance: f64,
+    pub brand_name: Option<String>,
+    pub model_name: Option<String>,
+    #[serde(default)]
+    pub retired: bool,
+}
+
+/// Totals of the athlete as returned by `/athletes/{id}/stats`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct AthleteStats {
+    pub recent_run_totals: Totals,
+    pub recent_ride_totals: Totals,
+    pub recent_swim_totals: Totals,
+    pub ytd_run_totals: Totals,
+    pub ytd_ride_totals: Totals,
+    pub ytd_swim_totals: Totals,
+    pub all_run_totals: Totals,
+    pub all_ride_totals: Totals,
+    pub all_swim_totals: Totals,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Totals {
+    pub count: i64,
+    pub distance: f64,
+    pub moving_time: i64,
+    pub elapsed_time: i64,
+    pub elevation_gain: f64,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Map {
     pub summary_polyline: String,
@@ -137,6 +286,9 @@ impl StravaClient {
         self.logger.info(format!(">> {}", url)).await;
 
         let res: Response<Body> = self.client.request(req).await?;
+        if let Some(rate_limit) = RateLimit::from_headers(res.headers()) {
+            *self.rate_limit.lock().unwrap() = Some(rate_limit);
+        }
 
         if res.status() != 200 {
             let message = format!("Got {} response for URL {}", res.status(), &url);
@@ -150,6 +302,11 @@ impl StravaClient {
         Ok(deserialized)
     }
 
+    /// Rate limit as of the last response, `None` if not known yet
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        *self.rate_limit.lock().unwrap()
+    }
+
     pub async fn athlete_activities(
         &self,
         page: u32,
@@ -181,4 +338,72 @@ impl StravaClient {
 
         Ok(activity)
     }
+
+    pub async fn athlete(&self) -> Result<Value, anyhow::Error> {
+        let athlete = self.request(Method::GET, "/v3/athlete".to_string()).await?;
+
+        Ok(athlete)
+    }
+
+    pub async fn athlete_stats(&self, id: i64) -> Result<Value, anyhow::Error> {
+        let stats = self
+            .request(Method::GET, format!("/v3/athletes/{}/stats", id))
+            .await?;
+
+        Ok(stats)
+    }
+
+    pub async fn gear(&self, id: String) -> Result<Value, anyhow::Error> {
+        let gear = self.request(Method::GET, format!("/v3/gear/{}", id)).await?;
+
+        Ok(gear)
+    }
+
+    pub async fn activity_streams(&self, id: String) -> Result<Value, anyhow::Error> {
+        let streams = self
+            .request(
+                Method::GET,
+                format!(
+                    "/v3/activities/{}/streams?keys={}&key_by_type=true",
+                    id, STREAM_KEYS,
+                ),
+            )
+            .await?;
+
+        Ok(streams)
+    }
+}
+
+const STREAM_KEYS: &str = "time,distance,latlng,altitude,velocity_smooth,heartrate,cadence,watts";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn only_local_urls_are_local() {
+        assert!(is_local("http://127.0.0.1:8080/api"));
+        assert!(is_local("http://localhost/api"));
+        assert!(!is_local(DEFAULT_API_URL));
+        assert!(!is_local("http://localhost.example.com/api"));
+        assert!(!is_local("not a url"));
+    }
+
+    #[test]
+    fn rate_limit_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(None, RateLimit::from_headers(&headers));
+
+        headers.insert("X-RateLimit-Limit", "100,1000".parse().unwrap());
+        headers.insert("X-RateLimit-Usage", "12, 995".parse().unwrap());
+        let rate_limit = RateLimit::from_headers(&headers).unwrap();
+        assert_eq!((12, 995), rate_limit.usage);
+        assert_eq!(5, rate_limit.remaining());
+
+        headers.insert("X-RateLimit-Usage", "120,200".parse().unwrap());
+        assert_eq!(0, RateLimit::from_headers(&headers).unwrap().remaining());
+
+        headers.insert("X-RateLimit-Usage", "garbage".parse().unwrap());
+        assert_eq!(None, RateLimit::from_headers(&headers));
+    }
 }
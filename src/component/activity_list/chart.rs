@@ -2,7 +2,7 @@ use chrono::DateTime;
 use tui::{
     layout::Constraint,
     prelude::Buffer,
-    style::{Color, Style},
+    style::Style,
     symbols::Marker,
     text::{Line, Span},
     widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Widget},
@@ -63,19 +63,19 @@ pub fn draw(app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
             .data(&data)
             .marker(Marker::Braille)
             .graph_type(GraphType::Line)
-            .style(Style::default().fg(Color::Blue)),
+            .style(Style::default().fg(app.theme.chart_line)),
         Dataset::default()
             .name("Pace")
             .data(&data)
             .marker(Marker::Braille)
             .graph_type(GraphType::Scatter)
-            .style(Style::default().fg(Color::Magenta)),
+            .style(Style::default().fg(app.theme.chart_points)),
         Dataset::default()
             .data(&current)
             .name("Selected")
             .marker(Marker::Braille)
             .graph_type(GraphType::Line)
-            .style(Style::default().fg(Color::Green)),
+            .style(Style::default().fg(app.theme.chart_marker)),
     ];
     let yaxisstep = (pdiff as f64 / area.height as f64) as usize;
     let yaxis = (*pmin..*pmax).step_by(if yaxisstep > 0 { yaxisstep } else { 1 });
@@ -86,8 +86,8 @@ pub fn draw(app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
         .block(Block::default().borders(Borders::all()))
         .x_axis(
             Axis::default()
-                .title(Span::styled("Date", Style::default().fg(Color::Red)))
-                .style(Style::default().fg(Color::White))
+                .title(Span::styled("Date", Style::default().fg(app.theme.axis_title)))
+                .style(Style::default().fg(app.theme.text))
                 .bounds([*tmin.unwrap() as f64, *tmax.unwrap() as f64])
                 .labels(xaxis.map(|p| {
                     Line::from(match DateTime::from_timestamp_millis(p * 1000) {
@@ -98,8 +98,8 @@ pub fn draw(app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
         )
         .y_axis(
             Axis::default()
-                .title(Span::styled("Pace", Style::default().fg(Color::Red)))
-                .style(Style::default().fg(Color::White))
+                .title(Span::styled("Pace", Style::default().fg(app.theme.axis_title)))
+                .style(Style::default().fg(app.theme.text))
                 .bounds([
                     *pmin as f64,
                     *pmax as f64 + (pdiff as f64 / activities.len() as f64),
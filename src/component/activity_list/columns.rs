@@ -0,0 +1,354 @@
+use std::{collections::BTreeMap, fmt::Display, fs, path::PathBuf, str::FromStr};
+
+use crossterm::event::KeyCode;
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::{app::App, store::activity::Activity};
+
+/// Key of the columns used when no activity type is selected
+pub const DEFAULT_TYPE: &str = "default";
+
+const COLUMN_SPACING: u16 = 1;
+const MIN_TITLE_WIDTH: u16 = 10;
+
+#[derive(EnumIter, Clone, Debug, PartialEq)]
+pub enum ListColumn {
+    Date,
+    Type,
+    Title,
+    Distance,
+    Time,
+    Pace,
+    Speed,
+    HeartRate,
+    MaxHeartRate,
+    Elevation,
+    Cadence,
+    Watts,
+    Kudos,
+    City,
+    Rank,
+    Load,
+}
+
+impl ListColumn {
+    pub fn to_label(&self) -> &'static str {
+        match self {
+            ListColumn::Date => "date",
+            ListColumn::Type => "type",
+            ListColumn::Title => "title",
+            ListColumn::Distance => "distance",
+            ListColumn::Time => "time",
+            ListColumn::Pace => "pace",
+            ListColumn::Speed => "speed",
+            ListColumn::HeartRate => "heartrate",
+            ListColumn::MaxHeartRate => "max_heartrate",
+            ListColumn::Elevation => "elevation",
+            ListColumn::Cadence => "cadence",
+            ListColumn::Watts => "watts",
+            ListColumn::Kudos => "kudos",
+            ListColumn::City => "city",
+            ListColumn::Rank => "rank",
+            ListColumn::Load => "load",
+        }
+    }
+
+    pub fn to_key(&self) -> char {
+        match self {
+            ListColumn::Date => 'd',
+            ListColumn::Type => 'y',
+            ListColumn::Title => 't',
+            ListColumn::Distance => 'i',
+            ListColumn::Time => 'm',
+            ListColumn::Pace => 'p',
+            ListColumn::Speed => 's',
+            ListColumn::HeartRate => 'h',
+            ListColumn::MaxHeartRate => 'x',
+            ListColumn::Elevation => 'e',
+            ListColumn::Cadence => 'c',
+            ListColumn::Watts => 'w',
+            ListColumn::Kudos => 'k',
+            ListColumn::City => 'a',
+            ListColumn::Rank => 'r',
+            ListColumn::Load => 'l',
+        }
+    }
+
+    pub fn from_key(key: KeyCode) -> Option<ListColumn> {
+        ListColumn::iter().find(|c| KeyCode::Char(c.to_key()) == key)
+    }
+
+    pub fn defaults() -> Vec<ListColumn> {
+        vec![
+            ListColumn::Date,
+            ListColumn::Type,
+            ListColumn::Title,
+            ListColumn::Distance,
+            ListColumn::Time,
+            ListColumn::Pace,
+            ListColumn::Speed,
+            ListColumn::HeartRate,
+            ListColumn::Elevation,
+            ListColumn::Rank,
+        ]
+    }
+
+    pub fn header(&self, use_moving_time: bool) -> &'static str {
+        match self {
+            ListColumn::Date => "Date",
+            ListColumn::Type => "",
+            ListColumn::Title => "Title",
+            ListColumn::Distance => "Dst",
+            ListColumn::Time => match use_moving_time {
+                true => "🕑 Moving",
+                false => "🕑 Elapsed",
+            },
+            ListColumn::Pace => "👣 Pace",
+            ListColumn::Speed => "󰓅  Speed",
+            ListColumn::HeartRate => "💓 Avg. Heart",
+            ListColumn::MaxHeartRate => "💓 Max Heart",
+            ListColumn::Elevation => "🌄 Elevation",
+            ListColumn::Cadence => "Cadence",
+            ListColumn::Watts => "Power",
+            ListColumn::Kudos => "Kudos",
+            ListColumn::City => "City",
+            ListColumn::Rank => "🪜 Rank",
+            ListColumn::Load => "Load",
+        }
+    }
+
+    pub fn value(&self, app: &App, activity: &Activity) -> String {
+        let time = match app.activity_list.use_moving_time {
+            true => activity.moving_time,
+            false => activity.elapsed_time,
+        };
+        let number = |v: Option<f64>, precision: usize| {
+            v.map_or_else(|| "n/a".to_string(), |v| app.unit_formatter.number(v, precision))
+        };
+        match self {
+            ListColumn::Date => match activity.start_date {
+                Some(x) => x.format("%Y-%m-%d").to_string(),
+                None => "".to_string(),
+            },
+            ListColumn::Type => activity.activity_type_icon(),
+            ListColumn::Title => activity.title.clone(),
+            ListColumn::Distance => app.unit_formatter.distance(activity.distance),
+            ListColumn::Time => app.unit_formatter.stopwatch_time(time),
+            ListColumn::Pace => app.unit_formatter.pace(time, activity.distance),
+            ListColumn::Speed => app.unit_formatter.speed(activity.meters_per_hour()),
+            ListColumn::HeartRate => number(activity.average_heartrate, 2),
+            ListColumn::MaxHeartRate => number(activity.max_heartrate, 0),
+            ListColumn::Elevation => app.unit_formatter.elevation(activity.total_elevation_gain),
+            ListColumn::Cadence => number(activity.average_cadence, 0),
+            ListColumn::Watts => number(activity.average_watts, 0),
+            ListColumn::Kudos => activity.kudos.to_string(),
+            ListColumn::City => activity.location_city.clone().unwrap_or_default(),
+            ListColumn::Rank => format!("{}", activity.rank),
+            ListColumn::Load => number(
+                app.ftp.and_then(|ftp| activity.training_stress_score(ftp)),
+                0,
+            ),
+        }
+    }
+}
+
+impl Display for ListColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_label())
+    }
+}
+
+impl FromStr for ListColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ListColumn::iter().find(|c| c.to_label() == s).ok_or_else(|| {
+            format!(
+                "unknown column `{}`, expected one of: {}",
+                s,
+                ListColumn::iter().map(|c| c.to_label().to_string()).collect::<Vec<String>>().join(", ")
+            )
+        })
+    }
+}
+
+/// Columns of the activity list for each activity type
+#[derive(Debug, Clone, Default)]
+pub struct ListColumns {
+    by_type: BTreeMap<String, Vec<ListColumn>>,
+    /// File the columns chosen at runtime are saved to
+    state_path: Option<PathBuf>,
+}
+
+impl ListColumns {
+    /// Columns from the config file, overridden by those chosen in previous sessions
+    pub fn load(config: BTreeMap<String, Vec<ListColumn>>, state_path: Option<PathBuf>) -> ListColumns {
+        let mut by_type = config;
+        if let Some(path) = &state_path {
+            let saved = fs::read_to_string(path)
+                .ok()
+                .and_then(|json| serde_json::from_str::<BTreeMap<String, Vec<String>>>(&json).ok())
+                .unwrap_or_default();
+            for (activity_type, names) in saved {
+                if let Ok(columns) = names.iter().map(|n| n.parse()).collect() {
+                    by_type.insert(activity_type, columns);
+                }
+            }
+        }
+        ListColumns { by_type, state_path }
+    }
+
+    pub fn parse_config(config: &BTreeMap<String, Vec<String>>) -> Result<BTreeMap<String, Vec<ListColumn>>, String> {
+        config
+            .iter()
+            .map(|(activity_type, names)| {
+                names
+                    .iter()
+                    .map(|n| n.parse())
+                    .collect::<Result<Vec<ListColumn>, String>>()
+                    .map(|columns| (activity_type.clone(), columns))
+            })
+            .collect()
+    }
+
+    pub fn get(&self, activity_type: Option<&str>) -> Vec<ListColumn> {
+        activity_type
+            .and_then(|t| self.by_type.get(t))
+            .or_else(|| self.by_type.get(DEFAULT_TYPE))
+            .cloned()
+            .unwrap_or_else(ListColumn::defaults)
+    }
+
+    /// Show or hide the column for the activity type, a shown column is
+    /// placed at its default position relative to the visible columns
+    pub fn toggle(&mut self, activity_type: Option<&str>, column: ListColumn) {
+        let mut columns = self.get(activity_type);
+        match columns.iter().position(|c| *c == column) {
+            Some(index) => {
+                columns.remove(index);
+            }
+            None => {
+                let order = |c: &ListColumn| ListColumn::iter().position(|o| o == *c);
+                let index = columns
+                    .iter()
+                    .position(|c| order(c) > order(&column))
+                    .unwrap_or(columns.len());
+                columns.insert(index, column);
+            }
+        }
+        self.by_type
+            .insert(activity_type.unwrap_or(DEFAULT_TYPE).to_string(), columns);
+    }
+
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        let path = match &self.state_path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let labels: BTreeMap<&String, Vec<&str>> = self
+            .by_type
+            .iter()
+            .map(|(t, columns)| (t, columns.iter().map(|c| c.to_label()).collect()))
+            .collect();
+        fs::write(path, serde_json::to_string_pretty(&labels)?)?;
+        Ok(())
+    }
+}
+
+/// Widths of the columns which fit in the available width given the width of
+/// their widest value, columns are dropped from the right when there is not
+/// enough room and the title takes up any remaining space.
+pub fn fit_columns(columns: &[ListColumn], widths: &[u16], available: u16) -> Vec<(ListColumn, u16)> {
+    let mut fitted: Vec<(ListColumn, u16)> = columns
+        .iter()
+        .cloned()
+        .zip(widths.iter().map(|w| *w.max(&1)))
+        .map(|(c, w)| match c {
+            ListColumn::Title => (c, w.min(MIN_TITLE_WIDTH)),
+            _ => (c, w),
+        })
+        .collect();
+
+    let total = |fitted: &[(ListColumn, u16)]| -> u16 {
+        fitted.iter().map(|(_, w)| *w).sum::<u16>()
+            + COLUMN_SPACING * (fitted.len() as u16).saturating_sub(1)
+    };
+    while total(&fitted) > available {
+        match fitted.iter().rposition(|(c, _)| *c != ListColumn::Title) {
+            Some(index) if fitted.len() > 1 => {
+                fitted.remove(index);
+            }
+            _ => break,
+        }
+    }
+
+    let remaining = available.saturating_sub(total(&fitted));
+    if let Some((_, width)) = fitted.iter_mut().find(|(c, _)| *c == ListColumn::Title) {
+        let widest = columns
+            .iter()
+            .position(|c| *c == ListColumn::Title)
+            .map_or(*width, |i| widths[i]);
+        *width += remaining.min(widest.saturating_sub(*width));
+    }
+    fitted
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn toggle_keeps_default_order() {
+        let mut columns = ListColumns::default();
+        columns.toggle(Some("Ride"), ListColumn::Date);
+        columns.toggle(Some("Ride"), ListColumn::Watts);
+        assert_eq!(
+            vec![
+                ListColumn::Type,
+                ListColumn::Title,
+                ListColumn::Distance,
+                ListColumn::Time,
+                ListColumn::Pace,
+                ListColumn::Speed,
+                ListColumn::HeartRate,
+                ListColumn::Elevation,
+                ListColumn::Watts,
+                ListColumn::Rank,
+            ],
+            columns.get(Some("Ride"))
+        );
+
+        columns.toggle(Some("Ride"), ListColumn::Date);
+        assert_eq!(ListColumn::Date, columns.get(Some("Ride"))[0]);
+        assert_eq!(ListColumn::defaults(), columns.get(Some("Run")));
+    }
+
+    #[test]
+    fn fit_columns_to_width() {
+        let columns = vec![ListColumn::Date, ListColumn::Title, ListColumn::Distance, ListColumn::Rank];
+        let widths = [10, 30, 8, 4];
+
+        assert_eq!(
+            vec![
+                (ListColumn::Date, 10),
+                (ListColumn::Title, 30),
+                (ListColumn::Distance, 8),
+                (ListColumn::Rank, 4)
+            ],
+            fit_columns(&columns, &widths, 100)
+        );
+        assert_eq!(
+            vec![
+                (ListColumn::Date, 10),
+                (ListColumn::Title, 15),
+                (ListColumn::Distance, 8),
+                (ListColumn::Rank, 4)
+            ],
+            fit_columns(&columns, &widths, 40)
+        );
+        assert_eq!(
+            vec![(ListColumn::Date, 10), (ListColumn::Title, 10), (ListColumn::Distance, 8)],
+            fit_columns(&columns, &widths, 30)
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use strum::IntoEnumIterator;
+use tui::{
+    prelude::Buffer,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::{
+    app::{App, Notification},
+    event::keymap::{MappedKey, StravaEvent},
+    ui::centered_rect_absolute,
+};
+
+use super::columns::ListColumn;
+
+pub fn handle(app: &mut App, key: MappedKey) {
+    let matched = match key.strava_event {
+        StravaEvent::Enter => {
+            app.activity_list.columns_dialog = false;
+            true
+        }
+        StravaEvent::Escape => {
+            app.activity_list.columns_dialog = false;
+            true
+        }
+        _ => false,
+    };
+
+    if matched {
+        return;
+    }
+
+    if let Some(column) = ListColumn::from_key(key.key_event.code) {
+        app.list_columns.toggle(app.activity_type.as_deref(), column);
+        if let Err(e) = app.list_columns.save() {
+            app.error_message = Some(Notification::new(format!("could not save columns: {}", e)));
+        }
+    }
+}
+
+pub fn draw(app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
+    let rect = centered_rect_absolute(64, 5, area);
+    Clear.render(rect, f);
+
+    let strava = app.theme.accent;
+    let visible = app.list_columns.get(app.activity_type.as_deref());
+    let mut options = vec![];
+    for column in ListColumn::iter() {
+        options.push(Span::styled(format!("[{}]", column.to_key()), Style::default().fg(strava)));
+        options.push(Span::styled(
+            format!("{} ", column.to_label()),
+            Style::default().fg(match visible.contains(&column) {
+                true => app.theme.text,
+                false => app.theme.dim,
+            }),
+        ));
+    }
+    options.push(Span::styled("<Enter> ".to_string(), Style::default().fg(strava)));
+    options.push(Span::styled("close ".to_string(), Style::default().fg(app.theme.text)));
+
+    Paragraph::new(vec![Line::from(options)])
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(format!(
+                    "Columns ({})",
+                    app.activity_type.as_deref().unwrap_or("all activities")
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.dialog))
+                .style(Style::default()),
+        )
+        .render(rect, f);
+}
@@ -2,7 +2,7 @@ use crossterm::event::KeyCode;
 use strum::IntoEnumIterator;
 use tui::{
     backend::Backend,
-    style::{Color, Style},
+    style::Style,
     text::{Span, Line},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
@@ -11,7 +11,7 @@ use tui::{
 use crate::{
     app::{App, SortBy},
     event::keymap::{MappedKey, StravaEvent},
-    ui::{centered_rect_absolute, color::ColorTheme},
+    ui::centered_rect_absolute,
 };
 
 impl SortBy {
@@ -76,15 +76,15 @@ pub fn draw<B: Backend>(
     let block = Block::default()
         .title("Sort".to_string())
         .borders(Borders::ALL)
-        .style(Style::default().fg(ColorTheme::Dialog.to_color()));
+        .style(Style::default().fg(app.theme.dialog));
 
     f.render_widget(block, rect);
     f.render_widget(sort_option_paragraph(app), rect);
 
     Ok(())
 }
-fn sort_option_paragraph<'a>(_app: &'a mut App) -> Paragraph<'a> {
-    let strava = ColorTheme::Orange.to_color();
+fn sort_option_paragraph<'a>(app: &'a mut App) -> Paragraph<'a> {
+    let strava = app.theme.accent;
     let mut sorts = vec![];
 
     for sort_by in SortBy::iter() {
@@ -94,7 +94,7 @@ fn sort_option_paragraph<'a>(_app: &'a mut App) -> Paragraph<'a> {
         ));
         sorts.push(Span::styled(
             format!("{} ", sort_by.to_label().to_string()),
-            Style::default().fg(Color::White),
+            Style::default().fg(app.theme.text),
         ));
     }
 
@@ -104,7 +104,7 @@ fn sort_option_paragraph<'a>(_app: &'a mut App) -> Paragraph<'a> {
     ));
     sorts.push(Span::styled(
         "cancel ".to_string(),
-        Style::default().fg(Color::White),
+        Style::default().fg(app.theme.text),
     ));
 
     let text: Vec<Line> = vec![Line::from(sorts)];
@@ -0,0 +1,111 @@
+use crossterm::event::KeyCode;
+use log::info;
+use tui::{
+    prelude::Buffer,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    app::{App, Notification},
+    event::keymap::{MappedKey, StravaEvent},
+    export::activities::{export_activities, ExportFormat},
+    ui::centered_rect_absolute,
+};
+
+const FORMATS: [(char, ExportFormat); 3] = [
+    ('c', ExportFormat::Csv),
+    ('j', ExportFormat::Json),
+    ('n', ExportFormat::Ndjson),
+];
+
+pub fn handle(app: &mut App, key: MappedKey) {
+    let matched = match key.strava_event {
+        StravaEvent::Enter => {
+            app.activity_list.export_dialog = false;
+            true
+        }
+        StravaEvent::Escape => {
+            app.activity_list.export_dialog = false;
+            true
+        }
+        _ => false,
+    };
+
+    if matched {
+        return;
+    }
+
+    let format = FORMATS.iter().find_map(|(c, format)| match key.key_event.code {
+        KeyCode::Char(k) if k == *c => Some(format.clone()),
+        _ => None,
+    });
+
+    if let Some(format) = format {
+        app.activity_list.export_dialog = false;
+        export(app, format);
+    }
+}
+
+fn export(app: &mut App, format: ExportFormat) {
+    let name = format!(
+        "strava-activities-{}.{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        format.extension()
+    );
+    let activities = app.activities();
+    let result = app.create_export_file(&name).and_then(|(mut file, path)| {
+        export_activities(
+            &mut file,
+            &activities,
+            &app.export_columns,
+            &format,
+            &app.unit_formatter,
+        )?;
+        Ok(path)
+    });
+    match result {
+        Ok(path) => {
+            info!("Exported {} activities to {}", activities.len(), path.display());
+            app.info_message = Some(Notification::new(format!(
+                "exported {} activities to {}",
+                activities.len(),
+                path.display()
+            )))
+        }
+        Err(e) => {
+            app.error_message = Some(Notification::new(format!(
+                "could not export activities: {}",
+                e
+            )))
+        }
+    }
+}
+
+pub fn draw(app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
+    let rect = centered_rect_absolute(64, 3, area);
+    Clear.render(rect, f);
+
+    let strava = app.theme.accent;
+    let mut options = vec![];
+    for (key, format) in FORMATS.iter() {
+        options.push(Span::styled(format!("[{}]", key), Style::default().fg(strava)));
+        options.push(Span::styled(
+            format!("{} ", format.extension()),
+            Style::default().fg(app.theme.text),
+        ));
+    }
+    options.push(Span::styled("<Enter> ".to_string(), Style::default().fg(strava)));
+    options.push(Span::styled("cancel ".to_string(), Style::default().fg(app.theme.text)));
+
+    Paragraph::new(vec![Line::from(options)])
+        .block(
+            Block::default()
+                .title("Export")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.dialog))
+                .style(Style::default()),
+        )
+        .render(rect, f);
+}
@@ -1,85 +1,63 @@
 use tui::{
     layout::Constraint,
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::Span,
     widgets::{Cell, Row, Table},
 };
 
 use crate::{app::App, store::activity::Activities};
 
-pub fn activity_list_table<'a>(app: &App, activities: &'a Activities) -> Table<'a> {
-    let mut rows = vec![];
-    let time_header = match app.activity_list.use_moving_time {
-        true => "🕑 Moving",
-        false => "🕑 Elapsed",
-    };
-    let header_names = [
-        "Date",
-        "",
-        "Title",
-        "Dst",
-        time_header,
-        "👣 Pace",
-        "󰓅  Speed",
-        "💓 Avg. Heart",
-        "🌄 Elevation",
-        "🪜 Rank",
-    ];
-    let headers = header_names
+use super::columns::fit_columns;
+
+/// Table of the activities with the columns chosen for the current activity
+/// type which fit in the given width
+pub fn activity_list_table<'a>(app: &App, activities: &'a Activities, width: u16) -> Table<'a> {
+    let columns = app.list_columns.get(app.activity_type.as_deref());
+    let headers: Vec<&str> = columns
+        .iter()
+        .map(|c| c.header(app.activity_list.use_moving_time))
+        .collect();
+    let values: Vec<Vec<String>> = activities
+        .to_vec()
+        .iter()
+        .map(|activity| columns.iter().map(|c| c.value(app, activity)).collect())
+        .collect();
+
+    let widths: Vec<u16> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            values
+                .iter()
+                .map(|row| Span::raw(row[i].as_str()).width())
+                .chain([Span::raw(*header).width()])
+                .max()
+                .unwrap_or(0) as u16
+        })
+        .collect();
+    let fitted = fit_columns(&columns, &widths, width);
+    let indexes: Vec<usize> = fitted
         .iter()
-        .map(|header| Cell::from(Span::styled(*header, Style::default().fg(Color::DarkGray))));
+        .filter_map(|(column, _)| columns.iter().position(|c| c == column))
+        .collect();
 
-    for activity in activities.to_vec() {
-        rows.push(Row::new([
-            Cell::from(match activity.start_date {
-                Some(x) => x.format("%Y-%m-%d").to_string(),
-                None => "".to_string(),
-            }),
-            Cell::from(activity.activity_type_icon()),
-            Cell::from(activity.title.clone()),
-            Cell::from(app.unit_formatter.distance(activity.distance)),
-            Cell::from(app.unit_formatter.stopwatch_time(
-                match app.activity_list.use_moving_time {
-                    true => activity.moving_time,
-                    false => activity.elapsed_time,
-                },
-            )),
-            Cell::from(match app.activity_list.use_moving_time {
-                true => app
-                    .unit_formatter
-                    .pace(activity.moving_time, activity.distance),
-                false => app
-                    .unit_formatter
-                    .pace(activity.elapsed_time, activity.distance),
-            }),
-            Cell::from(app.unit_formatter.speed(activity.meters_per_hour())),
-            Cell::from(
-                activity
-                    .average_heartrate
-                    .map_or_else(|| "n/a".to_string(), |v| format!("{:.2}", v)),
-            ),
-            Cell::from(app.unit_formatter.elevation(activity.total_elevation_gain)),
-            Cell::from(format!("{}", activity.rank)),
-        ]));
-    }
+    let header_cells = indexes
+        .iter()
+        .map(|i| Cell::from(Span::styled(headers[*i], Style::default().fg(app.theme.dim))));
+    let rows: Vec<Row> = values
+        .into_iter()
+        .map(|row| Row::new(indexes.iter().map(|i| Cell::from(row[*i].clone())).collect::<Vec<Cell>>()))
+        .collect();
 
     Table::new(
         rows,
-        &[
-            Constraint::Length(10),
-            Constraint::Length(2),
-            Constraint::Percentage(20),
-            Constraint::Min(8),
-            Constraint::Min(8),
-            Constraint::Min(8),
-            Constraint::Min(8),
-            Constraint::Min(8),
-            Constraint::Min(8),
-            Constraint::Min(8),
-        ],
+        fitted
+            .iter()
+            .map(|(_, width)| Constraint::Length(*width))
+            .collect::<Vec<Constraint>>(),
     )
     .header(
-        Row::new(headers)
+        Row::new(header_cells)
             .height(1)
             .bottom_margin(1)
             .style(Style::default()),
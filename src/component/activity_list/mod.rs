@@ -1,11 +1,14 @@
 pub mod chart;
+pub mod columns;
+pub mod columns_dialog;
+pub mod export_dialog;
 pub mod list;
 pub mod rank_dialog;
 pub mod sort_dialog;
 
 use crossterm::event::Event;
 use tui::{
-    layout::{Constraint, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     prelude::Buffer,
     style::Style,
     widgets::{Block, Borders, Clear, Paragraph, StatefulWidget, Table, TableState, Widget, block::Title},
@@ -18,14 +21,15 @@ use crate::{
     event::{
         input::InputEvent,
         keymap::{MappedKey, StravaEvent},
+        util::table_state_click,
     },
-    store::activity::SortOrder,
-    ui::{centered_rect_absolute, color::ColorTheme},
+    store::activity::{Sections, SortOrder},
+    ui::centered_rect_absolute,
 };
 
 use self::list::activity_list_table;
 
-use super::{table_status_select_current, View};
+use super::{goals, polyline::MapViewport, table_status_select_current, View};
 
 pub struct ActivityList {
     cursor_pos: Option<(u16,u16)>
@@ -43,6 +47,9 @@ impl View for ActivityList {
         self.cursor_pos
     }
     fn handle(&mut self, app: &mut App, key: MappedKey) {
+        if key.mouse.is_some() && dialog_open(app) {
+            return;
+        }
         if app.activity_list.filter_dialog {
             let matched = match key.strava_event {
                 StravaEvent::Enter => {
@@ -74,6 +81,16 @@ impl View for ActivityList {
 
             return;
         }
+        if app.activity_list.export_dialog {
+            export_dialog::handle(app, key);
+
+            return;
+        }
+        if app.activity_list.columns_dialog {
+            columns_dialog::handle(app, key);
+
+            return;
+        }
         match key.strava_event {
             StravaEvent::Quit => app.quit = true,
             StravaEvent::ToggleUnitSystem => {
@@ -93,9 +110,18 @@ impl View for ActivityList {
             StravaEvent::Filter => toggle_filter(app),
             StravaEvent::Sort => toggle_sort(app),
             StravaEvent::Rank => toggle_rank(app),
+            StravaEvent::Export => toggle_export(app),
+            StravaEvent::Columns => toggle_columns(app),
             StravaEvent::MovingElapsed => toggle_moving_elapsed(app),
             StravaEvent::Enter => table_status_select_current(app),
+            StravaEvent::Click => {
+                select_clicked(app, &key);
+            }
+            StravaEvent::DoubleClick if select_clicked(app, &key) => table_status_select_current(app),
+            StravaEvent::ScrollUp => app.page_up(),
+            StravaEvent::ScrollDown => app.page_down(),
             StravaEvent::Refresh => app.send(InputEvent::Sync),
+            StravaEvent::Reconcile => app.send(InputEvent::Reconcile),
             StravaEvent::IncreaseTolerance => {
                 app.filters.anchor_tolerance_add(0.01);
                 app.send(InputEvent::Reload)
@@ -111,6 +137,29 @@ impl View for ActivityList {
             StravaEvent::ToggleLogView => {
                 app.switch_to(ActivePage::LogView);
             }
+            StravaEvent::TogglePowerCurveView => {
+                app.switch_to(ActivePage::PowerCurve);
+            }
+            StravaEvent::ToggleSegmentsView => {
+                app.switch_to(ActivePage::Segments);
+            }
+            StravaEvent::ToggleStatsView => {
+                app.switch_to(ActivePage::Stats);
+            }
+            StravaEvent::ToggleGearView => {
+                app.switch_to(ActivePage::Gear);
+            }
+            StravaEvent::Mark => {
+                if let Some(a) = app.selected_activity() {
+                    app.toggle_marked(a);
+                }
+            }
+            StravaEvent::Compare => {
+                if let Some(a) = app.selected_activity() {
+                    app.activity = Some(a);
+                    app.compare_to_marked();
+                }
+            }
             _ => (),
         }
     }
@@ -119,14 +168,30 @@ impl View for ActivityList {
         let mut events = vec![
             StravaEvent::Down,
             StravaEvent::Up,
+            StravaEvent::PageDown,
+            StravaEvent::PageUp,
+            StravaEvent::Enter,
             StravaEvent::ToggleUnitSystem,
+            StravaEvent::ToggleSortOrder,
             StravaEvent::Filter,
             StravaEvent::Sort,
             StravaEvent::Rank,
             StravaEvent::Refresh,
+            StravaEvent::Reconcile,
             StravaEvent::MovingElapsed,
+            StravaEvent::TogglePowerCurveView,
+            StravaEvent::ToggleSegmentsView,
+            StravaEvent::ToggleStatsView,
+            StravaEvent::ToggleGearView,
+            StravaEvent::Export,
+            StravaEvent::Columns,
+            StravaEvent::ToggleLogView,
         ];
         events.push(StravaEvent::Anchor);
+        events.push(StravaEvent::Mark);
+        if app.activity_marked.is_some() {
+            events.push(StravaEvent::Compare);
+        }
         if app.activity_anchored.is_some() {
             events.push(StravaEvent::IncreaseTolerance);
             events.push(StravaEvent::DecreaseTolerance);
@@ -139,7 +204,17 @@ impl View for ActivityList {
         let rows = Layout::default()
             .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
             .split(area);
-        chart::draw(app, f, rows[1]);
+        match app.goal_progress.is_empty() {
+            true => chart::draw(app, f, rows[1]),
+            false => {
+                let cols = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(vec![Constraint::Percentage(70), Constraint::Percentage(30)])
+                    .split(rows[1]);
+                chart::draw(app, f, cols[0]);
+                goals::draw(app, f, cols[1]);
+            }
+        }
 
         let activities = &app.activities();
 
@@ -147,7 +222,8 @@ impl View for ActivityList {
             app.activity_list.table_state().select(Some(0));
         }
 
-        let table = activity_list_table(app, activities);
+        app.activity_list.table_area = rows[0];
+        let table = activity_list_table(app, activities, rows[0].width);
         <Table as StatefulWidget>::render(table, rows[0], f, app.activity_list.table_state());
 
         if app.activity_list.filter_dialog {
@@ -159,7 +235,7 @@ impl View for ActivityList {
                     .borders(Borders::ALL)
                     .title(Title::from("Filter"))
                     .title(Title::from("e.g. type=\"Run\" and distance > 21000 and title ~ 'Marathon'").position(tui::widgets::block::Position::Bottom))
-                    .border_style(Style::default().fg(ColorTheme::Dialog.to_color())),
+                    .border_style(Style::default().fg(app.theme.dialog)),
             );
 
             self.cursor_pos = Some((
@@ -181,6 +257,12 @@ impl View for ActivityList {
         if app.activity_list.rank_dialog {
             rank_dialog::draw(app, f, area);
         }
+        if app.activity_list.export_dialog {
+            export_dialog::draw(app, f, area);
+        }
+        if app.activity_list.columns_dialog {
+            columns_dialog::draw(app, f, area);
+        }
     }
 }
 
@@ -197,18 +279,38 @@ pub struct ActivityListState {
     pub filter_dialog: bool,
     pub sort_dialog: bool,
     pub rank_dialog: bool,
+    pub export_dialog: bool,
+    pub columns_dialog: bool,
     pub use_moving_time: bool,
+    pub table_area: Rect,
 }
 
 pub struct ActivityViewState {
     pub segment_efforts_state: TableState,
     pub pace_table_state: TableState,
+    /// Selected split, lap or rep
     pub selected_split: Option<i64>,
+    pub sections: Sections,
+    pub export_dialog: bool,
+    pub map_viewport: MapViewport,
+    pub map_area: Rect,
+    pub splits_area: Rect,
+    pub segment_efforts_area: Rect,
 }
 impl ActivityViewState {
     pub(crate) fn select_split(&mut self, selected: i64) {
         self.selected_split = Some(selected);
     }
+
+    /// Show the given sections, or the splits if they are already shown
+    pub(crate) fn toggle_sections(&mut self, sections: Sections) {
+        self.sections = match self.sections == sections {
+            true => Sections::Splits,
+            false => sections,
+        };
+        self.pace_table_state.select(None);
+        self.selected_split = None;
+    }
 }
 
 impl ActivityListState {
@@ -229,6 +331,33 @@ fn toggle_sort(app: &mut App) {
 fn toggle_rank(app: &mut App) {
     app.activity_list.rank_dialog = !app.activity_list.rank_dialog;
 }
+fn toggle_export(app: &mut App) {
+    app.activity_list.export_dialog = !app.activity_list.export_dialog;
+}
+fn dialog_open(app: &App) -> bool {
+    let state = &app.activity_list;
+    state.filter_dialog || state.sort_dialog || state.rank_dialog || state.export_dialog || state.columns_dialog
+}
+
+/// Select the clicked activity, returns false if no activity was clicked
+fn select_clicked(app: &mut App, key: &MappedKey) -> bool {
+    let len = app.activities.len();
+    let area = app.activity_list.table_area;
+    let clicked = key
+        .mouse
+        .and_then(|mouse| table_state_click(app.activity_list.table_state(), area, 2, &mouse, len));
+    match clicked {
+        Some(row) => {
+            app.activity = app.activities.get(row).cloned();
+            true
+        }
+        None => false,
+    }
+}
+
+fn toggle_columns(app: &mut App) {
+    app.activity_list.columns_dialog = !app.activity_list.columns_dialog;
+}
 pub fn toggle_moving_elapsed(app: &mut App) {
     app.activity_list.use_moving_time = !app.activity_list.use_moving_time;
 }
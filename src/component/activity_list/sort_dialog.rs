@@ -1,7 +1,7 @@
 
 use strum::IntoEnumIterator;
 use tui::{
-    style::{Color, Style},
+    style::Style,
     text::{Span, Line},
     widgets::{Block, Borders, Clear, Paragraph, Widget}, prelude::Buffer,
 };
@@ -9,7 +9,7 @@ use tui::{
 use crate::{
     app::App,
     event::{keymap::{MappedKey, StravaEvent}, input::InputEvent},
-    ui::{centered_rect_absolute, color::ColorTheme}, store::activity::SortBy,
+    ui::centered_rect_absolute, store::activity::SortBy,
 };
 
 pub fn handle(app: &mut App, key: MappedKey) {
@@ -45,8 +45,8 @@ pub fn draw(
     Clear.render(rect, f);
     sort_option_paragraph(app, "Sort".to_string()).render(rect, f);
 }
-pub fn sort_option_paragraph<'a>(_app: &'a mut App, title: String) -> Paragraph<'a> {
-    let strava = ColorTheme::Orange.to_color();
+pub fn sort_option_paragraph<'a>(app: &'a mut App, title: String) -> Paragraph<'a> {
+    let strava = app.theme.accent;
     let mut sorts = vec![];
 
     for sort_by in SortBy::iter() {
@@ -56,7 +56,7 @@ pub fn sort_option_paragraph<'a>(_app: &'a mut App, title: String) -> Paragraph<
         ));
         sorts.push(Span::styled(
             format!("{} ", sort_by.to_label()),
-            Style::default().fg(Color::White),
+            Style::default().fg(app.theme.text),
         ));
     }
 
@@ -66,7 +66,7 @@ pub fn sort_option_paragraph<'a>(_app: &'a mut App, title: String) -> Paragraph<
     ));
     sorts.push(Span::styled(
         "cancel ".to_string(),
-        Style::default().fg(Color::White),
+        Style::default().fg(app.theme.text),
     ));
 
     let text: Vec<Line> = vec![Line::from(sorts)];
@@ -75,7 +75,7 @@ pub fn sort_option_paragraph<'a>(_app: &'a mut App, title: String) -> Paragraph<
         Block::default()
             .title(title)
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(ColorTheme::Dialog.to_color()))
+            .border_style(Style::default().fg(app.theme.dialog))
             .style(Style::default()),
     )
 }
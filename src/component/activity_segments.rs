@@ -3,7 +3,7 @@ use tui::{
     widgets::{Block, Borders, Widget},
 };
 
-use crate::{app::{ActivePage, App}, event::{keymap::StravaEvent, util::{table_state_next, table_state_prev}}, store::activity::Activities};
+use crate::{app::{ActivePage, App}, event::{keymap::StravaEvent, util::{table_state_click, table_state_next, table_state_prev}}, store::activity::Activities};
 
 use super::{activity_list::{list::activity_list_table, toggle_moving_elapsed}, segments, View};
 
@@ -34,12 +34,32 @@ impl View for ActivitySegments {
                 app.previous_activity();
             }
             StravaEvent::MovingElapsed => toggle_moving_elapsed(app),
-            StravaEvent::Next => {
+            StravaEvent::Next | StravaEvent::ScrollDown => {
                 table_state_next(&mut app.activity_view_state.segment_efforts_state, nb_segments, true);
             }
-            StravaEvent::Previous => {
+            StravaEvent::Previous | StravaEvent::ScrollUp => {
                 table_state_prev(&mut app.activity_view_state.segment_efforts_state, nb_segments, true);
             }
+            StravaEvent::Click | StravaEvent::DoubleClick => {
+                let state = &mut app.activity_view_state;
+                let clicked = key.mouse.and_then(|mouse| {
+                    table_state_click(&mut state.segment_efforts_state, state.segment_efforts_area, 2, &mouse, nb_segments)
+                });
+                if clicked.is_some() && key.strava_event == StravaEvent::DoubleClick {
+                    self.handle(app, key.with_event(StravaEvent::Enter));
+                }
+            }
+            StravaEvent::Enter => {
+                let selected = app.activity_view_state.segment_efforts_state.selected();
+                let effort = match (&app.activity, selected) {
+                    (Some(a), Some(i)) => a.segment_efforts.get(i).cloned(),
+                    _ => None,
+                };
+                if let Some(effort) = effort {
+                    app.drill_into_segment(effort.segment_id, Some(ActivePage::ActivitySegments));
+                    app.switch_to(ActivePage::Segments);
+                }
+            }
             _ => (),
         }
     }
@@ -50,6 +70,9 @@ impl View for ActivitySegments {
             StravaEvent::Down,
             StravaEvent::Next,
             StravaEvent::Previous,
+            StravaEvent::Enter,
+            StravaEvent::ToggleUnitSystem,
+            StravaEvent::MovingElapsed,
             StravaEvent::ToggleSegmentEffortsView,
             StravaEvent::Quit,
         ]
@@ -70,17 +93,14 @@ impl View for ActivitySegments {
         if let Some(activity) = &app.activity {
             {
                 let a = Activities::from(activity.clone());
-                activity_list_table(app, &a).render(rows[0], f);
+                activity_list_table(app, &a, rows[0].width).render(rows[0], f);
             }
         }
 
-        segments::draw(
-            app,
-            f,
-            rows[1].inner(Margin {
-                vertical: 2,
-                horizontal: 2,
-            }),
-        );
+        app.activity_view_state.segment_efforts_area = rows[1].inner(Margin {
+            vertical: 2,
+            horizontal: 2,
+        });
+        segments::draw(app, f, app.activity_view_state.segment_efforts_area);
     }
 }
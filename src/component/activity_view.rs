@@ -9,36 +9,52 @@ use crate::{
     event::{
         input::InputEvent,
         keymap::{MappedKey, StravaEvent},
-        util::{table_state_next, table_state_prev},
+        util::{table_state_click, table_state_next, table_state_prev},
     },
-    store::activity::Activities,
+    store::activity::{Activities, Sections, SportType},
 };
 
 use super::{
-    activity_list::list::activity_list_table, polyline, race_predictor, splits, stats, View
+    activity_list::list::activity_list_table, polyline::{self, MapViewport}, power_curve, race_predictor, laps, reps, route_export_dialog, splits, stats, View
 };
 
+/// Zoom factor of one scroll wheel step on the map
+const MAP_ZOOM_STEP: f64 = 1.25;
+
 pub struct ActivityView {}
 
 impl View for ActivityView {
     fn handle(&mut self, app: &mut App, key: MappedKey) {
         let split_len = match &app.activity {
-            Some(a) => a.splits.len(),
+            Some(a) => a.section_count(&app.unit_formatter.system, &app.activity_view_state.sections),
             None => 0,
         };
 
+        if app.activity_view_state.export_dialog {
+            route_export_dialog::handle(app, key);
+
+            return;
+        }
+
         match key.strava_event {
             StravaEvent::ToggleUnitSystem => {
                 app.unit_formatter = app.unit_formatter.toggle();
+                // split boundaries differ between unit systems
+                app.activity_view_state.pace_table_state.select(None);
+                app.activity_view_state.selected_split = None;
             }
+            StravaEvent::ToggleLaps => app.activity_view_state.toggle_sections(Sections::Laps),
+            StravaEvent::ToggleReps => app.activity_view_state.toggle_sections(Sections::Reps),
             StravaEvent::Quit => app.switch_to(ActivePage::ActivityList),
             StravaEvent::ToggleSegmentEffortsView => app.switch_to(ActivePage::ActivitySegments),
             StravaEvent::Enter => app.switch_to(ActivePage::ActivityList),
             StravaEvent::Down => {
                 app.next_activity();
+                app.activity_view_state.map_viewport = MapViewport::default();
             }
             StravaEvent::Up => {
                 app.previous_activity();
+                app.activity_view_state.map_viewport = MapViewport::default();
             }
             StravaEvent::Next => {
                 table_state_next(&mut app.activity_view_state.pace_table_state, split_len, true);
@@ -59,23 +75,81 @@ impl View for ActivityView {
             StravaEvent::ToggleLogView => {
                 app.switch_to(ActivePage::LogView);
             }
+            StravaEvent::Mark => {
+                if let Some(a) = app.activity.clone() {
+                    app.toggle_marked(a);
+                }
+            }
+            StravaEvent::Compare => app.compare_to_marked(),
+            StravaEvent::Export => {
+                app.activity_view_state.export_dialog = !app.activity_view_state.export_dialog;
+            }
+            StravaEvent::Click => {
+                let state = &mut app.activity_view_state;
+                let clicked = key.mouse.and_then(|mouse| {
+                    table_state_click(&mut state.pace_table_state, state.splits_area, 1, &mouse, split_len)
+                });
+                if let Some(row) = clicked {
+                    state.select_split(row as i64);
+                }
+            }
+            StravaEvent::ScrollUp | StravaEvent::ScrollDown => {
+                let state = &mut app.activity_view_state;
+                match key.mouse {
+                    Some(mouse) if mouse.is_in(state.map_area) => {
+                        let area = state.map_area;
+                        let at = (
+                            (mouse.column - area.x) as f64 / area.width as f64,
+                            1.0 - (mouse.row - area.y) as f64 / area.height as f64,
+                        );
+                        let factor = match key.strava_event {
+                            StravaEvent::ScrollUp => MAP_ZOOM_STEP,
+                            _ => 1.0 / MAP_ZOOM_STEP,
+                        };
+                        state.map_viewport.zoom_at(factor, at, area.width as f64, area.height as f64);
+                    }
+                    Some(mouse) if mouse.is_in(state.splits_area) => {
+                        let event = match key.strava_event {
+                            StravaEvent::ScrollUp => StravaEvent::Previous,
+                            _ => StravaEvent::Next,
+                        };
+                        self.handle(app, key.with_event(event));
+                    }
+                    _ => (),
+                }
+            }
+            StravaEvent::Drag => {
+                let state = &mut app.activity_view_state;
+                if let Some(mouse) = key.mouse.filter(|m| m.is_in(state.map_area)) {
+                    state.map_viewport.pan(mouse.delta);
+                }
+            }
             _ => (),
         }
     }
 
-    fn mapped_events(&self, _app: &App) -> Vec<StravaEvent> {
-        vec![
+    fn mapped_events(&self, app: &App) -> Vec<StravaEvent> {
+        let mut events = vec![
             StravaEvent::Down,
             StravaEvent::Up,
             StravaEvent::ToggleUnitSystem,
+            StravaEvent::ToggleLaps,
+            StravaEvent::ToggleReps,
             StravaEvent::Next,
             StravaEvent::Previous,
             StravaEvent::Enter,
             StravaEvent::ToggleLogView,
             StravaEvent::MovingElapsed,
             StravaEvent::ToggleSegmentEffortsView,
-            StravaEvent::Quit,
-        ]
+            StravaEvent::Mark,
+            StravaEvent::Anchor,
+            StravaEvent::Export,
+        ];
+        if app.activity_marked.is_some() {
+            events.push(StravaEvent::Compare);
+        }
+        events.push(StravaEvent::Quit);
+        events
     }
 
     fn draw(&mut self, app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
@@ -87,7 +161,7 @@ impl View for ActivityView {
         if let Some(activity) = &app.activity {
             {
                 let a = Activities::from(activity.clone());
-                activity_list_table(app, &a).render(rows[0], f);
+                activity_list_table(app, &a, rows[0].width).render(rows[0], f);
             }
         }
 
@@ -107,19 +181,42 @@ impl View for ActivityView {
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
             .split(cols[0]);
 
-        let block = Block::default()
-            .title("Race Predictions")
-            .borders(Borders::ALL);
-        block.render(col1[1], f);
+        let power_curve = match &app.activity {
+            Some(a) if matches!(a.activity_category(), SportType::Ride) && !a.power_curve.is_empty() => {
+                Some(a.power_curve.clone())
+            }
+            _ => None,
+        };
+        match power_curve {
+            Some(curve) => {
+                let block = Block::default().title("Power Curve").borders(Borders::ALL);
+                block.render(col1[1], f);
+                power_curve::draw(
+                    &app.theme,
+                    f,
+                    col1[1].inner(Margin {
+                        vertical: 1,
+                        horizontal: 1,
+                    }),
+                    &curve,
+                );
+            }
+            None => {
+                let block = Block::default()
+                    .title("Race Predictions")
+                    .borders(Borders::ALL);
+                block.render(col1[1], f);
 
-        race_predictor::draw(
-            app,
-            f,
-            col1[1].inner(Margin {
-                vertical: 2,
-                horizontal: 2,
-            }),
-        );
+                race_predictor::draw(
+                    app,
+                    f,
+                    col1[1].inner(Margin {
+                        vertical: 2,
+                        horizontal: 2,
+                    }),
+                );
+            }
+        }
 
         let block = Block::default().title("Stats").borders(Borders::ALL);
         block.render(col1[0], f);
@@ -135,23 +232,30 @@ impl View for ActivityView {
 
         let block = Block::default().title("Map").borders(Borders::ALL);
         block.render(cols[1], f);
-        polyline::draw(
-            app,
-            f,
-            cols[1].inner(Margin {
-                vertical: 1,
-                horizontal: 1,
-            }),
-        );
-        let block = Block::default().title("Splits").borders(Borders::ALL);
+        app.activity_view_state.map_area = cols[1].inner(Margin {
+            vertical: 1,
+            horizontal: 1,
+        });
+        polyline::draw(app, f, app.activity_view_state.map_area);
+        let title = match app.activity_view_state.sections {
+            Sections::Splits => "Splits",
+            Sections::Laps => "Laps",
+            Sections::Reps => "Reps",
+        };
+        let block = Block::default().title(title).borders(Borders::ALL);
         block.render(cols[2], f);
-        splits::draw(
-            app,
-            f,
-            cols[2].inner(Margin {
-                vertical: 1,
-                horizontal: 1,
-            }),
-        );
+        app.activity_view_state.splits_area = cols[2].inner(Margin {
+            vertical: 1,
+            horizontal: 1,
+        });
+        match app.activity_view_state.sections {
+            Sections::Splits => splits::draw(app, f, app.activity_view_state.splits_area),
+            Sections::Laps => laps::draw(app, f, app.activity_view_state.splits_area),
+            Sections::Reps => reps::draw(app, f, app.activity_view_state.splits_area),
+        }
+
+        if app.activity_view_state.export_dialog {
+            route_export_dialog::draw(app, f, area);
+        }
     }
 }
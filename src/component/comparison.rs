@@ -0,0 +1,260 @@
+use tui::{
+    layout::{Constraint, Direction, Layout, Margin},
+    prelude::Buffer,
+    style::Style,
+    text::Span,
+    widgets::{Block, Borders, Cell, Row, Table, Widget},
+};
+
+use crate::{
+    app::{ActivePage, App},
+    component::unit_formatter::UnitFormatter,
+    event::keymap::{MappedKey, StravaEvent},
+    store::activity::{Activities, Activity, ActivitySplit},
+    ui::color::Theme,
+};
+
+use super::{activity_list::list::activity_list_table, polyline, View};
+
+pub struct ComparisonView {}
+
+impl ComparisonView {
+    pub(crate) fn new() -> ComparisonView {
+        ComparisonView {}
+    }
+}
+
+impl View for ComparisonView {
+    fn handle(&mut self, app: &mut App, key: MappedKey) {
+        match key.strava_event {
+            StravaEvent::ToggleUnitSystem => {
+                app.unit_formatter = app.unit_formatter.toggle();
+            }
+            StravaEvent::Down => app.next_activity(),
+            StravaEvent::Up => app.previous_activity(),
+            StravaEvent::Quit => app.switch_to_previous(),
+            StravaEvent::Escape => app.switch_to_previous(),
+            StravaEvent::ToggleLogView => app.switch_to(ActivePage::LogView),
+            _ => (),
+        }
+    }
+
+    fn mapped_events(&self, _app: &App) -> Vec<StravaEvent> {
+        vec![
+            StravaEvent::Down,
+            StravaEvent::Up,
+            StravaEvent::ToggleUnitSystem,
+            StravaEvent::ToggleLogView,
+            StravaEvent::Quit,
+        ]
+    }
+
+    fn draw(&mut self, app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
+        let (marked, activity) = match (&app.activity_marked, &app.activity) {
+            (Some(m), Some(a)) => (m.clone(), a.clone()),
+            _ => return,
+        };
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(5), Constraint::Min(2)].as_ref())
+            .split(area);
+
+        let both = Activities::from(vec![marked.clone(), activity.clone()]);
+        activity_list_table(app, &both, rows[0].width).render(rows[0], f);
+
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Percentage(30),
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(30),
+                ]
+                .as_ref(),
+            )
+            .split(rows[1]);
+
+        Block::default().title("Summary").borders(Borders::ALL).render(cols[0], f);
+        summary_table(&app.unit_formatter, &app.theme, &marked, &activity).render(
+            cols[0].inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            f,
+        );
+
+        Block::default()
+            .title(vec![
+                Span::styled(marked.title.clone(), Style::default().fg(app.theme.chart_line)),
+                Span::raw(" vs "),
+                Span::styled(
+                    activity.title.clone(),
+                    Style::default().fg(app.theme.accent),
+                ),
+            ])
+            .borders(Borders::ALL)
+            .render(cols[1], f);
+        if let (Ok(first), Ok(second)) = (marked.polyline(), activity.polyline()) {
+            polyline::draw_comparison(
+                app,
+                f,
+                cols[1].inner(Margin {
+                    vertical: 1,
+                    horizontal: 1,
+                }),
+                &first,
+                &second,
+            );
+        }
+
+        Block::default().title("Splits").borders(Borders::ALL).render(cols[2], f);
+        let system = &app.unit_formatter.system;
+        splits_table(&app.unit_formatter, &app.theme, marked.splits(system), activity.splits(system)).render(
+            cols[2].inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            f,
+        );
+    }
+}
+
+fn header<'a>(theme: &Theme, names: &[&'a str]) -> Row<'a> {
+    Row::new(
+        names
+            .iter()
+            .map(|header| Cell::from(Span::styled(*header, Style::default().fg(theme.dim)))),
+    )
+    .height(1)
+    .bottom_margin(1)
+}
+
+fn delta_style(theme: &Theme, delta: f64) -> Style {
+    match delta {
+        d if d < 0.0 => Style::default().fg(theme.good),
+        d if d > 0.0 => Style::default().fg(theme.bad),
+        _ => Style::default(),
+    }
+}
+
+fn summary_table<'a>(formatter: &UnitFormatter, theme: &Theme, a: &Activity, b: &Activity) -> Table<'a> {
+    let hr = |hr: Option<f64>| hr.map_or_else(|| "n/a".to_string(), |v| format!("{:.0}", v));
+    let hr_delta = match (a.average_heartrate, b.average_heartrate) {
+        (Some(a), Some(b)) => format!("{:+.0}", b - a),
+        _ => "n/a".to_string(),
+    };
+    let elevation_delta = b.total_elevation_gain - a.total_elevation_gain;
+    let rows = vec![
+        Row::new([
+            Cell::from("Distance"),
+            Cell::from(formatter.distance(a.distance)),
+            Cell::from(formatter.distance(b.distance)),
+            Cell::from(format!(
+                "{}{}",
+                if b.distance < a.distance { "-" } else { "+" },
+                formatter.distance((b.distance - a.distance).abs())
+            )),
+        ]),
+        Row::new([
+            Cell::from("Time"),
+            Cell::from(formatter.stopwatch_time(a.moving_time)),
+            Cell::from(formatter.stopwatch_time(b.moving_time)),
+            Cell::from(formatter.stopwatch_delta(b.moving_time - a.moving_time))
+                .style(delta_style(theme, (b.moving_time - a.moving_time) as f64)),
+        ]),
+        Row::new([
+            Cell::from("Pace"),
+            Cell::from(formatter.pace(a.moving_time, a.distance)),
+            Cell::from(formatter.pace(b.moving_time, b.distance)),
+            Cell::from(formatter.pace_delta(a.moving_time, a.distance, b.moving_time, b.distance))
+                .style(delta_style(theme,
+                    b.moving_time as f64 / b.distance - a.moving_time as f64 / a.distance,
+                )),
+        ]),
+        Row::new([
+            Cell::from("Avg HR"),
+            Cell::from(hr(a.average_heartrate)),
+            Cell::from(hr(b.average_heartrate)),
+            Cell::from(hr_delta),
+        ]),
+        Row::new([
+            Cell::from("Max HR"),
+            Cell::from(hr(a.max_heartrate)),
+            Cell::from(hr(b.max_heartrate)),
+            Cell::from(match (a.max_heartrate, b.max_heartrate) {
+                (Some(a), Some(b)) => format!("{:+.0}", b - a),
+                _ => "n/a".to_string(),
+            }),
+        ]),
+        Row::new([
+            Cell::from("Elevation"),
+            Cell::from(formatter.elevation(a.total_elevation_gain)),
+            Cell::from(formatter.elevation(b.total_elevation_gain)),
+            Cell::from(format!(
+                "{}{}",
+                if elevation_delta < 0.0 { "-" } else { "+" },
+                formatter.elevation(elevation_delta.abs())
+            )),
+        ]),
+    ];
+
+    Table::new(
+        rows,
+        &[
+            Constraint::Length(9),
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+        ],
+    )
+    .header(header(theme, &["", "A", "B", "Δ"]))
+}
+
+fn splits_table<'a>(
+    formatter: &UnitFormatter,
+    theme: &Theme,
+    a: &[ActivitySplit],
+    b: &[ActivitySplit],
+) -> Table<'a> {
+    let pace = |s: Option<&ActivitySplit>| {
+        s.map_or_else(|| "".to_string(), |s| formatter.pace(s.moving_time, s.distance))
+    };
+    let rows: Vec<Row> = (0..a.len().max(b.len()))
+        .map(|i| {
+            let (sa, sb) = (a.get(i), b.get(i));
+            let (pace_delta, time_delta) = match (sa, sb) {
+                (Some(sa), Some(sb)) => (
+                    Cell::from(formatter.pace_delta(
+                        sa.moving_time,
+                        sa.distance,
+                        sb.moving_time,
+                        sb.distance,
+                    ))
+                    .style(delta_style(theme, sb.seconds_per_meter() - sa.seconds_per_meter())),
+                    Cell::from(formatter.stopwatch_delta(sb.moving_time - sa.moving_time)),
+                ),
+                _ => (Cell::from(""), Cell::from("")),
+            };
+            Row::new([
+                Cell::from(format!("{}", i + 1)),
+                Cell::from(pace(sa)),
+                Cell::from(pace(sb)),
+                pace_delta,
+                time_delta,
+            ])
+        })
+        .collect();
+
+    Table::new(
+        rows,
+        &[
+            Constraint::Length(3),
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+        ],
+    )
+    .header(header(theme, &["#", "A", "B", "Δ Pace", "Δ Time"]))
+}
@@ -0,0 +1,256 @@
+use tui::{
+    layout::{Constraint, Direction, Layout, Margin},
+    prelude::Buffer,
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Cell, Row, StatefulWidget, Table, TableState, Widget},
+};
+use tui_input::Input;
+
+use crate::{
+    app::{ActivePage, App},
+    client::Totals,
+    event::{
+        input::InputEvent,
+        keymap::{MappedKey, StravaEvent},
+        util::{table_state_next, table_state_prev},
+    },
+    store::gear::{gear_usage, GearUsage},
+};
+
+use super::{unit_formatter::UnitFormatter, View};
+
+pub const GEAR_HEADERS: [&str; 10] = [
+    "Gear", "Type", "Model", "Count", "Distance", "Synced", "Time", "Last Used", "Retire At", "Wear",
+];
+
+const TOTALS_HEADERS: [&str; 7] = ["", "Runs", "Run Dst", "Rides", "Ride Dst", "Swims", "Swim Dst"];
+
+#[derive(Default)]
+pub struct GearViewState {
+    pub table_state: TableState,
+}
+
+pub struct GearView {}
+
+impl GearView {
+    pub(crate) fn new() -> GearView {
+        GearView {}
+    }
+}
+
+fn usage(app: &App) -> Vec<GearUsage> {
+    gear_usage(&app.gear, &app.activities(), &app.gear_retirement)
+}
+
+/// Formatted cells of a gear row
+fn gear_row(formatter: &UnitFormatter, usage: &GearUsage) -> Vec<String> {
+    let gear = &usage.gear;
+    let mut name = gear.name.clone();
+    if gear.primary {
+        name.push_str(" ★");
+    }
+    vec![
+        name,
+        match gear.retired {
+            true => format!("{} (retired)", gear.gear_type.to_label()),
+            false => gear.gear_type.to_label().to_string(),
+        },
+        gear.model(),
+        format!("{}", usage.activities),
+        formatter.distance(gear.distance),
+        formatter.distance(usage.distance),
+        formatter.stopwatch_time(usage.moving_time),
+        usage
+            .last_used
+            .map_or_else(|| "".to_string(), |d| d.format("%Y-%m-%d").to_string()),
+        usage
+            .threshold
+            .map_or_else(|| "".to_string(), |t| formatter.distance(t)),
+        usage
+            .wear()
+            .map_or_else(|| "".to_string(), |w| format!("{:.0}%", w * 100.0)),
+    ]
+}
+
+fn totals_row<'a>(formatter: &UnitFormatter, label: &'a str, totals: [&Totals; 3]) -> Row<'a> {
+    let mut cells = vec![label.to_string()];
+    for t in totals {
+        cells.push(format!("{}", t.count));
+        cells.push(formatter.distance(t.distance));
+    }
+    Row::new(cells)
+}
+
+impl View for GearView {
+    fn handle(&mut self, app: &mut App, key: MappedKey) {
+        let usage = usage(app);
+        match key.strava_event {
+            StravaEvent::Down => table_state_next(&mut app.gear_view.table_state, usage.len(), false),
+            StravaEvent::Up => table_state_prev(&mut app.gear_view.table_state, usage.len(), false),
+            StravaEvent::Enter => {
+                // list the activities of the selected gear
+                if let Some(u) = app.gear_view.table_state.selected().and_then(|i| usage.get(i)) {
+                    let filter = format!("gear = \"{}\"", u.gear.name);
+                    app.activity_list.filter_text_area = Input::new(filter.clone());
+                    app.filters.filter = filter;
+                    app.send(InputEvent::Reload);
+                    app.switch_to(ActivePage::ActivityList);
+                }
+            }
+            StravaEvent::ToggleUnitSystem => {
+                app.unit_formatter = app.unit_formatter.toggle();
+            }
+            StravaEvent::ToggleLogView => app.switch_to(ActivePage::LogView),
+            StravaEvent::Quit => app.switch_to(ActivePage::ActivityList),
+            StravaEvent::ToggleGearView => app.switch_to(ActivePage::ActivityList),
+            _ => (),
+        }
+    }
+
+    fn mapped_events(&self, _app: &App) -> Vec<StravaEvent> {
+        vec![
+            StravaEvent::Down,
+            StravaEvent::Up,
+            StravaEvent::Enter,
+            StravaEvent::ToggleUnitSystem,
+            StravaEvent::ToggleGearView,
+            StravaEvent::ToggleLogView,
+            StravaEvent::Quit,
+        ]
+    }
+
+    fn draw(&mut self, app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
+        let usage = usage(app);
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(8), Constraint::Min(4)].as_ref())
+            .split(area);
+
+        let title = match &app.athlete {
+            Some(athlete) => {
+                let location: Vec<&str> = [&athlete.city, &athlete.country]
+                    .iter()
+                    .filter_map(|s| s.as_deref())
+                    .collect();
+                let mut title = athlete.name();
+                if !location.is_empty() {
+                    title.push_str(&format!(", {}", location.join(", ")));
+                }
+                if let Some(weight) = athlete.weight {
+                    title.push_str(&format!(", {:.1}kg", weight));
+                }
+                if let Some(ftp) = athlete.ftp {
+                    title.push_str(&format!(", FTP {}W", ftp));
+                }
+                title
+            }
+            None => "Athlete (not synced)".to_string(),
+        };
+        Block::default().title(title).borders(Borders::ALL).render(rows[0], f);
+        let stats = app.athlete.as_ref().and_then(|a| a.stats.clone()).unwrap_or_default();
+        let totals = Table::new(
+            vec![
+                totals_row(
+                    &app.unit_formatter,
+                    "Last 4 weeks",
+                    [&stats.recent_run_totals, &stats.recent_ride_totals, &stats.recent_swim_totals],
+                ),
+                totals_row(
+                    &app.unit_formatter,
+                    "This year",
+                    [&stats.ytd_run_totals, &stats.ytd_ride_totals, &stats.ytd_swim_totals],
+                ),
+                totals_row(
+                    &app.unit_formatter,
+                    "All time",
+                    [&stats.all_run_totals, &stats.all_ride_totals, &stats.all_swim_totals],
+                ),
+            ],
+            &[
+                Constraint::Min(13),
+                Constraint::Min(6),
+                Constraint::Min(10),
+                Constraint::Min(6),
+                Constraint::Min(10),
+                Constraint::Min(6),
+                Constraint::Min(10),
+            ],
+        )
+        .header(
+            Row::new(
+                TOTALS_HEADERS
+                    .iter()
+                    .map(|h| Cell::from(Span::styled(*h, Style::default().fg(app.theme.dim)))),
+            )
+            .bottom_margin(1),
+        );
+        Widget::render(
+            totals,
+            rows[0].inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            f,
+        );
+
+        if app.gear_view.table_state.selected().is_none() && !usage.is_empty() {
+            app.gear_view.table_state.select(Some(0));
+        }
+
+        let header = GEAR_HEADERS
+            .iter()
+            .map(|header| Cell::from(Span::styled(*header, Style::default().fg(app.theme.dim))));
+        let table_rows: Vec<Row> = usage
+            .iter()
+            .map(|u| {
+                let style = if u.is_due() {
+                    Style::default().fg(app.theme.bad)
+                } else if u.is_worn() {
+                    Style::default().fg(app.theme.warning)
+                } else if u.gear.retired {
+                    Style::default().fg(app.theme.muted)
+                } else {
+                    Style::default()
+                };
+                Row::new(gear_row(&app.unit_formatter, u)).style(style)
+            })
+            .collect();
+
+        let due = usage.iter().filter(|u| u.is_due()).count();
+        Block::default()
+            .title(match due {
+                0 => "Gear".to_string(),
+                n => format!("Gear ({} due for retirement)", n),
+            })
+            .borders(Borders::ALL)
+            .render(rows[1], f);
+        let table = Table::new(
+            table_rows,
+            &[
+                Constraint::Min(14),
+                Constraint::Min(6),
+                Constraint::Min(14),
+                Constraint::Min(5),
+                Constraint::Min(10),
+                Constraint::Min(10),
+                Constraint::Min(9),
+                Constraint::Min(10),
+                Constraint::Min(10),
+                Constraint::Min(5),
+            ],
+        )
+        .header(Row::new(header).height(1).bottom_margin(1))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol("");
+        <Table as StatefulWidget>::render(
+            table,
+            rows[1].inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            f,
+            &mut app.gear_view.table_state,
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use tui::{
+    layout::{Constraint, Direction, Layout, Margin},
+    prelude::Buffer,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, LineGauge, Paragraph, Widget},
+};
+
+use crate::{
+    app::App,
+    store::goal::{GoalMetric, GoalProgress},
+    ui::color::Theme,
+};
+
+use super::unit_formatter::UnitFormatter;
+
+fn format_value(formatter: &UnitFormatter, metric: &GoalMetric, value: f64) -> String {
+    match metric {
+        GoalMetric::Distance => formatter.distance(value),
+        GoalMetric::Time => formatter.stopwatch_time(value as i64),
+        GoalMetric::Elevation => formatter.elevation(value),
+    }
+}
+
+/// Progress of the configured goals in the current period, one goal per 3 lines
+pub fn draw(app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
+    Block::default().title("Goals").borders(Borders::ALL).render(area, f);
+    let inner = area.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            app.goal_progress
+                .iter()
+                .map(|_| Constraint::Length(3))
+                .collect::<Vec<Constraint>>(),
+        )
+        .split(inner);
+
+    for (progress, area) in app.goal_progress.iter().zip(rows.iter()) {
+        draw_goal(&app.unit_formatter, &app.theme, progress, f, *area);
+    }
+}
+
+fn draw_goal(formatter: &UnitFormatter, theme: &Theme, progress: &GoalProgress, f: &mut Buffer, area: tui::layout::Rect) {
+    let goal = &progress.goal;
+    let lines = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1); 3].as_ref())
+        .split(area);
+
+    let on_track = progress.projected >= goal.target;
+    LineGauge::default()
+        .label(Span::styled(
+            format!("{} ({})", goal.name, goal.period.to_label()),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .filled_style(Style::default().fg(match on_track {
+            true => theme.good,
+            false => theme.accent,
+        }))
+        .ratio((progress.percent() / 100.0).clamp(0.0, 1.0))
+        .render(lines[0], f);
+
+    Paragraph::new(Line::from(vec![
+        Span::raw(format!(
+            "{} / {} ({:.0}%) ",
+            format_value(formatter, &goal.metric, progress.current),
+            format_value(formatter, &goal.metric, goal.target),
+            progress.percent()
+        )),
+        Span::styled(
+            format!("→ {}", format_value(formatter, &goal.metric, progress.projected)),
+            Style::default().fg(theme.dim),
+        ),
+    ]))
+    .render(lines[1], f);
+
+    let mut history = vec![Span::styled("history ", Style::default().fg(theme.dim))];
+    for result in progress.history.iter() {
+        history.push(match result.hit {
+            true => Span::styled("■", Style::default().fg(theme.good)),
+            false => Span::styled("■", Style::default().fg(theme.bad)),
+        });
+    }
+    Paragraph::new(Line::from(history)).render(lines[2], f);
+}
@@ -0,0 +1,73 @@
+use tui::{
+    layout::Constraint,
+    style::{Modifier, Style, Styled},
+    widgets::{Cell, Row, StatefulWidget, Table},
+    prelude::Buffer,
+};
+
+use crate::{app::App, store::activity::{ActivityLap, SportType}};
+
+pub fn draw(
+    app: &mut App,
+    f: &mut Buffer,
+    area: tui::layout::Rect,
+) {
+    if app.activity.is_none() {
+        return;
+    }
+    let activity = app.activity.as_ref().unwrap();
+    let laps: &Vec<ActivityLap> = &activity.laps;
+
+    let mut max = 0.0;
+    let mut min = f64::MAX;
+    for lap in laps.iter() {
+        if lap.seconds_per_meter() > max {
+            max = lap.seconds_per_meter()
+        }
+        if lap.seconds_per_meter() < min {
+            min = lap.seconds_per_meter()
+        }
+    }
+
+    let speed_header = match activity.activity_category() {
+        SportType::Ride => "󰓅 Speed",
+        _ => "👣 Pace",
+    };
+    let header = vec!["#", "Dist", "Time", speed_header, "♥", "🌄"];
+
+    let mut rows = vec![];
+    for (count, lap) in laps.iter().enumerate() {
+        let color = app.theme.gradient(lap.seconds_per_meter() - min, max - min);
+        rows.push(Row::new([
+            Cell::from(format!("{}", count + 1)).set_style(Style::default().bg(color)),
+            Cell::from(app.unit_formatter.distance(lap.distance)),
+            Cell::from(app.unit_formatter.stopwatch_time(lap.moving_time)),
+            match activity.activity_category() {
+                SportType::Ride => Cell::from(app.unit_formatter.speed(lap.meters_per_hour())),
+                _ => Cell::from(app.unit_formatter.pace(lap.moving_time, lap.distance)),
+            },
+            Cell::from(
+                lap.average_heartrate
+                    .map_or_else(|| "".to_string(), |hr| format!("{:.0}", hr)),
+            ),
+            Cell::from(app.unit_formatter.elevation(lap.total_elevation_gain)),
+        ]));
+    }
+    Table::new(rows, &[
+            Constraint::Length(2),
+            Constraint::Min(7),
+            Constraint::Min(5),
+            Constraint::Min(9),
+            Constraint::Min(3),
+            Constraint::Min(5),
+        ])
+        .header(
+            Row::new(header)
+                .height(1)
+                .bottom_margin(0)
+                .style(Style::default()),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol("")
+        .render(area, f, &mut app.activity_view_state.pace_table_state);
+}
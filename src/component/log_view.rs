@@ -1,6 +1,6 @@
 
 use tui::{
-    style::{Color, Style},
+    style::Style,
     widgets::{Widget},
 };
 use tui_logger::{TuiLoggerLevelOutput, TuiLoggerWidget};
@@ -51,15 +51,15 @@ impl View for LogView {
             .output_timestamp(Some("%F %H:%M:%S%.3f".to_string()))
             .output_level(Some(TuiLoggerLevelOutput::Long))
             .output_target(false)
-            .style_error(Style::default().fg(Color::Red))
-            .style_debug(Style::default().fg(Color::Green))
-            .style_warn(Style::default().fg(Color::Yellow))
-            .style_trace(Style::default().fg(Color::Magenta))
-            .style_info(Style::default().fg(Color::Cyan))
+            .style_error(Style::default().fg(app.theme.bad))
+            .style_debug(Style::default().fg(app.theme.good))
+            .style_warn(Style::default().fg(app.theme.warning))
+            .style_trace(Style::default().fg(app.theme.trace))
+            .style_info(Style::default().fg(app.theme.info))
             .output_file(false)
             .output_line(false)
-            .state(&mut app.log_view_state)
-            .style(Style::default().fg(Color::White).bg(Color::Black));
+            .state(&app.log_view_state)
+            .style(Style::default().fg(app.theme.text).bg(app.theme.background));
         tui_w.render(area, f);
     }
 }
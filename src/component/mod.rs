@@ -3,13 +3,23 @@ use tui::{prelude::{Buffer}};
 use crate::{app::{ActivePage, App}, event::keymap::{MappedKey, StravaEvent}};
 
 pub mod splits;
+pub mod laps;
 pub mod activity_list;
 pub mod activity_view;
 pub mod activity_segments;
+pub mod comparison;
+pub mod gear_view;
+pub mod goals;
 pub mod polyline;
+pub mod power_curve;
+pub mod power_curve_view;
 pub mod race_predictor;
+pub mod reps;
+pub mod route_export_dialog;
 pub mod stats;
+pub mod stats_view;
 pub mod segments;
+pub mod segment_leaderboard;
 pub mod unit_formatter;
 pub mod log_view;
 
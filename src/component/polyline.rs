@@ -11,11 +11,8 @@ use tui::{
 use crate::{
     app::App,
     store::{activity::Polyline, polyline_compare::length},
-    ui::color::{gradient, Rgb},
 };
 
-use super::unit_formatter::KILOMETER_TO_MILE;
-
 pub fn draw(
     app: &mut App,
     f: &mut Buffer,
@@ -34,37 +31,42 @@ pub fn draw(
             area.height.saturating_sub(4)
         );
 
-        let length_per_split =
-            mapped_polyline.length() / ((activity.distance / 1000.0) * KILOMETER_TO_MILE);
+        // the selected split or lap as a share of the route length
+        let state = &app.activity_view_state;
+        let section = state
+            .selected_split
+            .and_then(|s| activity.section(&app.unit_formatter.system, &state.sections, s as usize))
+            .map(|(start, end)| (start / activity.distance, end / activity.distance));
+        let route_length = mapped_polyline.length();
 
+        let viewport = &app.activity_view_state.map_viewport;
+        let (x_bounds, y_bounds) = viewport.bounds(area.width as f64, area.height as f64);
         let canvas = Canvas::default()
-            .x_bounds([0.0, area.width as f64])
-            .y_bounds([0.0, area.height as f64])
+            .x_bounds(x_bounds)
+            .y_bounds(y_bounds)
             .paint(|ctx| {
                 let mut prev: Option<(f64, f64)> = None;
                 let mut offset = 0;
                 ctx.print(
-                    0.0,
-                    0.0,
+                    x_bounds[0],
+                    y_bounds[0],
                     Span::from(format!(
                         "{} → ",
                         app.unit_formatter
-                            .distance(mapped_polyline.x_distance().meters())
+                            .distance(mapped_polyline.x_distance().meters() / viewport.zoom)
                     )),
                 );
 
                 ctx.print(
-                    0.0,
-                    area.height as f64,
+                    x_bounds[0],
+                    y_bounds[1],
                     Span::from(
                         app.unit_formatter
-                            .distance(mapped_polyline.y_distance().meters()),
+                            .distance(mapped_polyline.y_distance().meters() / viewport.zoom),
                     ),
                 );
-                ctx.print(0.0, area.height as f64 - 2.0, Span::from("↓"));
+                ctx.print(x_bounds[0], y_bounds[1] - 2.0 / viewport.zoom, Span::from("↓"));
                 let mut running_length = 0.0;
-                let mut next_break = length_per_split;
-                let mut split: i64 = 0;
                 let mut route_lines = vec![];
                 let mut split_lines = vec![];
 
@@ -76,6 +78,7 @@ pub fn draw(
                     let from = prev.unwrap();
                     let to = coord;
 
+                    let share = running_length / route_length;
                     running_length += length(&LineString::new(vec![
                         Coord {
                             x: from.0,
@@ -84,48 +87,26 @@ pub fn draw(
                         Coord { x: to.0, y: to.1 },
                     ]));
 
-                    match in_selected_split(app.activity_view_state.selected_split, split) {
+                    match in_section(section, share) {
                         true => split_lines.push(Line {
                             x1: from.0 + 1.0,
                             y1: from.1 + 1.0,
                             x2: to.0 + 1.0,
                             y2: to.1 + 1.0,
-                            color: gradient(
-                                Rgb {
-                                    red: 50,
-                                    green: 255,
-                                    blue: 255,
-                                },
-                                Rgb {
-                                    red: 255,
-                                    green: 50,
-                                    blue: 255,
-                                },
+                            color: app.theme.highlight(
                                 offset as f64,
                                 mapped_polyline.coords.len() as f64,
-                            )
-                            .to_color(),
+                            ),
                         }),
                         false => route_lines.push(Line {
                             x1: from.0 + 1.0,
                             y1: from.1 + 1.0,
                             x2: to.0 + 1.0,
                             y2: to.1 + 1.0,
-                            color: gradient(
-                                Rgb {
-                                    red: 0,
-                                    green: 255,
-                                    blue: 0,
-                                },
-                                Rgb {
-                                    red: 255,
-                                    green: 0,
-                                    blue: 0,
-                                },
+                            color: app.theme.gradient(
                                 offset as f64,
                                 mapped_polyline.coords.len() as f64,
-                            )
-                            .to_color(),
+                            ),
                         }),
                     };
 
@@ -136,11 +117,6 @@ pub fn draw(
                         ctx.draw(line);
                     }
 
-                    if running_length >= next_break {
-                        next_break += length_per_split;
-                        split += 1;
-                    }
-
                     prev = Some(*to);
                     offset += 1;
                 }
@@ -149,13 +125,115 @@ pub fn draw(
     }
 }
 
-fn in_selected_split(selected_split: Option<i64>, split: i64) -> bool {
-    match selected_split {
-        Some(s) => s == split,
+/// Draw two routes on the same canvas, the first in the chart colour and the second in the accent colour
+pub fn draw_comparison(
+    app: &App,
+    f: &mut Buffer,
+    area: tui::layout::Rect,
+    first: &Polyline,
+    second: &Polyline,
+) {
+    let bounds = LineString::from_iter(first.coords().chain(second.coords()).copied());
+    let width = area.width.saturating_add(4);
+    let height = area.height.saturating_sub(4);
+    let maps = [
+        (ActivityMap::from_polyline_in_bounds(first.clone(), &bounds, width, height), app.theme.chart_line),
+        (
+            ActivityMap::from_polyline_in_bounds(second.clone(), &bounds, width, height),
+            app.theme.accent,
+        ),
+    ];
+    let extent = ActivityMap::from_polyline(bounds, width, height);
+
+    Canvas::default()
+        .x_bounds([0.0, area.width as f64])
+        .y_bounds([0.0, area.height as f64])
+        .paint(|ctx| {
+            ctx.print(
+                0.0,
+                0.0,
+                Span::from(format!(
+                    "{} → ",
+                    app.unit_formatter.distance(extent.x_distance().meters())
+                )),
+            );
+            for (map, color) in maps.iter() {
+                for (from, to) in map.coords.iter().zip(map.coords.iter().skip(1)) {
+                    ctx.draw(&Line {
+                        x1: from.0 + 1.0,
+                        y1: from.1 + 1.0,
+                        x2: to.0 + 1.0,
+                        y2: to.1 + 1.0,
+                        color: *color,
+                    });
+                }
+            }
+        })
+        .render(area, f);
+}
+
+fn in_section(section: Option<(f64, f64)>, share: f64) -> bool {
+    match section {
+        Some((start, end)) => share >= start && share < end,
         None => false,
     }
 }
 
+const MAX_ZOOM: f64 = 16.0;
+
+/// Zoom and pan of the map, the offset of the centre is in cells at zoom 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapViewport {
+    pub zoom: f64,
+    pub offset: (f64, f64),
+}
+
+impl Default for MapViewport {
+    fn default() -> Self {
+        MapViewport {
+            zoom: 1.0,
+            offset: (0.0, 0.0),
+        }
+    }
+}
+
+impl MapViewport {
+    /// Canvas bounds showing the visible part of a map of the given size
+    pub fn bounds(&self, width: f64, height: f64) -> ([f64; 2], [f64; 2]) {
+        let (x, y) = (width / 2.0 + self.offset.0, height / 2.0 + self.offset.1);
+        let (half_width, half_height) = (width / 2.0 / self.zoom, height / 2.0 / self.zoom);
+        (
+            [x - half_width, x + half_width],
+            [y - half_height, y + half_height],
+        )
+    }
+
+    /// Multiply the zoom by `factor` keeping the point at the fraction `at`
+    /// of the map (from the bottom left) in place.
+    pub fn zoom_at(&mut self, factor: f64, at: (f64, f64), width: f64, height: f64) {
+        let zoom = (self.zoom * factor).clamp(1.0, MAX_ZOOM);
+        if zoom == 1.0 {
+            *self = MapViewport::default();
+            return;
+        }
+        let (x_bounds, y_bounds) = self.bounds(width, height);
+        let x = x_bounds[0] + at.0 * (x_bounds[1] - x_bounds[0]);
+        let y = y_bounds[0] + at.1 * (y_bounds[1] - y_bounds[0]);
+        self.zoom = zoom;
+        self.offset = (
+            x - (at.0 * 2.0 - 1.0) * width / 2.0 / zoom - width / 2.0,
+            y - (at.1 * 2.0 - 1.0) * height / 2.0 / zoom - height / 2.0,
+        );
+    }
+
+    /// Move the map along with the pointer, `delta` is in terminal cells
+    pub fn pan(&mut self, delta: (i32, i32)) {
+        self.offset.0 -= delta.0 as f64 / self.zoom;
+        // terminal rows go down while the canvas y axis goes up
+        self.offset.1 += delta.1 as f64 / self.zoom;
+    }
+}
+
 struct ActivityMap {
     pub coords: Vec<(f64, f64)>,
     x_distance: f64,
@@ -164,23 +242,35 @@ struct ActivityMap {
 
 impl ActivityMap {
     pub fn from_polyline(decoded: LineString, width: u16, height: u16) -> Self {
-        let x_max = decoded
+        let bounds = decoded.clone();
+        ActivityMap::from_polyline_in_bounds(decoded, &bounds, width, height)
+    }
+
+    /// Scale the polyline to the extent of `bounds` so that several polylines
+    /// can share the same projection.
+    pub fn from_polyline_in_bounds(
+        decoded: LineString,
+        bounds: &LineString,
+        width: u16,
+        height: u16,
+    ) -> Self {
+        let x_max = bounds
             .points()
             .map(|p| p.x())
             .reduce(f64::max)
             .unwrap_or(0 as f64);
-        let x_min = decoded
+        let x_min = bounds
             .points()
             .map(|p| p.x())
             .reduce(f64::min)
             .unwrap_or(0 as f64);
         let x_width = x_max - x_min;
-        let y_max = decoded
+        let y_max = bounds
             .points()
             .map(|p| p.y())
             .reduce(f64::max)
             .unwrap_or(0 as f64);
-        let y_min = decoded
+        let y_min = bounds
             .points()
             .map(|p| p.y())
             .reduce(f64::min)
@@ -251,7 +341,23 @@ impl ActivityMap {
 
 #[cfg(test)]
 mod tests {
-    use super::ActivityMap;
+    use super::{ActivityMap, MapViewport};
+
+    #[test]
+    fn test_zoom_and_pan() {
+        let mut viewport = MapViewport::default();
+        assert_eq!(([0.0, 100.0], [0.0, 50.0]), viewport.bounds(100.0, 50.0));
+
+        // zooming in on the bottom left corner keeps it in place
+        viewport.zoom_at(2.0, (0.0, 0.0), 100.0, 50.0);
+        assert_eq!(([0.0, 50.0], [0.0, 25.0]), viewport.bounds(100.0, 50.0));
+
+        viewport.pan((-10, 4));
+        assert_eq!(([5.0, 55.0], [2.0, 27.0]), viewport.bounds(100.0, 50.0));
+
+        viewport.zoom_at(0.5, (0.5, 0.5), 100.0, 50.0);
+        assert_eq!(MapViewport::default(), viewport);
+    }
 
     #[test]
     fn test_decode_polyline() {
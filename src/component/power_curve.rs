@@ -0,0 +1,66 @@
+use tui::{
+    layout::Constraint,
+    prelude::Buffer,
+    style::Style,
+    symbols::Marker,
+    text::Span,
+    widgets::{Axis, Chart, Dataset, GraphType, Widget},
+};
+
+use crate::{store::power_curve::PowerCurve, ui::color::Theme};
+
+pub fn draw(theme: &Theme, f: &mut Buffer, area: tui::layout::Rect, curve: &PowerCurve) {
+    if curve.is_empty() {
+        return;
+    }
+    let data: Vec<(f64, f64)> = curve
+        .points
+        .iter()
+        .map(|p| ((p.duration as f64).log10(), p.watts))
+        .collect();
+    let wmax = curve.points.iter().map(|p| p.watts).fold(0.0, f64::max);
+    let xmax = data.last().map(|(x, _)| *x).unwrap_or(0.0);
+
+    let datasets = vec![Dataset::default()
+        .name("Power")
+        .data(&data)
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(theme.chart_curve))];
+
+    let ylabels = [0.0, wmax / 2.0, wmax]
+        .iter()
+        .map(|w| Span::from(format!("{:.0}W", w)))
+        .collect::<Vec<Span>>();
+    // durations are plotted on a log scale, labels are spread evenly across it
+    let xlabels = (0..=4)
+        .map(|i| {
+            let seconds = 10_f64.powf(xmax * i as f64 / 4.0).round() as i64;
+            Span::from(duration_label(seconds))
+        })
+        .collect::<Vec<Span>>();
+
+    Chart::new(datasets)
+        .hidden_legend_constraints((Constraint::Max(1), Constraint::Max(1)))
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.text))
+                .bounds([0.0, xmax])
+                .labels(xlabels),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.text))
+                .bounds([0.0, wmax])
+                .labels(ylabels),
+        )
+        .render(area, f);
+}
+
+pub fn duration_label(seconds: i64) -> String {
+    match seconds {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s => format!("{}h", s / 3600),
+    }
+}
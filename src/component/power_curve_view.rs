@@ -0,0 +1,115 @@
+use tui::{
+    layout::{Constraint, Direction, Layout, Margin},
+    prelude::Buffer,
+    style::Style,
+    text::Span,
+    widgets::{Block, Borders, Cell, Row, Table, Widget},
+};
+
+use crate::{
+    app::{ActivePage, App},
+    event::keymap::{MappedKey, StravaEvent},
+    store::{
+        activity::{Activity, SportType},
+        power_curve::PowerCurve,
+    },
+};
+
+use super::{power_curve, power_curve::duration_label, View};
+
+pub struct PowerCurveView {}
+
+impl PowerCurveView {
+    pub(crate) fn new() -> PowerCurveView {
+        PowerCurveView {}
+    }
+}
+
+impl View for PowerCurveView {
+    fn handle(&mut self, app: &mut App, key: MappedKey) {
+        match key.strava_event {
+            StravaEvent::Quit => app.switch_to_previous(),
+            StravaEvent::TogglePowerCurveView => app.switch_to_previous(),
+            StravaEvent::ToggleLogView => app.switch_to(ActivePage::LogView),
+            _ => (),
+        }
+    }
+
+    fn mapped_events(&self, _app: &App) -> Vec<StravaEvent> {
+        vec![
+            StravaEvent::TogglePowerCurveView,
+            StravaEvent::ToggleLogView,
+            StravaEvent::Quit,
+        ]
+    }
+
+    fn draw(&mut self, app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
+        let rides: Vec<Activity> = app
+            .activities()
+            .to_vec()
+            .into_iter()
+            .filter(|a| matches!(a.activity_category(), SportType::Ride) && !a.power_curve.is_empty())
+            .collect();
+        let curve = PowerCurve::best_of(rides.iter().map(|a| &a.power_curve));
+
+        let dates: Vec<String> = rides
+            .iter()
+            .filter_map(|a| a.start_date.map(|d| d.format("%Y-%m-%d").to_string()))
+            .collect();
+        let title = match (dates.iter().min(), dates.iter().max()) {
+            (Some(from), Some(to)) => format!("Power Curve: {} rides from {} to {}", rides.len(), from, to),
+            _ => "Power Curve: no rides with power data".to_string(),
+        };
+
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(70), Constraint::Percentage(30)].as_ref())
+            .split(area);
+
+        Block::default().title(title).borders(Borders::ALL).render(cols[0], f);
+        power_curve::draw(
+            &app.theme,
+            f,
+            cols[0].inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            &curve,
+        );
+
+        Block::default().title("Best Efforts").borders(Borders::ALL).render(cols[1], f);
+        let header = ["Time", "Power", "Activity"]
+            .iter()
+            .map(|header| Cell::from(Span::styled(*header, Style::default().fg(app.theme.dim))));
+        let rows: Vec<Row> = curve
+            .points
+            .iter()
+            .map(|point| {
+                let activity = rides
+                    .iter()
+                    .find(|a| a.power_curve.watts_at(point.duration) == Some(point.watts));
+                Row::new([
+                    Cell::from(duration_label(point.duration)),
+                    Cell::from(format!("{:.0}W", point.watts)),
+                    Cell::from(activity.map_or_else(|| "".to_string(), |a| a.title.clone())),
+                ])
+            })
+            .collect();
+        Table::new(
+            rows,
+            &[
+                Constraint::Length(5),
+                Constraint::Length(6),
+                Constraint::Percentage(100),
+            ],
+        )
+        .header(Row::new(header).height(1).bottom_margin(1))
+        .render(
+            cols[1].inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            f,
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use tui::{
     layout::Constraint,
-    style::{Color, Style},
+    style::Style,
     text::Span,
     widgets::{Cell, Row, Table, Widget},
     prelude::Buffer,
@@ -17,7 +17,7 @@ pub fn draw(
     let header_names = ["Distance", "Time"];
     let headers = header_names
         .iter()
-        .map(|header| Cell::from(Span::styled(*header, Style::default().fg(Color::DarkGray))));
+        .map(|header| Cell::from(Span::styled(*header, Style::default().fg(app.theme.dim))));
 
     if let Some(activity) = &app.activity {
         rows.push(Row::new([
@@ -0,0 +1,111 @@
+use tui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style, Styled},
+    text::Text,
+    widgets::{Cell, Paragraph, Row, StatefulWidget, Table, Widget, Wrap},
+    prelude::Buffer,
+};
+
+use crate::{app::App, store::workout::Workout};
+
+use super::unit_formatter::UnitFormatter;
+
+pub fn draw(
+    app: &mut App,
+    f: &mut Buffer,
+    area: tui::layout::Rect,
+) {
+    let workout = match app.activity.as_ref().and_then(|a| a.workout.clone()) {
+        Some(w) => w,
+        None => {
+            Paragraph::new("No reps detected")
+                .style(Style::default().fg(app.theme.muted))
+                .render(area, f);
+            return;
+        }
+    };
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(workout.reps.len() as u16 + 2), Constraint::Min(0)].as_ref())
+        .split(area);
+
+    let mut max = 0.0;
+    let mut min = f64::MAX;
+    for rep in workout.reps.iter() {
+        if rep.seconds_per_meter() > max {
+            max = rep.seconds_per_meter()
+        }
+        if rep.seconds_per_meter() < min {
+            min = rep.seconds_per_meter()
+        }
+    }
+
+    let header = vec!["#", "Dist", "Time", "👣 Pace", "♥", "Rest"];
+    let mut table_rows = vec![];
+    for (count, rep) in workout.reps.iter().enumerate() {
+        let color = app.theme.gradient(rep.seconds_per_meter() - min, max - min);
+        table_rows.push(Row::new([
+            Cell::from(format!("{}", count + 1)).set_style(Style::default().bg(color)),
+            Cell::from(app.unit_formatter.distance(rep.distance)),
+            Cell::from(app.unit_formatter.stopwatch_time(rep.time)),
+            Cell::from(app.unit_formatter.pace(rep.time, rep.distance)),
+            Cell::from(
+                rep.average_heartrate
+                    .map_or_else(|| "".to_string(), |hr| format!("{:.0}", hr)),
+            ),
+            Cell::from(
+                workout
+                    .recovery_after(count)
+                    .map_or_else(|| "".to_string(), |r| app.unit_formatter.stopwatch_time(r.time)),
+            ),
+        ]));
+    }
+    let table = Table::new(table_rows, &[
+            Constraint::Length(2),
+            Constraint::Min(7),
+            Constraint::Min(5),
+            Constraint::Min(9),
+            Constraint::Min(3),
+            Constraint::Min(5),
+        ])
+        .header(
+            Row::new(header)
+                .height(1)
+                .bottom_margin(0)
+                .style(Style::default()),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol("");
+    <Table as StatefulWidget>::render(table, rows[0], f, &mut app.activity_view_state.pace_table_state);
+
+    Paragraph::new(Text::from(consistency(&app.unit_formatter, &workout)))
+        .wrap(Wrap { trim: false })
+        .render(rows[1], f);
+}
+
+/// Summary of the workout and how evenly the reps were paced
+fn consistency(formatter: &UnitFormatter, workout: &Workout) -> String {
+    let rep_pace = |index: Option<usize>| {
+        index
+            .and_then(|i| workout.reps.get(i).map(|r| (i, r)))
+            .map_or_else(
+                || "N/A".to_string(),
+                |(i, r)| format!("#{} {}", i + 1, formatter.pace(r.time, r.distance)),
+            )
+    };
+    let mut stats: Vec<(String, String)> = vec![
+        ("Pace Variation".to_string(), format!("{:.1}%", workout.pace_variation() * 100.0)),
+        ("Fastest".to_string(), rep_pace(workout.fastest())),
+        ("Slowest".to_string(), rep_pace(workout.slowest())),
+        ("Fade".to_string(), format!("{:+.1}%", workout.fade() * 100.0)),
+    ];
+    if let Some(drift) = workout.heartrate_drift() {
+        stats.push(("HR Drift".to_string(), format!("{:+.0} bpm", drift)));
+    }
+
+    let mut text = format!("{}\n\n", workout.summary(&formatter.system));
+    for (name, value) in stats {
+        text.push_str(format!("{}: {}\n", name, value).as_str());
+    }
+    text
+}
@@ -0,0 +1,81 @@
+use crossterm::event::KeyCode;
+use tui::{
+    prelude::Buffer,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    app::App,
+    event::{
+        input::InputEvent,
+        keymap::{MappedKey, StravaEvent},
+    },
+    export::route::RouteFormat,
+    ui::centered_rect_absolute,
+};
+
+const FORMATS: [(char, RouteFormat); 3] = [
+    ('g', RouteFormat::Gpx),
+    ('j', RouteFormat::GeoJson),
+    ('k', RouteFormat::Kml),
+];
+
+pub fn handle(app: &mut App, key: MappedKey) {
+    let matched = match key.strava_event {
+        StravaEvent::Enter => {
+            app.activity_view_state.export_dialog = false;
+            true
+        }
+        StravaEvent::Escape => {
+            app.activity_view_state.export_dialog = false;
+            true
+        }
+        _ => false,
+    };
+
+    if matched {
+        return;
+    }
+
+    let format = FORMATS.iter().find_map(|(c, format)| match key.key_event.code {
+        KeyCode::Char(k) if k == *c => Some(format.clone()),
+        _ => None,
+    });
+
+    if let Some(format) = format {
+        app.activity_view_state.export_dialog = false;
+        if let Some(activity) = &app.activity {
+            let id = activity.id;
+            app.send(InputEvent::ExportRoute(id, format));
+        }
+    }
+}
+
+pub fn draw(app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
+    let rect = centered_rect_absolute(64, 3, area);
+    Clear.render(rect, f);
+
+    let strava = app.theme.accent;
+    let mut options = vec![];
+    for (key, format) in FORMATS.iter() {
+        options.push(Span::styled(format!("[{}]", key), Style::default().fg(strava)));
+        options.push(Span::styled(
+            format!("{} ", format.extension()),
+            Style::default().fg(app.theme.text),
+        ));
+    }
+    options.push(Span::styled("<Enter> ".to_string(), Style::default().fg(strava)));
+    options.push(Span::styled("cancel ".to_string(), Style::default().fg(app.theme.text)));
+
+    Paragraph::new(vec![Line::from(options)])
+        .block(
+            Block::default()
+                .title("Export Route")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.dialog))
+                .style(Style::default()),
+        )
+        .render(rect, f);
+}
@@ -0,0 +1,516 @@
+use std::collections::HashMap;
+
+use chrono::DateTime;
+use tui::{
+    layout::{Constraint, Direction, Layout, Margin, Rect},
+    prelude::Buffer,
+    style::{Modifier, Style},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{
+        Axis, Block, Borders, Cell, Chart, Dataset, GraphType, Row, StatefulWidget, Table,
+        TableState, Widget,
+    },
+};
+
+use crate::{
+    app::{ActivePage, App},
+    event::{
+        keymap::{MappedKey, StravaEvent},
+        util::{table_state_click, table_state_next, table_state_prev},
+    },
+    store::activity::{Activities, Segment, SegmentAttempt},
+};
+
+use super::{activity_list::toggle_moving_elapsed, View};
+
+pub struct SegmentLeaderboardState {
+    pub table_state: TableState,
+    pub attempts_table_state: TableState,
+    pub selected_segment: Option<i64>,
+    /// Efforts of the listed activities on the selected segment, fastest first
+    pub attempts: Vec<SegmentAttempt>,
+    /// Page to return to when leaving the efforts, if they were not opened from the segment list
+    pub return_to: Option<ActivePage>,
+    pub table_area: Rect,
+    pub attempts_area: Rect,
+}
+
+impl SegmentLeaderboardState {
+    pub fn new() -> SegmentLeaderboardState {
+        SegmentLeaderboardState {
+            table_state: TableState::default(),
+            attempts_table_state: TableState::default(),
+            selected_segment: None,
+            attempts: vec![],
+            return_to: None,
+            table_area: Rect::default(),
+            attempts_area: Rect::default(),
+        }
+    }
+
+    /// Show the effort history of the given segment, returning to the given page when leaving it.
+    /// The efforts are loaded by `App::drill_into_segment`.
+    pub fn drill_into(&mut self, segment_id: i64, return_to: Option<ActivePage>) {
+        self.selected_segment = Some(segment_id);
+        self.attempts = vec![];
+        self.return_to = return_to;
+        self.attempts_table_state.select(None);
+    }
+}
+
+impl Default for SegmentLeaderboardState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct SegmentLeaderboard {}
+
+impl SegmentLeaderboard {
+    pub(crate) fn new() -> SegmentLeaderboard {
+        SegmentLeaderboard {}
+    }
+}
+
+struct SegmentSummary {
+    segment: Segment,
+    attempts: Vec<SegmentAttempt>,
+}
+
+impl SegmentSummary {
+    fn best(&self, use_moving_time: bool) -> Option<i64> {
+        self.attempts
+            .iter()
+            .map(|a| attempt_time(a, use_moving_time))
+            .min()
+    }
+    fn latest(&self, use_moving_time: bool) -> Option<i64> {
+        self.attempts.last().map(|a| attempt_time(a, use_moving_time))
+    }
+}
+
+/// Attempts ordered by the moving or elapsed time, fastest first
+fn ranked(attempts: &[SegmentAttempt], use_moving_time: bool) -> Vec<SegmentAttempt> {
+    let mut ranked = attempts.to_vec();
+    ranked.sort_by_key(|a| attempt_time(a, use_moving_time));
+    ranked
+}
+
+fn attempt_time(attempt: &SegmentAttempt, use_moving_time: bool) -> i64 {
+    match use_moving_time {
+        true => attempt.effort.moving_time,
+        false => attempt.effort.elapsed_time,
+    }
+}
+
+/// Segments of the listed activities, most attempted first
+fn segment_summaries(activities: &Activities, segments: &HashMap<i64, Segment>) -> Vec<SegmentSummary> {
+    let mut attempts: HashMap<i64, Vec<SegmentAttempt>> = activities.segment_attempts();
+    let mut summaries: Vec<SegmentSummary> = segments
+        .values()
+        .filter_map(|segment| {
+            attempts.remove(&segment.id).map(|attempts| SegmentSummary {
+                segment: segment.clone(),
+                attempts,
+            })
+        })
+        .collect();
+    summaries.sort_by(|a, b| {
+        b.attempts
+            .len()
+            .cmp(&a.attempts.len())
+            .then(a.segment.name.cmp(&b.segment.name))
+    });
+    summaries
+}
+
+impl View for SegmentLeaderboard {
+    fn handle(&mut self, app: &mut App, key: MappedKey) {
+        let summaries = segment_summaries(&app.activities(), &app.segments);
+        let state = &mut app.segment_leaderboard;
+
+        if state.selected_segment.is_some() {
+            let attempts = ranked(&state.attempts, app.activity_list.use_moving_time);
+            match key.strava_event {
+                StravaEvent::Down | StravaEvent::ScrollDown => {
+                    table_state_next(&mut state.attempts_table_state, attempts.len(), false)
+                }
+                StravaEvent::Up | StravaEvent::ScrollUp => {
+                    table_state_prev(&mut state.attempts_table_state, attempts.len(), false)
+                }
+                StravaEvent::Click | StravaEvent::DoubleClick => {
+                    let clicked = key.mouse.and_then(|mouse| {
+                        table_state_click(&mut state.attempts_table_state, state.attempts_area, 2, &mouse, attempts.len())
+                    });
+                    if clicked.is_some() && key.strava_event == StravaEvent::DoubleClick {
+                        self.handle(app, key.with_event(StravaEvent::Enter));
+                    }
+                }
+                StravaEvent::Escape | StravaEvent::Quit => {
+                    state.selected_segment = None;
+                    if let Some(page) = state.return_to.take() {
+                        app.switch_to(page);
+                    }
+                }
+                StravaEvent::MovingElapsed => toggle_moving_elapsed(app),
+                StravaEvent::ToggleUnitSystem => {
+                    app.unit_formatter = app.unit_formatter.toggle();
+                }
+                StravaEvent::Enter => {
+                    let selected = state.attempts_table_state.selected();
+                    let attempt = selected.and_then(|i| attempts.get(i));
+                    if let Some(activity) =
+                        attempt.and_then(|a| app.activities.find(a.activity_id).cloned())
+                    {
+                        app.activity = Some(activity);
+                        app.switch_to(ActivePage::Activity);
+                    }
+                }
+                _ => (),
+            }
+            return;
+        }
+
+        match key.strava_event {
+            StravaEvent::Down | StravaEvent::ScrollDown => {
+                table_state_next(&mut state.table_state, summaries.len(), false)
+            }
+            StravaEvent::Up | StravaEvent::ScrollUp => {
+                table_state_prev(&mut state.table_state, summaries.len(), false)
+            }
+            StravaEvent::Click | StravaEvent::DoubleClick => {
+                let clicked = key.mouse.and_then(|mouse| {
+                    table_state_click(&mut state.table_state, state.table_area, 2, &mouse, summaries.len())
+                });
+                if clicked.is_some() && key.strava_event == StravaEvent::DoubleClick {
+                    self.handle(app, key.with_event(StravaEvent::Enter));
+                }
+            }
+            StravaEvent::Enter => {
+                if let Some(summary) = state.table_state.selected().and_then(|i| summaries.get(i)) {
+                    app.drill_into_segment(summary.segment.id, None);
+                }
+            }
+            StravaEvent::MovingElapsed => toggle_moving_elapsed(app),
+            StravaEvent::ToggleUnitSystem => {
+                app.unit_formatter = app.unit_formatter.toggle();
+            }
+            StravaEvent::ToggleLogView => app.switch_to(ActivePage::LogView),
+            StravaEvent::Quit => app.switch_to(ActivePage::ActivityList),
+            StravaEvent::ToggleSegmentsView => app.switch_to(ActivePage::ActivityList),
+            _ => (),
+        }
+    }
+
+    fn mapped_events(&self, app: &App) -> Vec<StravaEvent> {
+        let mut events = vec![
+            StravaEvent::Down,
+            StravaEvent::Up,
+            StravaEvent::Enter,
+            StravaEvent::ToggleUnitSystem,
+            StravaEvent::MovingElapsed,
+        ];
+        match app.segment_leaderboard.selected_segment {
+            Some(_) => events.push(StravaEvent::Escape),
+            None => events.push(StravaEvent::ToggleLogView),
+        }
+        events.push(StravaEvent::Quit);
+        events
+    }
+
+    fn draw(&mut self, app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
+        if let Some(segment_id) = app.segment_leaderboard.selected_segment {
+            if let Some(segment) = app.segments.get(&segment_id).cloned() {
+                draw_attempts(app, f, area, &segment);
+                return;
+            }
+        }
+
+        let summaries = segment_summaries(&app.activities(), &app.segments);
+
+        if app.segment_leaderboard.table_state.selected().is_none() && !summaries.is_empty() {
+            app.segment_leaderboard.table_state.select(Some(0));
+        }
+
+        let header = ["Name", "Dst", "Attempts", "🏅 PR", "Last"]
+            .iter()
+            .map(|header| Cell::from(Span::styled(*header, Style::default().fg(app.theme.dim))));
+        let use_moving_time = app.activity_list.use_moving_time;
+        let rows: Vec<Row> = summaries
+            .iter()
+            .map(|summary| {
+                Row::new([
+                    Cell::from(summary.segment.name.clone()),
+                    Cell::from(app.unit_formatter.distance(summary.segment.distance)),
+                    Cell::from(format!("{}", summary.attempts.len())),
+                    Cell::from(
+                        summary
+                            .best(use_moving_time)
+                            .map_or_else(|| "".to_string(), |t| app.unit_formatter.stopwatch_time(t)),
+                    ),
+                    Cell::from(
+                        summary
+                            .latest(use_moving_time)
+                            .map_or_else(|| "".to_string(), |t| app.unit_formatter.stopwatch_time(t)),
+                    ),
+                ])
+            })
+            .collect();
+
+        Block::default().title("Segments").borders(Borders::ALL).render(area, f);
+        app.segment_leaderboard.table_area = area.inner(Margin {
+            vertical: 1,
+            horizontal: 1,
+        });
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Percentage(50),
+                Constraint::Min(8),
+                Constraint::Min(8),
+                Constraint::Min(8),
+                Constraint::Min(8),
+            ],
+        )
+        .header(Row::new(header).height(1).bottom_margin(1))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol("");
+        <Table as StatefulWidget>::render(
+            table,
+            app.segment_leaderboard.table_area,
+            f,
+            &mut app.segment_leaderboard.table_state,
+        );
+    }
+}
+
+/// Axis bounds which are at least the padding apart from the value when min and max are equal
+fn padded_bounds(min: f64, max: f64, padding: f64) -> [f64; 2] {
+    match max > min {
+        true => [min, max],
+        false => [min - padding, max + padding],
+    }
+}
+
+fn draw_attempts(app: &mut App, f: &mut Buffer, area: tui::layout::Rect, segment: &Segment) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+        .split(area);
+    let use_moving_time = app.activity_list.use_moving_time;
+    let attempts = ranked(&app.segment_leaderboard.attempts, use_moving_time);
+    let best = attempts.first().map(|a| attempt_time(a, use_moving_time));
+
+    let mut by_date = attempts.clone();
+    by_date.sort_by_key(|a| a.start_date);
+    let data: Vec<(f64, f64)> = by_date
+        .iter()
+        .filter_map(|a| {
+            a.start_date.map(|d| {
+                (
+                    d.and_utc().timestamp() as f64,
+                    attempt_time(a, use_moving_time) as f64,
+                )
+            })
+        })
+        .collect();
+    // a single attempt (or attempts on the same day or in the same time)
+    // would collapse the axis
+    let [tmin, tmax] = padded_bounds(
+        data.iter().map(|(t, _)| *t).fold(f64::MAX, f64::min),
+        data.iter().map(|(t, _)| *t).fold(f64::MIN, f64::max),
+        86400.0,
+    );
+    let [smin, smax] = padded_bounds(
+        data.iter().map(|(_, s)| *s).fold(f64::MAX, f64::min),
+        data.iter().map(|(_, s)| *s).fold(f64::MIN, f64::max),
+        10.0,
+    );
+
+    let title = format!(
+        "{} ({})",
+        segment.name,
+        app.unit_formatter.distance(segment.distance)
+    );
+    if !data.is_empty() {
+        let datasets = vec![
+            Dataset::default()
+                .name("Time")
+                .data(&data)
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(app.theme.chart_line)),
+            Dataset::default()
+                .name("Time")
+                .data(&data)
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Scatter)
+                .style(Style::default().fg(app.theme.chart_points)),
+        ];
+        let date_label = |ts: f64| {
+            Line::from(match DateTime::from_timestamp(ts as i64, 0) {
+                Some(t) => t.format("%Y-%m-%d").to_string(),
+                None => "n/a".to_string(),
+            })
+        };
+        Chart::new(datasets)
+            .hidden_legend_constraints((Constraint::Max(1), Constraint::Max(1)))
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(app.theme.text))
+                    .bounds([tmin, tmax])
+                    .labels(vec![date_label(tmin), date_label(tmax)]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title(Span::styled("Time", Style::default().fg(app.theme.axis_title)))
+                    .style(Style::default().fg(app.theme.text))
+                    .bounds([smin, smax])
+                    .labels(vec![
+                        Span::from(app.unit_formatter.stopwatch_time(smin as i64)),
+                        Span::from(app.unit_formatter.stopwatch_time(smax as i64)),
+                    ]),
+            )
+            .render(rows[0], f);
+    }
+
+    let header = ["Date", "Time", "👣 Pace", "󰓅 Speed", "🏅", "Activity"]
+        .iter()
+        .map(|header| Cell::from(Span::styled(*header, Style::default().fg(app.theme.dim))));
+    let distance = segment.distance;
+    let table_rows: Vec<Row> = attempts
+        .iter()
+        .map(|attempt| {
+            let time = attempt_time(attempt, use_moving_time);
+            Row::new([
+                Cell::from(match attempt.start_date {
+                    Some(d) => d.format("%Y-%m-%d").to_string(),
+                    None => "".to_string(),
+                }),
+                Cell::from(app.unit_formatter.stopwatch_time(time)),
+                Cell::from(app.unit_formatter.pace(time, distance)),
+                Cell::from(app.unit_formatter.speed(distance / (time as f64 / 3600.0))),
+                Cell::from(if Some(time) == best { "PR" } else { "" }),
+                Cell::from(attempt.activity_title.clone()),
+            ])
+        })
+        .collect();
+
+    Block::default().title("Efforts").borders(Borders::ALL).render(rows[1], f);
+    app.segment_leaderboard.attempts_area = rows[1].inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let table = Table::new(
+        table_rows,
+        &[
+            Constraint::Length(10),
+            Constraint::Min(8),
+            Constraint::Min(10),
+            Constraint::Min(10),
+            Constraint::Length(3),
+            Constraint::Percentage(40),
+        ],
+    )
+    .header(Row::new(header).height(1).bottom_margin(1))
+    .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+    .highlight_symbol("");
+    <Table as StatefulWidget>::render(
+        table,
+        app.segment_leaderboard.attempts_area,
+        f,
+        &mut app.segment_leaderboard.attempts_table_state,
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use crate::store::activity::{Activity, ActivitySegmentEffort};
+
+    use super::*;
+
+    fn activity(id: i64, day: u32, efforts: Vec<(i64, i64, i64)>) -> Activity {
+        Activity {
+            id,
+            title: format!("Activity {}", id),
+            start_date: NaiveDate::from_ymd_opt(2024, 1, day).and_then(|d| d.and_hms_opt(8, 0, 0)),
+            segment_efforts: efforts
+                .into_iter()
+                .map(|(segment_id, elapsed_time, moving_time)| ActivitySegmentEffort {
+                    segment_id,
+                    elapsed_time,
+                    moving_time,
+                    pr_rank: None,
+                    kom_rank: None,
+                })
+                .collect(),
+            ..Activity::default()
+        }
+    }
+
+    fn segment(id: i64, name: &str) -> (i64, Segment) {
+        (
+            id,
+            Segment {
+                id,
+                name: name.to_string(),
+                distance: 400.0,
+                activity_type: "Run".to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn summaries() {
+        let activities = Activities::from(vec![
+            activity(3, 20, vec![(10, 110, 105), (11, 300, 290)]),
+            activity(1, 1, vec![(10, 120, 100), (12, 60, 60)]),
+            activity(2, 10, vec![(10, 130, 125), (11, 310, 280)]),
+        ]);
+        // segment 13 has no attempts in the listed activities
+        let segments = HashMap::from([
+            segment(10, "Hill"),
+            segment(11, "Bridge"),
+            segment(12, "Alley"),
+            segment(13, "Track"),
+        ]);
+
+        let summaries = segment_summaries(&activities, &segments);
+        // most attempted first, then by name
+        assert_eq!(
+            vec![("Hill", 3), ("Bridge", 2), ("Alley", 1)],
+            summaries
+                .iter()
+                .map(|s| (s.segment.name.as_str(), s.attempts.len()))
+                .collect::<Vec<(&str, usize)>>()
+        );
+        // attempts are ordered by date
+        assert_eq!(
+            vec![1, 2, 3],
+            summaries[0].attempts.iter().map(|a| a.activity_id).collect::<Vec<i64>>()
+        );
+        assert_eq!((Some(110), Some(110)), (summaries[0].best(false), summaries[0].latest(false)));
+        assert_eq!((Some(100), Some(105)), (summaries[0].best(true), summaries[0].latest(true)));
+        assert_eq!(Some(280), summaries[1].best(true));
+
+        // the efforts of a segment are ranked by the shown time
+        let rank = |use_moving_time: bool| {
+            ranked(&summaries[0].attempts, use_moving_time)
+                .iter()
+                .map(|a| a.activity_id)
+                .collect::<Vec<i64>>()
+        };
+        assert_eq!(vec![3, 1, 2], rank(false));
+        assert_eq!(vec![1, 3, 2], rank(true));
+    }
+
+    #[test]
+    fn bounds_of_a_single_attempt() {
+        assert_eq!([100.0, 120.0], padded_bounds(100.0, 120.0, 10.0));
+        assert_eq!([90.0, 110.0], padded_bounds(100.0, 100.0, 10.0));
+    }
+}
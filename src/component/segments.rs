@@ -1,5 +1,5 @@
 use tui::{
-    layout::Constraint, prelude::Buffer, style::{Color, Modifier, Style, Styled}, text::Span, widgets::{Cell, Row, StatefulWidget, Table}
+    layout::Constraint, prelude::Buffer, style::{Modifier, Style, Styled}, text::Span, widgets::{Cell, Row, StatefulWidget, Table}
 };
 
 use crate::{app::App, store::activity::ActivitySegmentEffort};
@@ -12,10 +12,10 @@ pub fn draw(app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
     let efforts: &Vec<ActivitySegmentEffort> = activity.segment_efforts.as_ref();
 
     let mut rows = vec![];
-    let header = vec!["🏅", "Name", "Dst", "Time", "👣 Pace", "󰓅 Speed"];
+    let header = ["🏅", "Name", "Dst", "Time", "👣 Pace", "󰓅 Speed"];
     let header = header
         .iter()
-        .map(|header| Cell::from(Span::styled(*header, Style::default().fg(Color::DarkGray))));
+        .map(|header| Cell::from(Span::styled(*header, Style::default().fg(app.theme.dim))));
 
     for effort in efforts {
         match app.segments.get(&effort.segment_id) {
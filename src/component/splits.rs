@@ -1,14 +1,11 @@
 
-use std::f64::MAX;
-
-
 use tui::{
     layout::{Constraint},
     style::{Style, Styled, Modifier},
     widgets::{Row, Cell, Table, StatefulWidget}, prelude::Buffer,
 };
 
-use crate::{app::App, store::activity::{ActivitySplit, SportType}, ui::color::{gradient, Rgb}};
+use crate::{app::App, store::activity::{ActivitySplit, SportType}};
 
 pub fn draw(
     app: &mut App,
@@ -19,13 +16,12 @@ pub fn draw(
         return;
     }
     let activity = app.activity.as_ref().unwrap();
-    // TODO: cant use async DB access here
-    let splits: &Vec<ActivitySplit> = activity.splits.as_ref();
+    let splits: &Vec<ActivitySplit> = activity.splits(&app.unit_formatter.system);
     let mut constraints = vec![];
     constraints.push(Constraint::Max(1));
 
     let mut max = 0.0;
-    let mut min = MAX;
+    let mut min = f64::MAX;
     for split in splits.iter() {
         constraints.push(Constraint::Max(1));
         if split.seconds_per_meter() > max {
@@ -51,12 +47,7 @@ pub fn draw(
     let mut count = 0;
     for split in splits {
         count += 1;
-        let color = gradient(
-            Rgb { red: 0, green: 255, blue: 0 },
-            Rgb { red: 255, green: 0, blue: 0 },
-            split.seconds_per_meter() - min,
-            max - min,
-        ).to_color();
+        let color = app.theme.gradient(split.seconds_per_meter() - min, max - min);
         rows.push(
             Row::new([
                 Cell::from(format!("{}", count)).set_style(Style::default().bg(color)),
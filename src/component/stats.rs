@@ -1,6 +1,6 @@
 use tui::{text::Text, widgets::Paragraph, widgets::Widget, prelude::Buffer};
 
-use crate::app::App;
+use crate::{app::App, store::activity::{Activity, Sections, SportType}};
 
 pub fn draw(
     app: &mut App,
@@ -11,7 +11,11 @@ pub fn draw(
         return;
     }
     let activity = app.activity.clone().unwrap();
-    let stats: Vec<(String, String)> = vec![
+    let mut power = match activity.activity_category() {
+        SportType::Ride => power_stats(app.ftp, &activity),
+        _ => vec![],
+    };
+    let mut stats: Vec<(String, String)> = vec![
         (
             "GPS Points".to_string(),
             format!(
@@ -22,11 +26,15 @@ pub fn draw(
                 }
             ),
         ),
-        ("Activity Type".to_string(), format!("{}", activity.activity_type)),
-        ("Sport Type".to_string(), format!("{}", activity.sport_type)),
+        ("Activity Type".to_string(), activity.activity_type.to_string()),
+        ("Sport Type".to_string(), activity.sport_type.to_string()),
         ("Kudos".to_string(), format!("{}", activity.kudos)),
         (
-            "Split".to_string(),
+            match app.activity_view_state.sections {
+                Sections::Splits => "Split".to_string(),
+                Sections::Laps => "Lap".to_string(),
+                Sections::Reps => "Rep".to_string(),
+            },
             match app.activity_view_state.selected_split {
                 Some(s) => format!("{}", s),
                 None => "N/A".to_string()
@@ -40,6 +48,7 @@ pub fn draw(
                 }),
         ),
     ];
+    stats.append(&mut power);
 
     let mut text = String::new();
     if !activity.description.is_empty() {
@@ -50,3 +59,32 @@ pub fn draw(
     }
     Paragraph::new(Text::from(text)).wrap(tui::widgets::Wrap{trim: false}).render(area, f);
 }
+
+pub(crate) fn power_stats(ftp: Option<f64>, activity: &Activity) -> Vec<(String, String)> {
+    let watts = |w: Option<f64>| w.map_or_else(|| "N/A".to_string(), |w| format!("{:.0}W", w));
+    let mut stats = vec![
+        ("Avg Power".to_string(), watts(activity.average_watts)),
+        ("Norm. Power".to_string(), watts(activity.weighted_average_watts)),
+        (
+            "Energy".to_string(),
+            activity
+                .kilojoules
+                .map_or_else(|| "N/A".to_string(), |kj| format!("{:.0}kJ", kj)),
+        ),
+    ];
+    if let Some(ftp) = ftp {
+        stats.push((
+            "IF".to_string(),
+            activity
+                .intensity_factor(ftp)
+                .map_or_else(|| "N/A".to_string(), |i| format!("{:.2}", i)),
+        ));
+        stats.push((
+            "TSS".to_string(),
+            activity
+                .training_stress_score(ftp)
+                .map_or_else(|| "N/A".to_string(), |t| format!("{:.0}", t)),
+        ));
+    }
+    stats
+}
@@ -0,0 +1,201 @@
+use tui::{
+    layout::{Constraint, Direction, Layout, Margin},
+    prelude::Buffer,
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{BarChart, Block, Borders, Cell, Row, StatefulWidget, Table, TableState, Widget},
+};
+
+use crate::{
+    app::{ActivePage, App},
+    event::{
+        keymap::{MappedKey, StravaEvent},
+        util::{table_state_next, table_state_prev},
+    },
+    store::stats::{group_stats, GroupBy, GroupedStats},
+};
+
+use super::{unit_formatter::UnitFormatter, View};
+
+pub const STATS_HEADERS: [&str; 11] = [
+    "Group", "Count", "Dst", "Avg Dst", "Time", "Avg Time", "Elevation", "Pace", "HR",
+    "Prev Year", "YoY",
+];
+
+pub struct StatsViewState {
+    pub table_state: TableState,
+    pub group_by: GroupBy,
+}
+
+impl StatsViewState {
+    pub fn new() -> StatsViewState {
+        StatsViewState {
+            table_state: TableState::default(),
+            group_by: GroupBy::Month,
+        }
+    }
+
+    /// Switch to the next grouping
+    pub fn cycle_group_by(&mut self) {
+        self.group_by = match self.group_by {
+            GroupBy::Week => GroupBy::Month,
+            GroupBy::Month => GroupBy::Year,
+            GroupBy::Year => GroupBy::Type,
+            GroupBy::Type => GroupBy::Gear,
+            GroupBy::Gear => GroupBy::Week,
+        };
+        self.table_state.select(None);
+    }
+}
+
+impl Default for StatsViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Formatted cells of a stats row, shared with the `stats` command
+pub fn stats_row(formatter: &UnitFormatter, stats: &GroupedStats) -> Vec<String> {
+    let group = &stats.group;
+    vec![
+        group.key.clone(),
+        format!("{}", group.count),
+        formatter.distance(group.distance),
+        formatter.distance(group.average_distance()),
+        formatter.stopwatch_time(group.moving_time),
+        formatter.stopwatch_time(group.average_time()),
+        formatter.elevation(group.elevation),
+        formatter.pace(group.moving_time, group.distance),
+        group
+            .average_heartrate()
+            .map_or_else(|| "n/a".to_string(), |hr| format!("{:.0}", hr)),
+        stats
+            .previous_year
+            .as_ref()
+            .map_or_else(|| "".to_string(), |p| formatter.distance(p.distance)),
+        stats
+            .distance_change()
+            .map_or_else(|| "".to_string(), |c| format!("{:+.0}%", c)),
+    ]
+}
+
+pub struct StatsView {}
+
+impl StatsView {
+    pub(crate) fn new() -> StatsView {
+        StatsView {}
+    }
+}
+
+impl View for StatsView {
+    fn handle(&mut self, app: &mut App, key: MappedKey) {
+        let len = group_stats(&app.activities(), &app.stats_view.group_by).len();
+        match key.strava_event {
+            StravaEvent::Down => table_state_next(&mut app.stats_view.table_state, len, false),
+            StravaEvent::Up => table_state_prev(&mut app.stats_view.table_state, len, false),
+            StravaEvent::GroupBy => app.stats_view.cycle_group_by(),
+            StravaEvent::ToggleUnitSystem => {
+                app.unit_formatter = app.unit_formatter.toggle();
+            }
+            StravaEvent::ToggleLogView => app.switch_to(ActivePage::LogView),
+            StravaEvent::Quit => app.switch_to(ActivePage::ActivityList),
+            StravaEvent::ToggleStatsView => app.switch_to(ActivePage::ActivityList),
+            _ => (),
+        }
+    }
+
+    fn mapped_events(&self, _app: &App) -> Vec<StravaEvent> {
+        vec![
+            StravaEvent::Down,
+            StravaEvent::Up,
+            StravaEvent::GroupBy,
+            StravaEvent::ToggleUnitSystem,
+            StravaEvent::ToggleStatsView,
+            StravaEvent::ToggleLogView,
+            StravaEvent::Quit,
+        ]
+    }
+
+    fn draw(&mut self, app: &mut App, f: &mut Buffer, area: tui::layout::Rect) {
+        let stats = group_stats(&app.activities(), &app.stats_view.group_by);
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
+            .split(area);
+
+        // oldest group on the left, the table lists the most recent first
+        let bars: Vec<(String, u64)> = stats
+            .iter()
+            .take((rows[0].width / 9) as usize)
+            .rev()
+            .map(|s| {
+                (
+                    s.group.key.clone(),
+                    app.unit_formatter.distance_value(s.group.distance).round() as u64,
+                )
+            })
+            .collect();
+        let bar_data: Vec<(&str, u64)> = bars.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+        BarChart::default()
+            .block(
+                Block::default()
+                    .title(format!(
+                        "Distance ({}) by {}",
+                        app.unit_formatter.distance_unit(),
+                        app.stats_view.group_by
+                    ))
+                    .borders(Borders::ALL),
+            )
+            .data(&bar_data)
+            .bar_width(8)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(app.theme.accent))
+            .value_style(Style::default().fg(app.theme.on_accent).bg(app.theme.accent))
+            .render(rows[0], f);
+
+        if app.stats_view.table_state.selected().is_none() && !stats.is_empty() {
+            app.stats_view.table_state.select(Some(0));
+        }
+
+        let header = STATS_HEADERS
+            .iter()
+            .map(|header| Cell::from(Span::styled(*header, Style::default().fg(app.theme.dim))));
+        let table_rows: Vec<Row> = stats
+            .iter()
+            .map(|s| Row::new(stats_row(&app.unit_formatter, s)))
+            .collect();
+
+        Block::default()
+            .title(format!("Stats by {}", app.stats_view.group_by))
+            .borders(Borders::ALL)
+            .render(rows[1], f);
+        let table = Table::new(
+            table_rows,
+            &[
+                Constraint::Min(10),
+                Constraint::Min(5),
+                Constraint::Min(10),
+                Constraint::Min(9),
+                Constraint::Min(9),
+                Constraint::Min(8),
+                Constraint::Min(9),
+                Constraint::Min(10),
+                Constraint::Min(4),
+                Constraint::Min(10),
+                Constraint::Min(5),
+            ],
+        )
+        .header(Row::new(header).height(1).bottom_margin(1))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol("");
+        <Table as StatefulWidget>::render(
+            table,
+            rows[1].inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            f,
+            &mut app.stats_view.table_state,
+        );
+    }
+}
@@ -1,16 +1,40 @@
-use std::{fmt::Display, f64::INFINITY};
+use std::{fmt::Display, str::FromStr};
 
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug)]
 pub struct UnitFormatter {
     pub system: UnitSystem,
+    /// Unit system of elevations when it differs from that of distances (e.g. miles and meters)
+    pub elevation_system: Option<UnitSystem>,
+    /// Number of decimal places of distances, elevations and speeds
+    pub precision: usize,
+    pub time_format: TimeFormat,
+    /// Separator of the decimal places, e.g. `,` for `10,5km`
+    pub decimal_separator: char,
 }
 pub const KILOMETER_TO_MILE: f64 = 0.621371;
 const METERS_TO_FOOT: f64 = 3.28084;
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum UnitSystem {
     Metric,
     Imperial,
 }
 
+impl FromStr for UnitSystem {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "metric" => Ok(UnitSystem::Metric),
+            "imperial" => Ok(UnitSystem::Imperial),
+            _ => Err(format!("unknown unit system `{}`, expected metric or imperial", s)),
+        }
+    }
+}
+
 impl Display for UnitSystem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match *self {
@@ -20,46 +44,91 @@ impl Display for UnitSystem {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimeFormat {
+    /// `05:00` or `01:23:20`
+    Stopwatch,
+    /// `00:05:00` or `01:23:20`
+    Full,
+    /// `5m 00s` or `1h 23m 20s`
+    Human,
+}
+
+impl FromStr for TimeFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stopwatch" => Ok(TimeFormat::Stopwatch),
+            "full" => Ok(TimeFormat::Full),
+            "human" => Ok(TimeFormat::Human),
+            _ => Err(format!("unknown time format `{}`, expected stopwatch, full or human", s)),
+        }
+    }
+}
+
+impl Default for UnitFormatter {
+    fn default() -> Self {
+        UnitFormatter {
+            system: UnitSystem::Imperial,
+            elevation_system: None,
+            precision: 2,
+            time_format: TimeFormat::Stopwatch,
+            decimal_separator: '.',
+        }
+    }
+}
+
 impl UnitFormatter {
     pub fn stopwatch_time(&self, seconds: i64) -> String {
-        if seconds == 0 {
-            return "00:00".to_string();
-        }
         let hours = seconds / 3600;
         let rem = seconds % 3600;
         let minutes = rem / 60;
         let seconds = rem % 60;
-        if hours == 0 {
-            return format!("{:0>2}:{:0>2}", minutes, seconds);
+        match (&self.time_format, hours) {
+            (TimeFormat::Stopwatch, 0) => format!("{:0>2}:{:0>2}", minutes, seconds),
+            (TimeFormat::Stopwatch | TimeFormat::Full, _) => {
+                format!("{:0>2}:{:0>2}:{:0>2}", hours, minutes, seconds)
+            }
+            (TimeFormat::Human, 0) => format!("{}m {:0>2}s", minutes, seconds),
+            (TimeFormat::Human, _) => format!("{}h {:0>2}m {:0>2}s", hours, minutes, seconds),
         }
-        format!("{:0>2}:{:0>2}:{:0>2}", hours, minutes, seconds)
+    }
+
+    /// Signed stopwatch time, e.g. `+01:05` or `-00:12`
+    pub fn stopwatch_delta(&self, seconds: i64) -> String {
+        let sign = if seconds < 0 { "-" } else { "+" };
+        format!("{}{}", sign, self.stopwatch_time(seconds.abs()))
     }
 
     pub fn distance(&self, quantity: f64) -> String {
-        match self.system {
-            UnitSystem::Metric => {
-                format!("{:.2}km", (quantity / 1000.0))
-            }
-            UnitSystem::Imperial => {
-                format!("{:.2}mi", ((quantity / 1000.0) * KILOMETER_TO_MILE))
-            }
-        }
+        format!(
+            "{}{}",
+            self.number(self.distance_value(quantity), self.precision),
+            self.distance_unit()
+        )
     }
 
     pub fn elevation(&self, elevation: f64) -> String {
-        match self.system {
-            UnitSystem::Metric => {
-                format!("{:.2}m", elevation)
-            }
-            UnitSystem::Imperial => {
-                format!("{:.2}ft", elevation * METERS_TO_FOOT)
-            }
+        format!(
+            "{}{}",
+            self.number(self.elevation_value(elevation), self.precision),
+            self.elevation_unit()
+        )
+    }
+
+    /// Number with the given decimal places and the configured decimal separator
+    pub fn number(&self, value: f64, precision: usize) -> String {
+        let formatted = format!("{:.*}", precision, value);
+        match self.decimal_separator {
+            '.' => formatted,
+            separator => formatted.replacen('.', &separator.to_string(), 1),
         }
     }
 
     pub fn pace(&self, time: i64, meters: f64) -> String {
         let spm = time as f64 / meters;
-        if spm == INFINITY {
+        if spm == f64::INFINITY {
             return "N/A".to_string();
         }
         match self.system {
@@ -75,37 +144,104 @@ impl UnitFormatter {
         }
     }
 
-    pub(crate) fn imperial() -> Self {
+    /// Difference between two paces per kilometer or mile
+    pub fn pace_delta(&self, time: i64, meters: f64, other_time: i64, other_meters: f64) -> String {
+        let delta = (other_time as f64 / other_meters) - (time as f64 / meters);
+        if !delta.is_finite() {
+            return "N/A".to_string();
+        }
+        let per_unit = match self.system {
+            UnitSystem::Metric => delta * 1000.0,
+            UnitSystem::Imperial => (delta * 1000.0) / KILOMETER_TO_MILE,
+        };
+        self.stopwatch_delta(per_unit.round() as i64)
+    }
+
+    /// Distance in kilometers or miles
+    pub fn distance_value(&self, meters: f64) -> f64 {
+        match self.system {
+            UnitSystem::Metric => meters / 1000.0,
+            UnitSystem::Imperial => (meters / 1000.0) * KILOMETER_TO_MILE,
+        }
+    }
+
+    pub fn distance_unit(&self) -> &'static str {
+        match self.system {
+            UnitSystem::Metric => "km",
+            UnitSystem::Imperial => "mi",
+        }
+    }
+
+    fn elevation_system(&self) -> &UnitSystem {
+        self.elevation_system.as_ref().unwrap_or(&self.system)
+    }
+
+    /// Elevation in meters or feet
+    pub fn elevation_value(&self, meters: f64) -> f64 {
+        match self.elevation_system() {
+            UnitSystem::Metric => meters,
+            UnitSystem::Imperial => meters * METERS_TO_FOOT,
+        }
+    }
+
+    pub fn elevation_unit(&self) -> &'static str {
+        match self.elevation_system() {
+            UnitSystem::Metric => "m",
+            UnitSystem::Imperial => "ft",
+        }
+    }
+
+    /// Pace in seconds per kilometer or mile
+    pub fn pace_value(&self, time: i64, meters: f64) -> f64 {
+        time as f64 / self.distance_value(meters)
+    }
+
+    /// Speed in kilometers or miles per hour
+    pub fn speed_value(&self, meters_per_hour: f64) -> f64 {
+        self.distance_value(meters_per_hour)
+    }
+
+    pub fn speed_unit(&self) -> &'static str {
+        match self.system {
+            UnitSystem::Metric => "kmph",
+            UnitSystem::Imperial => "mph",
+        }
+    }
+
+    pub(crate) fn new(system: UnitSystem) -> Self {
         UnitFormatter {
-            system: UnitSystem::Imperial,
+            system,
+            ..UnitFormatter::default()
         }
     }
+
+    pub(crate) fn imperial() -> Self {
+        UnitFormatter::new(UnitSystem::Imperial)
+    }
+
+    /// Switch the unit system of distances, a configured elevation system is kept
     pub(crate) fn toggle(&self) -> UnitFormatter {
         UnitFormatter {
             system: match self.system {
                 UnitSystem::Metric => UnitSystem::Imperial,
                 UnitSystem::Imperial => UnitSystem::Metric,
             },
+            ..self.clone()
         }
     }
 
-    #[allow(unused)]
     pub(crate) fn speed(&self, meters_per_hour: f64) -> String {
-        let kmph = meters_per_hour / 1000.0;
-        match self.system {
-            UnitSystem::Metric => {
-                format!("{:.2}kmph", kmph)
-            }
-            UnitSystem::Imperial => {
-                format!("{:.2}mph", kmph * 0.621371)
-            }
-        }
+        format!(
+            "{}{}",
+            self.number(self.speed_value(meters_per_hour), self.precision),
+            self.speed_unit()
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::component::unit_formatter::UnitFormatter;
+    use crate::component::unit_formatter::{TimeFormat, UnitFormatter, UnitSystem};
 
     #[test]
     fn test_stopwatch_time() {
@@ -116,9 +252,61 @@ mod tests {
         assert_eq!("01:00:00", f.stopwatch_time(3600));
     }
 
+    #[test]
+    fn test_time_formats() {
+        let mut f = UnitFormatter::imperial();
+        f.time_format = TimeFormat::Full;
+        assert_eq!("00:00:30", f.stopwatch_time(30));
+        f.time_format = TimeFormat::Human;
+        assert_eq!("0m 30s", f.stopwatch_time(30));
+        assert_eq!("1h 23m 20s", f.stopwatch_time(5000));
+        assert_eq!("5m 00s /km", f.toggle().pace(300, 1000.0));
+    }
+
     #[test]
     fn test_pace() {
         let f = UnitFormatter::imperial();
         assert_eq!("03:13 /mi", f.pace(120, 1000.0));
+        assert_eq!("02:00 /km", f.toggle().pace(120, 1000.0));
+    }
+
+    #[test]
+    fn test_mixed_systems_and_precision() {
+        let f = UnitFormatter {
+            elevation_system: Some(UnitSystem::Metric),
+            precision: 1,
+            ..UnitFormatter::imperial()
+        };
+        assert_eq!("6.2mi", f.distance(10000.0));
+        assert_eq!("100.0m", f.elevation(100.0));
+        assert_eq!("12.4mph", f.speed(20000.0));
+        assert_eq!("10.0km", f.toggle().distance(10000.0));
+        assert_eq!("100.0m", f.toggle().elevation(100.0));
+    }
+
+    #[test]
+    fn test_decimal_separator() {
+        let f = UnitFormatter {
+            decimal_separator: ',',
+            precision: 1,
+            ..UnitFormatter::imperial().toggle()
+        };
+        assert_eq!("10,0km", f.distance(10000.0));
+        assert_eq!("1234,5m", f.elevation(1234.5));
+        assert_eq!("20,0kmph", f.speed(20000.0));
+        assert_eq!("6,2mi", f.toggle().distance(10000.0));
+        assert_eq!("-1,25", f.number(-1.25, 2));
+        assert_eq!("42", f.number(42.0, 0));
+        // a point by default
+        assert_eq!("10.00km", UnitFormatter::new(UnitSystem::Metric).distance(10000.0));
+    }
+
+    #[test]
+    fn test_deltas() {
+        let f = UnitFormatter::imperial().toggle();
+        assert_eq!("+01:05", f.stopwatch_delta(65));
+        assert_eq!("-00:12", f.stopwatch_delta(-12));
+        assert_eq!("-00:10", f.pace_delta(300, 1000.0, 290, 1000.0));
+        assert_eq!("N/A", f.pace_delta(300, 0.0, 290, 1000.0));
     }
 }
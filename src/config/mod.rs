@@ -2,11 +2,105 @@ use confy::get_configuration_file_path;
 use serde::{Deserialize, Serialize};
 use clap::Parser;
 
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
+
+use crate::{
+    cli::Command,
+    client::{DEFAULT_API_URL, DEFAULT_OAUTH_URL},
+    component::{
+        activity_list::columns::{ListColumn, ListColumns},
+        unit_formatter::UnitFormatter,
+    },
+    event::keymap::{KeyMap, KeymapEntry},
+    export::activities::ExportColumn,
+    expr::evaluator::Evaluator,
+    store::{
+        gear::{RetirementThresholds, DEFAULT_SHOE_RETIREMENT},
+        goal::Goal,
+        session::Session,
+    },
+    sync::{reconcile::ReconcileOptions, webhook::WebhookConfig},
+    ui::color::{Rgb, Theme},
+};
+
 #[derive(Default, Debug, Serialize, Deserialize)]
 pub struct ConfigFile {
     pub client_id: Option<String>,
     pub client_secret: Option<String>,
     pub activity_type: Option<String>,
+    pub ftp: Option<f64>,
+    pub export_columns: Option<Vec<String>>,
+    /// Directory exported files are written to, defaults to the current directory
+    pub export_dir: Option<String>,
+    pub goals: Option<Vec<Goal>>,
+    pub keymap: Option<BTreeMap<String, KeymapEntry>>,
+    pub theme: Option<ThemeConfig>,
+    pub list_columns: Option<BTreeMap<String, Vec<String>>>,
+    pub mouse: Option<bool>,
+    pub defaults: Option<DefaultsConfig>,
+    pub units: Option<UnitsConfig>,
+    /// Minutes between automatic syncs, 0 to only sync on start and when requested
+    pub sync_interval: Option<u64>,
+    pub sync_concurrency: Option<usize>,
+    pub reconcile: Option<ReconcileConfig>,
+    pub webhook: Option<WebhookFileConfig>,
+    pub api: Option<ApiConfig>,
+    pub gear: Option<GearConfig>,
+}
+
+/// Retirement distances in meters, 0 to disable
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct GearConfig {
+    pub shoe_retirement: Option<f64>,
+    pub bike_retirement: Option<f64>,
+    /// Retirement distances of individual gear by name or ID
+    pub retirement: Option<BTreeMap<String, f64>>,
+}
+
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct ApiConfig {
+    pub base_url: Option<String>,
+    pub oauth_url: Option<String>,
+}
+
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct WebhookFileConfig {
+    pub address: Option<String>,
+    pub verify_token: Option<String>,
+    pub subscription_id: Option<i64>,
+}
+
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct ReconcileConfig {
+    pub days: Option<u64>,
+    pub on_sync: Option<bool>,
+    pub remove_deleted: Option<bool>,
+}
+
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct UnitsConfig {
+    pub system: Option<String>,
+    pub elevation: Option<String>,
+    pub precision: Option<usize>,
+    pub time_format: Option<String>,
+    pub decimal_separator: Option<String>,
+}
+
+/// Initial state of the activity list, used until a session has been saved
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct DefaultsConfig {
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+    pub rank_by: Option<String>,
+    pub rank_order: Option<String>,
+    pub filter: Option<String>,
+}
+
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    pub name: Option<String>,
+    pub gradient_start: Option<String>,
+    pub gradient_end: Option<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -18,16 +112,38 @@ struct Args {
     pub client_id: Option<String>,
     #[arg(long)]
     pub client_secret: Option<String>,
+    #[arg(long)]
+    pub ftp: Option<f64>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
 }
 
 pub struct Config {
     pub activity_type: Option<String>,
     pub client_id: String,
     pub client_secret: String,
+    pub ftp: Option<f64>,
+    pub export_columns: Vec<ExportColumn>,
+    pub export_dir: PathBuf,
+    pub goals: Vec<Goal>,
+    pub key_map: KeyMap,
+    pub theme: Theme,
+    pub list_columns: BTreeMap<String, Vec<ListColumn>>,
+    pub mouse: bool,
+    pub session: Session,
+    pub units: UnitFormatter,
+    pub sync_interval: Option<Duration>,
+    pub sync_concurrency: usize,
+    pub reconcile: ReconcileOptions,
+    pub webhook: Option<WebhookConfig>,
+    pub api_url: String,
+    pub oauth_url: String,
+    pub gear_retirement: RetirementThresholds,
+    pub command: Option<Command>,
 }
 
 pub enum ConfigResult {
-    Ok(Config),
+    Ok(Box<Config>),
     Instructions(String)
 }
 
@@ -55,9 +171,213 @@ And run the application again, alternatively you can use the `--client-id` and `
                                    ", get_configuration_file_path("strava-rs", "config").unwrap().to_str().unwrap()))
     }
 
-    ConfigResult::Ok(Config {
+    let export_columns = match config.export_columns {
+        Some(names) => match names.iter().map(|n| n.parse()).collect() {
+            Ok(columns) => columns,
+            Err(e) => return ConfigResult::Instructions(format!("Invalid `export_columns` in config: {}", e)),
+        },
+        None => ExportColumn::defaults(),
+    };
+
+    let ftp = args.ftp.or(config.ftp);
+    if let Some(ftp) = ftp.filter(|ftp| *ftp <= 0.0) {
+        return ConfigResult::Instructions(format!("Invalid `ftp` in config: must be positive, got {}", ftp));
+    }
+
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let export_dir = match config.export_dir {
+        Some(dir) => cwd.join(dir),
+        None => cwd,
+    };
+    if !export_dir.is_dir() {
+        return ConfigResult::Instructions(format!("Invalid `export_dir` in config: {} is not a directory", export_dir.display()));
+    }
+
+    let goals = config.goals.unwrap_or_default();
+    for goal in goals.iter() {
+        if let Some(filter) = &goal.filter {
+            if let Err(e) = Evaluator::new().parse(filter) {
+                return ConfigResult::Instructions(format!("Invalid filter for goal `{}`: {}", goal.name, e));
+            }
+        }
+    }
+
+    let key_map = match KeyMap::from_config(&config.keymap.unwrap_or_default()) {
+        Ok(key_map) => key_map,
+        Err(errors) => {
+            return ConfigResult::Instructions(format!(
+                "Invalid key bindings in config:\n\n{}",
+                errors.iter().map(|e| format!("  - {}", e)).collect::<Vec<String>>().join("\n")
+            ))
+        }
+    };
+
+    let theme = match load_theme(config.theme.unwrap_or_default()) {
+        Ok(theme) => theme,
+        Err(e) => return ConfigResult::Instructions(format!("Invalid `theme` in config: {}", e)),
+    };
+
+    let list_columns = match ListColumns::parse_config(&config.list_columns.unwrap_or_default()) {
+        Ok(columns) => columns,
+        Err(e) => return ConfigResult::Instructions(format!("Invalid `list_columns` in config: {}", e)),
+    };
+
+    let units = match load_units(config.units.unwrap_or_default()) {
+        Ok(units) => units,
+        Err(e) => return ConfigResult::Instructions(format!("Invalid `units` in config: {}", e)),
+    };
+
+    let session = match load_defaults(config.defaults.unwrap_or_default()) {
+        Ok(session) => Session {
+            unit_system: units.system.clone(),
+            ..session
+        },
+        Err(e) => return ConfigResult::Instructions(format!("Invalid `defaults` in config: {}", e)),
+    };
+
+    let webhook = match config.webhook {
+        Some(WebhookFileConfig { verify_token: None, .. }) => {
+            return ConfigResult::Instructions("Invalid `webhook` in config: webhook.verify_token is required when [webhook] is set".to_string())
+        }
+        Some(webhook) => match webhook.address.as_deref().unwrap_or("127.0.0.1:8113").parse() {
+            Ok(address) => Some(WebhookConfig {
+                address,
+                verify_token: webhook.verify_token.unwrap_or_default(),
+                subscription_id: webhook.subscription_id,
+            }),
+            Err(e) => return ConfigResult::Instructions(format!("Invalid `webhook` address in config: {}", e)),
+        },
+        None => None,
+    };
+
+    let sync_concurrency = match config.sync_concurrency.unwrap_or(4) {
+        n @ 1..=16 => n,
+        n => return ConfigResult::Instructions(format!("Invalid `sync_concurrency` in config: must be between 1 and 16, got {}", n)),
+    };
+
+    let gear_retirement = match load_gear_retirement(config.gear.unwrap_or_default()) {
+        Ok(thresholds) => thresholds,
+        Err(e) => return ConfigResult::Instructions(format!("Invalid `gear` in config: {}", e)),
+    };
+
+    let api = config.api.unwrap_or_default();
+    let api_url = api.base_url.unwrap_or(DEFAULT_API_URL.to_string());
+    let oauth_url = api.oauth_url.unwrap_or(DEFAULT_OAUTH_URL.to_string());
+    for url in [&api_url, &oauth_url] {
+        if let Err(e) = url::Url::parse(url) {
+            return ConfigResult::Instructions(format!("Invalid `api` URL `{}` in config: {}", url, e));
+        }
+    }
+
+    ConfigResult::Ok(Box::new(Config {
         activity_type: args.activity_type.or(config.activity_type),
         client_id: args.client_id.or(config.client_id).unwrap(),
-        client_secret:args.client_secret.or(config.client_secret).unwrap() 
+        client_secret:args.client_secret.or(config.client_secret).unwrap(),
+        ftp,
+        export_columns,
+        export_dir,
+        goals,
+        key_map,
+        theme,
+        list_columns,
+        mouse: config.mouse.unwrap_or(true),
+        session,
+        units,
+        sync_interval: match config.sync_interval {
+            Some(0) | None => None,
+            Some(minutes) => Some(Duration::from_secs(minutes * 60)),
+        },
+        sync_concurrency,
+        reconcile: match config.reconcile {
+            Some(reconcile) => ReconcileOptions {
+                days: reconcile.days,
+                on_sync: reconcile.on_sync.unwrap_or(false),
+                remove_deleted: reconcile.remove_deleted.unwrap_or(false),
+            },
+            None => ReconcileOptions::default(),
+        },
+        webhook,
+        api_url: api_url.trim_end_matches('/').to_string(),
+        oauth_url: oauth_url.trim_end_matches('/').to_string(),
+        gear_retirement,
+        command: args.command,
+    }))
+}
+
+fn load_gear_retirement(config: GearConfig) -> Result<RetirementThresholds, String> {
+    let retirement = config.retirement.unwrap_or_default();
+    let distances = [config.shoe_retirement, config.bike_retirement]
+        .into_iter()
+        .flatten()
+        .chain(retirement.values().copied());
+    for distance in distances {
+        if distance < 0.0 {
+            return Err(format!("retirement distance must not be negative, got {}", distance));
+        }
+    }
+    // a distance of 0 disables the warning
+    let threshold = |distance: f64| (distance > 0.0).then_some(distance);
+    Ok(RetirementThresholds {
+        shoe: threshold(config.shoe_retirement.unwrap_or(DEFAULT_SHOE_RETIREMENT)),
+        bike: config.bike_retirement.and_then(threshold),
+        gear: retirement.into_iter().collect(),
     })
 }
+
+fn load_theme(config: ThemeConfig) -> Result<Theme, String> {
+    let mut theme = Theme::from_name(config.name.as_deref().unwrap_or("dark"))?;
+    if let Some(start) = config.gradient_start {
+        theme.gradient_start = start.parse::<Rgb>()?;
+    }
+    if let Some(end) = config.gradient_end {
+        theme.gradient_end = end.parse::<Rgb>()?;
+    }
+    Ok(theme)
+}
+
+fn load_defaults(config: DefaultsConfig) -> Result<Session, String> {
+    let mut session = Session::default();
+    if let Some(sort_by) = config.sort_by {
+        session.sort_by = sort_by.parse()?;
+    }
+    if let Some(sort_order) = config.sort_order {
+        session.sort_order = sort_order.parse()?;
+    }
+    if let Some(rank_by) = config.rank_by {
+        session.rank_by = rank_by.parse()?;
+    }
+    if let Some(rank_order) = config.rank_order {
+        session.rank_order = rank_order.parse()?;
+    }
+    if let Some(filter) = config.filter {
+        session.filter = filter;
+    }
+    Ok(session)
+}
+
+fn load_units(config: UnitsConfig) -> Result<UnitFormatter, String> {
+    let mut units = UnitFormatter::default();
+    if let Some(system) = config.system {
+        units.system = system.parse()?;
+    }
+    if let Some(elevation) = config.elevation {
+        units.elevation_system = Some(elevation.parse()?);
+    }
+    if let Some(precision) = config.precision {
+        if precision > 6 {
+            return Err(format!("precision must be between 0 and 6, got {}", precision));
+        }
+        units.precision = precision;
+    }
+    if let Some(time_format) = config.time_format {
+        units.time_format = time_format.parse()?;
+    }
+    if let Some(separator) = config.decimal_separator {
+        let mut chars = separator.chars();
+        units.decimal_separator = match (chars.next(), chars.next()) {
+            (Some(c), None) if !c.is_ascii_digit() && c != '-' => c,
+            _ => return Err(format!("decimal_separator must be a single character other than a digit or `-`, got `{}`", separator)),
+        };
+    }
+    Ok(units)
+}
@@ -1,16 +1,24 @@
 use std::{time::Duration, thread};
 
-use crossterm::event::{Event, KeyEvent, self, poll};
+use crossterm::event::{Event, KeyEvent, MouseEvent, MouseEventKind, self, poll};
 use tokio::sync::mpsc::Sender;
 
+use crate::{export::route::RouteFormat, sync::status::SyncStatus};
+
 #[derive(Debug)]
 pub enum InputEvent {
     Input(KeyEvent),
+    Mouse(MouseEvent),
     Tick,
     InfoMessage(String),
     ErrorMessage(String),
     Reload,
     Sync,
+    Reconcile,
+    SyncStatus(SyncStatus),
+    ExportRoute(i64, RouteFormat),
+    /// Load the efforts on the segment shown in the leaderboard
+    SegmentEfforts(i64),
 }
 
 pub type EventSender = Sender<InputEvent>;
@@ -19,8 +27,13 @@ pub fn start(event_sender: EventSender) {
     thread::spawn(move || {
         loop {
             if poll(Duration::from_millis(20)).unwrap() {
-                if let Event::Key(key) = event::read().unwrap() {
-                    event_sender.blocking_send(InputEvent::Input(key)).unwrap();
+                match event::read().unwrap() {
+                    Event::Key(key) => event_sender.blocking_send(InputEvent::Input(key)).unwrap(),
+                    // pointer movement without a button pressed is not used
+                    Event::Mouse(mouse) if mouse.kind != MouseEventKind::Moved => {
+                        event_sender.blocking_send(InputEvent::Mouse(mouse)).unwrap()
+                    }
+                    _ => (),
                 }
             }
 
@@ -1,52 +1,356 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    str::FromStr,
+};
 
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use super::mouse::MouseInput;
+use strum::{AsRefStr, EnumIter, EnumString};
+
+/// Names of the views which can have their own key bindings
+pub const VIEWS: [&str; 9] = [
+    "list",
+    "activity",
+    "segments",
+    "log",
+    "power",
+    "comparison",
+    "leaderboard",
+    "stats",
+    "gear",
+];
+
+/// A key with its modifiers, e.g. `ctrl-n`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Normalize the chord so that it matches what the terminal sends: the
+    /// shift of a character is part of the character and shift-tab is a
+    /// back tab.
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+        KeyChord { code, modifiers }
+    }
+
+    pub fn plain(code: KeyCode) -> KeyChord {
+        KeyChord::new(code, KeyModifiers::NONE)
+    }
+}
+
+impl From<&KeyEvent> for KeyChord {
+    fn from(ke: &KeyEvent) -> Self {
+        KeyChord::new(ke.code, ke.modifiers)
+    }
+}
+
+const NAMED_KEYS: [(&str, KeyCode); 15] = [
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+];
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (prefix, key) = if s.chars().count() == 1 {
+            ("", s)
+        } else if let Some(prefix) = s.strip_suffix("--") {
+            (prefix, "-")
+        } else {
+            s.rsplit_once('-').unwrap_or(("", s))
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('-').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier `{}` in key `{}`", modifier, s)),
+            };
+        }
+
+        let lower = key.to_lowercase();
+        let code = if key.chars().count() == 1 {
+            KeyCode::Char(key.chars().next().unwrap())
+        } else if lower == "space" {
+            KeyCode::Char(' ')
+        } else if let Some((_, code)) = NAMED_KEYS.iter().find(|(name, _)| *name == lower) {
+            *code
+        } else if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            KeyCode::F(n)
+        } else {
+            return Err(format!("unknown key `{}`", s));
+        };
+
+        Ok(KeyChord::new(code, modifiers))
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            KeyCode::BackTab => write!(f, "shift-tab"),
+            code => match NAMED_KEYS.iter().find(|(_, c)| *c == code) {
+                Some((name, _)) => write!(f, "{}", name),
+                None => write!(f, "{:?}", code),
+            },
+        }
+    }
+}
+
+/// An entry of the `[keymap]` config section: either a key bound to an event
+/// name or a table of bindings for one view.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum KeymapEntry {
+    Event(String),
+    View(BTreeMap<String, String>),
+}
 
 pub struct KeyMap {
-    map: HashMap<KeyCode, StravaEvent>
+    map: HashMap<KeyChord, StravaEvent>,
+    views: HashMap<String, HashMap<KeyChord, StravaEvent>>,
 }
 
-impl KeyMap {
-    pub fn default() -> KeyMap {
+impl Default for KeyMap {
+    fn default() -> KeyMap {
         let mut map = HashMap::new();
-        map.insert(KeyCode::Char('q'), StravaEvent::Quit);
-        map.insert(KeyCode::Char('k'), StravaEvent::Up);
-        map.insert(KeyCode::Char('j'), StravaEvent::Down);
-        map.insert(KeyCode::Char('K'), StravaEvent::PageUp);
-        map.insert(KeyCode::Char('J'), StravaEvent::PageDown);
-        map.insert(KeyCode::Char('n'), StravaEvent::Next);
-        map.insert(KeyCode::Char('p'), StravaEvent::Previous);
-        map.insert(KeyCode::Char('m'), StravaEvent::MovingElapsed);
-        map.insert(KeyCode::Char('o'), StravaEvent::ToggleSortOrder);
-        map.insert(KeyCode::Char('u'), StravaEvent::ToggleUnitSystem);
-        map.insert(KeyCode::Char('s'), StravaEvent::Sort);
-        map.insert(KeyCode::Char('S'), StravaEvent::Rank);
-        map.insert(KeyCode::Char('f'), StravaEvent::Filter);
-        map.insert(KeyCode::Char('r'), StravaEvent::Refresh);
-        map.insert(KeyCode::Char('a'), StravaEvent::Anchor);
-        map.insert(KeyCode::Char('+'), StravaEvent::IncreaseTolerance);
-        map.insert(KeyCode::Char('-'), StravaEvent::DecreaseTolerance);
-        map.insert(KeyCode::Char('0'), StravaEvent::ToggleLogView);
-        map.insert(KeyCode::Enter, StravaEvent::Enter);
-        map.insert(KeyCode::Esc, StravaEvent::Escape);
-        map.insert(KeyCode::Char('e'), StravaEvent::ToggleSegmentEffortsView);
-        KeyMap{map}
-    }
-
-    pub fn map_key(&self, ke: KeyEvent) -> MappedKey {
-        match self.map.get(&ke.code) {
-            Some(event) => new_strava_key(ke, event.clone()),
-            None => new_strava_key(ke, StravaEvent::None),
+        let mut bind = |code: KeyCode, event: StravaEvent| map.insert(KeyChord::plain(code), event);
+        bind(KeyCode::Char('q'), StravaEvent::Quit);
+        bind(KeyCode::Char('k'), StravaEvent::Up);
+        bind(KeyCode::Char('j'), StravaEvent::Down);
+        bind(KeyCode::Char('K'), StravaEvent::PageUp);
+        bind(KeyCode::Char('J'), StravaEvent::PageDown);
+        bind(KeyCode::Char('n'), StravaEvent::Next);
+        bind(KeyCode::Char('p'), StravaEvent::Previous);
+        bind(KeyCode::Char('m'), StravaEvent::MovingElapsed);
+        bind(KeyCode::Char('o'), StravaEvent::ToggleSortOrder);
+        bind(KeyCode::Char('u'), StravaEvent::ToggleUnitSystem);
+        bind(KeyCode::Char('s'), StravaEvent::Sort);
+        bind(KeyCode::Char('S'), StravaEvent::Rank);
+        bind(KeyCode::Char('f'), StravaEvent::Filter);
+        bind(KeyCode::Char('r'), StravaEvent::Refresh);
+        bind(KeyCode::Char('R'), StravaEvent::Reconcile);
+        bind(KeyCode::Char('a'), StravaEvent::Anchor);
+        bind(KeyCode::Char('+'), StravaEvent::IncreaseTolerance);
+        bind(KeyCode::Char('-'), StravaEvent::DecreaseTolerance);
+        bind(KeyCode::Char('0'), StravaEvent::ToggleLogView);
+        bind(KeyCode::Enter, StravaEvent::Enter);
+        bind(KeyCode::Esc, StravaEvent::Escape);
+        bind(KeyCode::Char('e'), StravaEvent::ToggleSegmentEffortsView);
+        bind(KeyCode::Char('w'), StravaEvent::TogglePowerCurveView);
+        bind(KeyCode::Char('x'), StravaEvent::Mark);
+        bind(KeyCode::Char('c'), StravaEvent::Compare);
+        bind(KeyCode::Char('L'), StravaEvent::ToggleSegmentsView);
+        bind(KeyCode::Char('l'), StravaEvent::ToggleLaps);
+        bind(KeyCode::Char('i'), StravaEvent::ToggleReps);
+        bind(KeyCode::Char('E'), StravaEvent::Export);
+        bind(KeyCode::Char('T'), StravaEvent::ToggleStatsView);
+        bind(KeyCode::Char('G'), StravaEvent::ToggleGearView);
+        bind(KeyCode::Char('g'), StravaEvent::GroupBy);
+        bind(KeyCode::Char('?'), StravaEvent::Help);
+        bind(KeyCode::Char('C'), StravaEvent::Columns);
+        KeyMap{map, views: HashMap::new()}
+    }
+}
+
+/// Parse the bindings of one config section, reporting keys which can not be
+/// parsed and different spellings of the same key bound to different events.
+fn parse_bindings(
+    section: &str,
+    bindings: &BTreeMap<String, String>,
+    errors: &mut Vec<String>,
+) -> HashMap<KeyChord, StravaEvent> {
+    let mut parsed: HashMap<KeyChord, (String, StravaEvent)> = HashMap::new();
+    for (key, event_name) in bindings {
+        let chord = match KeyChord::from_str(key) {
+            Ok(c) => c,
+            Err(e) => {
+                errors.push(format!("[{}] {}", section, e));
+                continue;
+            }
+        };
+        let event = match StravaEvent::from_str(event_name) {
+            Ok(e) if !e.is_mouse() => e,
+            _ => {
+                errors.push(format!(
+                    "[{}] unknown event `{}` for key `{}`",
+                    section, event_name, key
+                ));
+                continue;
+            }
+        };
+        if let Some((other_key, other_event)) = parsed.get(&chord) {
+            if other_event != &event {
+                errors.push(format!(
+                    "[{}] `{}` and `{}` are the same key but are bound to {} and {}",
+                    section,
+                    other_key,
+                    key,
+                    other_event.as_ref(),
+                    event.as_ref()
+                ));
+            }
+            continue;
         }
+        parsed.insert(chord, (key.clone(), event));
     }
+    parsed.into_iter().map(|(chord, (_, event))| (chord, event)).collect()
+}
+
+impl KeyMap {
+    /// Apply the bindings from the `[keymap]` config section to the default key map
+    pub fn from_config(config: &BTreeMap<String, KeymapEntry>) -> Result<KeyMap, Vec<String>> {
+        let mut key_map = KeyMap::default();
+        let mut errors = vec![];
+
+        let global: BTreeMap<String, String> = config
+            .iter()
+            .filter_map(|(key, entry)| match entry {
+                KeymapEntry::Event(event) => Some((key.clone(), event.clone())),
+                KeymapEntry::View(_) => None,
+            })
+            .collect();
+        let bindings = parse_bindings("keymap", &global, &mut errors);
 
-    pub(crate) fn key(&self, f: &StravaEvent) -> Option<KeyCode> {
-        for (key, event) in self.map.clone() {
-            if &event == f {
-                return Some(key)
+        // re-binding a key must not leave the event it was bound to by default without a key
+        let mut effective = key_map.map.clone();
+        effective.extend(bindings.clone());
+        let mut displaced: Vec<String> = key_map
+            .map
+            .iter()
+            .filter(|(chord, default)| match bindings.get(chord) {
+                Some(event) => {
+                    event != *default
+                        && *event != StravaEvent::None
+                        && !effective.values().any(|e| e == *default)
+                }
+                None => false,
+            })
+            .map(|(chord, default)| {
+                format!(
+                    "[keymap] `{}` is bound to {} by default, bind {} to another key or `{}` to None",
+                    chord,
+                    default.as_ref(),
+                    default.as_ref(),
+                    chord
+                )
+            })
+            .collect();
+        displaced.sort();
+        errors.append(&mut displaced);
+        key_map.map = effective;
+
+        for (view, entry) in config.iter() {
+            if let KeymapEntry::View(bindings) = entry {
+                if !VIEWS.contains(&view.as_str()) {
+                    errors.push(format!(
+                        "[keymap.{}] unknown view, expected one of: {}",
+                        view,
+                        VIEWS.join(", ")
+                    ));
+                    continue;
+                }
+                let section = format!("keymap.{}", view);
+                let bindings = parse_bindings(&section, bindings, &mut errors);
+                key_map.views.insert(view.clone(), bindings);
             }
         }
-        None
+
+        match errors.is_empty() {
+            true => Ok(key_map),
+            false => Err(errors),
+        }
+    }
+
+    fn event_for(&self, chord: &KeyChord, view: &str) -> Option<&StravaEvent> {
+        self.views
+            .get(view)
+            .and_then(|bindings| bindings.get(chord))
+            .or_else(|| self.map.get(chord))
+    }
+
+    pub fn map_key(&self, ke: KeyEvent, view: &str) -> MappedKey {
+        match self.event_for(&KeyChord::from(&ke), view) {
+            Some(event) => new_strava_key(ke, event.clone()),
+            None => new_strava_key(ke, StravaEvent::None),
+        }
+    }
+
+    /// Key bound to the event in the given view, preferring view specific
+    /// bindings and then the shortest key name.
+    pub(crate) fn key(&self, f: &StravaEvent, view: &str) -> Option<KeyChord> {
+        self.keys(f, view).first().copied()
+    }
+
+    /// All keys bound to the event in the given view in order of preference
+    pub(crate) fn keys(&self, f: &StravaEvent, view: &str) -> Vec<KeyChord> {
+        let view_bindings = self.views.get(view);
+        let mut chords: Vec<&KeyChord> = view_bindings
+            .iter()
+            .flat_map(|bindings| bindings.iter())
+            .chain(self.map.iter())
+            .filter(|(chord, _)| self.event_for(chord, view) == Some(f))
+            .map(|(chord, _)| chord)
+            .collect();
+        chords.sort_by_key(|c| {
+            (
+                !view_bindings.is_some_and(|b| b.contains_key(c)),
+                c.to_string().len(),
+                c.to_string(),
+            )
+        });
+        chords.dedup();
+        chords.into_iter().copied().collect()
     }
 }
 
@@ -54,20 +358,43 @@ fn new_strava_key(ke: KeyEvent, se: StravaEvent) -> MappedKey {
     MappedKey {
         key_event: ke,
         strava_event: se,
+        mouse: None,
     }
 }
 
+#[derive(Clone)]
 pub struct MappedKey {
     pub key_event: KeyEvent,
     pub strava_event: StravaEvent,
+    /// Position of the pointer when the event comes from the mouse
+    pub mouse: Option<MouseInput>,
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+impl MappedKey {
+    pub fn from_mouse(se: StravaEvent, mouse: MouseInput) -> MappedKey {
+        MappedKey {
+            key_event: KeyEvent::new(KeyCode::Null, KeyModifiers::NONE),
+            strava_event: se,
+            mouse: Some(mouse),
+        }
+    }
+
+    /// The same input mapped to another event, e.g. to open the row which was double clicked
+    pub fn with_event(&self, se: StravaEvent) -> MappedKey {
+        MappedKey {
+            strava_event: se,
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter, EnumString, AsRefStr)]
 pub enum StravaEvent {
     Rank,
     ToggleUnitSystem,
     ToggleSortOrder,
     Refresh,
+    Reconcile,
     ToggleLogView,
     Filter,
     Anchor,
@@ -86,6 +413,23 @@ pub enum StravaEvent {
     PageUp,
     MovingElapsed,
     ToggleSegmentEffortsView,
+    TogglePowerCurveView,
+    Mark,
+    Compare,
+    ToggleSegmentsView,
+    Export,
+    ToggleStatsView,
+    ToggleGearView,
+    ToggleLaps,
+    ToggleReps,
+    GroupBy,
+    Help,
+    Columns,
+    Click,
+    DoubleClick,
+    ScrollUp,
+    ScrollDown,
+    Drag,
 }
 impl StravaEvent {
     pub fn describe(se: &StravaEvent) -> &'static str {
@@ -94,6 +438,7 @@ impl StravaEvent {
             StravaEvent::ToggleUnitSystem => "unit",
             StravaEvent::ToggleSortOrder => "order",
             StravaEvent::Refresh => "refresh",
+            StravaEvent::Reconcile => "reconcile",
             StravaEvent::Filter => "filter",
             StravaEvent::Anchor => "anchor",
             StravaEvent::Sort => "sort",
@@ -112,6 +457,181 @@ impl StravaEvent {
             StravaEvent::Previous => "prev",
             StravaEvent::ToggleLogView => "logs",
             StravaEvent::MovingElapsed => "moving/elapsed",
+            StravaEvent::TogglePowerCurveView => "power",
+            StravaEvent::Mark => "mark",
+            StravaEvent::Compare => "compare",
+            StravaEvent::ToggleSegmentsView => "leaderboard",
+            StravaEvent::Export => "export",
+            StravaEvent::ToggleStatsView => "stats",
+            StravaEvent::ToggleGearView => "gear",
+            StravaEvent::ToggleLaps => "laps",
+            StravaEvent::ToggleReps => "reps",
+            StravaEvent::GroupBy => "group by",
+            StravaEvent::Help => "help",
+            StravaEvent::Columns => "columns",
+            StravaEvent::Click => "click",
+            StravaEvent::DoubleClick => "double click",
+            StravaEvent::ScrollUp => "scroll up",
+            StravaEvent::ScrollDown => "scroll down",
+            StravaEvent::Drag => "drag",
+        }
+    }
+
+    /// Mouse events are not bound to keys
+    pub fn is_mouse(&self) -> bool {
+        Self::category(self) == "Mouse"
+    }
+
+    /// Heading the event is listed under in the help
+    pub fn category(se: &StravaEvent) -> &'static str {
+        match se {
+            StravaEvent::Up
+            | StravaEvent::Down
+            | StravaEvent::PageUp
+            | StravaEvent::PageDown
+            | StravaEvent::Next
+            | StravaEvent::Previous
+            | StravaEvent::Enter
+            | StravaEvent::Escape
+            | StravaEvent::Quit
+            | StravaEvent::None => "Navigation",
+            StravaEvent::Click
+            | StravaEvent::DoubleClick
+            | StravaEvent::ScrollUp
+            | StravaEvent::ScrollDown
+            | StravaEvent::Drag => "Mouse",
+            StravaEvent::ToggleLogView
+            | StravaEvent::ToggleSegmentEffortsView
+            | StravaEvent::TogglePowerCurveView
+            | StravaEvent::ToggleSegmentsView
+            | StravaEvent::ToggleStatsView
+            | StravaEvent::ToggleGearView
+            | StravaEvent::Compare
+            | StravaEvent::Help => "Views",
+            StravaEvent::Filter
+            | StravaEvent::Sort
+            | StravaEvent::Rank
+            | StravaEvent::ToggleSortOrder
+            | StravaEvent::Anchor
+            | StravaEvent::IncreaseTolerance
+            | StravaEvent::DecreaseTolerance
+            | StravaEvent::Mark
+            | StravaEvent::GroupBy => "Activities",
+            StravaEvent::ToggleUnitSystem
+            | StravaEvent::MovingElapsed
+            | StravaEvent::ToggleLaps
+            | StravaEvent::ToggleReps
+            | StravaEvent::Columns => "Display",
+            StravaEvent::Refresh | StravaEvent::Reconcile | StravaEvent::Export => "Actions",
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::KeyEventKind;
+
+    use super::*;
+
+    fn key_event(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new_with_kind(code, modifiers, KeyEventKind::Press)
+    }
+
+    fn config(entries: &[(&str, KeymapEntry)]) -> BTreeMap<String, KeymapEntry> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_chords() {
+        assert_eq!(
+            KeyChord::new(KeyCode::Char('n'), KeyModifiers::CONTROL),
+            KeyChord::from_str("ctrl-n").unwrap()
+        );
+        assert_eq!(KeyChord::plain(KeyCode::Char('K')), KeyChord::from_str("shift-k").unwrap());
+        assert_eq!(KeyChord::plain(KeyCode::Char('-')), KeyChord::from_str("-").unwrap());
+        assert_eq!(
+            KeyChord::new(KeyCode::Char('-'), KeyModifiers::ALT),
+            KeyChord::from_str("alt--").unwrap()
+        );
+        assert_eq!(KeyChord::plain(KeyCode::BackTab), KeyChord::from_str("shift-tab").unwrap());
+        assert_eq!(KeyChord::plain(KeyCode::F(5)), KeyChord::from_str("f5").unwrap());
+        assert!(KeyChord::from_str("hyper-x").is_err());
+        assert!(KeyChord::from_str("ctrl-foo").is_err());
+
+        for chord in ["ctrl-n", "alt-down", "space", "shift-tab", "K", "ctrl-alt-x"] {
+            assert_eq!(chord, KeyChord::from_str(chord).unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn map_with_modifiers_and_view_overrides() {
+        let key_map = KeyMap::from_config(&config(&[
+            ("ctrl-n", KeymapEntry::Event("Down".to_string())),
+            (
+                "activity",
+                KeymapEntry::View(BTreeMap::from([("j".to_string(), "Next".to_string())])),
+            ),
+        ]))
+        .unwrap();
+
+        let ctrl_n = key_event(KeyCode::Char('n'), KeyModifiers::CONTROL);
+        assert_eq!(StravaEvent::Down, key_map.map_key(ctrl_n, "list").strava_event);
+        let n = key_event(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(StravaEvent::Next, key_map.map_key(n, "list").strava_event);
+        let shift_k = key_event(KeyCode::Char('K'), KeyModifiers::SHIFT);
+        assert_eq!(StravaEvent::PageUp, key_map.map_key(shift_k, "list").strava_event);
+
+        let j = key_event(KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(StravaEvent::Down, key_map.map_key(j, "list").strava_event);
+        assert_eq!(StravaEvent::Next, key_map.map_key(j, "activity").strava_event);
+        assert_eq!(
+            Some(KeyChord::plain(KeyCode::Char('j'))),
+            key_map.key(&StravaEvent::Next, "activity")
+        );
+        assert_eq!(
+            vec![
+                KeyChord::plain(KeyCode::Char('j')),
+                KeyChord::new(KeyCode::Char('n'), KeyModifiers::CONTROL)
+            ],
+            key_map.keys(&StravaEvent::Down, "list")
+        );
+    }
+
+    #[test]
+    fn report_conflicts() {
+        let errors = KeyMap::from_config(&config(&[
+            ("K", KeymapEntry::Event("Up".to_string())),
+            ("shift-k", KeymapEntry::Event("Down".to_string())),
+            ("x", KeymapEntry::Event("Compare".to_string())),
+            ("ctrl-x", KeymapEntry::Event("Foo".to_string())),
+            (
+                "nope",
+                KeymapEntry::View(BTreeMap::from([("j".to_string(), "Next".to_string())])),
+            ),
+        ]))
+        .err()
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                "[keymap] unknown event `Foo` for key `ctrl-x`",
+                "[keymap] `K` and `shift-k` are the same key but are bound to Up and Down",
+                "[keymap] `K` is bound to PageUp by default, bind PageUp to another key or `K` to None",
+                "[keymap] `x` is bound to Mark by default, bind Mark to another key or `x` to None",
+                "[keymap.nope] unknown view, expected one of: list, activity, segments, log, power, comparison, leaderboard, stats, gear",
+            ],
+            errors
+        );
+
+        // unbinding explicitly or re-binding the displaced event resolves the conflict
+        assert!(KeyMap::from_config(&config(&[
+            ("x", KeymapEntry::Event("Compare".to_string())),
+            ("ctrl-x", KeymapEntry::Event("Mark".to_string())),
+            ("c", KeymapEntry::Event("None".to_string())),
+        ]))
+        .is_ok());
+    }
+}
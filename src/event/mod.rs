@@ -2,3 +2,4 @@ pub mod keymap;
 pub mod util;
 pub mod input;
 pub mod logger;
+pub mod mouse;
@@ -0,0 +1,127 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use tui::layout::{Position, Rect};
+
+use super::keymap::StravaEvent;
+
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MouseInput {
+    pub column: u16,
+    pub row: u16,
+    /// Cells moved since the previous event while dragging
+    pub delta: (i32, i32),
+}
+
+impl MouseInput {
+    pub fn is_in(&self, area: Rect) -> bool {
+        area.contains(Position::new(self.column, self.row))
+    }
+}
+
+/// Turns the raw mouse events into clicks, double clicks, scrolls and drags
+#[derive(Default)]
+pub struct MouseTracker {
+    last_click: Option<(Instant, u16, u16)>,
+    last_position: Option<(u16, u16)>,
+}
+
+impl MouseTracker {
+    pub fn map(&mut self, event: MouseEvent, now: Instant) -> Option<(StravaEvent, MouseInput)> {
+        let mut input = MouseInput {
+            column: event.column,
+            row: event.row,
+            delta: (0, 0),
+        };
+        let strava_event = match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.last_position = Some((event.column, event.row));
+                match self.last_click.take() {
+                    Some((at, column, row))
+                        if now.duration_since(at) <= DOUBLE_CLICK_INTERVAL
+                            && (column, row) == (event.column, event.row) =>
+                    {
+                        StravaEvent::DoubleClick
+                    }
+                    _ => {
+                        self.last_click = Some((now, event.column, event.row));
+                        StravaEvent::Click
+                    }
+                }
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                let (column, row) = self.last_position.unwrap_or((event.column, event.row));
+                self.last_position = Some((event.column, event.row));
+                self.last_click = None;
+                input.delta = (
+                    event.column as i32 - column as i32,
+                    event.row as i32 - row as i32,
+                );
+                StravaEvent::Drag
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                self.last_position = None;
+                return None;
+            }
+            MouseEventKind::ScrollUp => StravaEvent::ScrollUp,
+            MouseEventKind::ScrollDown => StravaEvent::ScrollDown,
+            _ => return None,
+        };
+        Some((strava_event, input))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn double_click() {
+        let mut tracker = MouseTracker::default();
+        let now = Instant::now();
+        let down = mouse(MouseEventKind::Down(MouseButton::Left), 3, 4);
+
+        assert_eq!(StravaEvent::Click, tracker.map(down, now).unwrap().0);
+        assert_eq!(None, tracker.map(mouse(MouseEventKind::Up(MouseButton::Left), 3, 4), now));
+        assert_eq!(
+            StravaEvent::DoubleClick,
+            tracker.map(down, now + Duration::from_millis(200)).unwrap().0
+        );
+        assert_eq!(
+            StravaEvent::Click,
+            tracker.map(down, now + Duration::from_millis(300)).unwrap().0
+        );
+        assert_eq!(StravaEvent::Click, tracker.map(down, now + Duration::from_secs(1)).unwrap().0);
+    }
+
+    #[test]
+    fn drag_delta() {
+        let mut tracker = MouseTracker::default();
+        let now = Instant::now();
+        tracker.map(mouse(MouseEventKind::Down(MouseButton::Left), 10, 10), now);
+
+        let (event, input) = tracker
+            .map(mouse(MouseEventKind::Drag(MouseButton::Left), 12, 9), now)
+            .unwrap();
+        assert_eq!(StravaEvent::Drag, event);
+        assert_eq!((2, -1), input.delta);
+
+        let (_, input) = tracker
+            .map(mouse(MouseEventKind::Drag(MouseButton::Left), 13, 9), now)
+            .unwrap();
+        assert_eq!((1, 0), input.delta);
+    }
+}
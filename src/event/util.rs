@@ -1,4 +1,6 @@
-use tui::widgets::TableState;
+use tui::{layout::Rect, widgets::TableState};
+
+use super::mouse::MouseInput;
 
 pub fn table_state_next(table_state: &mut TableState, max: usize, repeat: bool) {
     let i = match table_state.selected() {
@@ -36,3 +38,54 @@ pub fn table_state_prev(table_state: &mut TableState, max: usize, repeat: bool)
     };
     table_state.select(Some(i));
 }
+
+/// Index of the table row under the pointer given the lines taken by the
+/// table header, taking the scroll offset of the table into account.
+pub fn table_row_at(area: Rect, table_state: &TableState, header_height: u16, mouse: &MouseInput) -> Option<usize> {
+    if !mouse.is_in(area) || mouse.row < area.y + header_height {
+        return None;
+    }
+    Some((mouse.row - area.y - header_height) as usize + table_state.offset())
+}
+
+/// Select the clicked row of a table with `len` rows, returns the selected row
+pub fn table_state_click(
+    table_state: &mut TableState,
+    area: Rect,
+    header_height: u16,
+    mouse: &MouseInput,
+    len: usize,
+) -> Option<usize> {
+    let row = table_row_at(area, table_state, header_height, mouse).filter(|r| *r < len)?;
+    table_state.select(Some(row));
+    Some(row)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn click(column: u16, row: u16) -> MouseInput {
+        MouseInput {
+            column,
+            row,
+            delta: (0, 0),
+        }
+    }
+
+    #[test]
+    fn row_under_pointer() {
+        let area = Rect::new(2, 10, 40, 10);
+        let mut state = TableState::default();
+
+        assert_eq!(None, table_row_at(area, &state, 2, &click(5, 11)));
+        assert_eq!(Some(0), table_row_at(area, &state, 2, &click(5, 12)));
+        assert_eq!(None, table_row_at(area, &state, 2, &click(1, 12)));
+
+        *state.offset_mut() = 5;
+        assert_eq!(Some(8), table_row_at(area, &state, 2, &click(5, 15)));
+        assert_eq!(None, table_state_click(&mut state, area, 2, &click(5, 15), 8));
+        assert_eq!(Some(7), table_state_click(&mut state, area, 2, &click(5, 14), 8));
+        assert_eq!(Some(7), state.selected());
+    }
+}
@@ -0,0 +1,293 @@
+use std::{fmt::Display, io::Write, str::FromStr};
+
+use serde_json::{json, Map, Value};
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::{
+    component::unit_formatter::UnitFormatter,
+    store::activity::{Activities, Activity},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "ndjson" => Ok(ExportFormat::Ndjson),
+            _ => Err(format!("unknown format `{}`, expected csv, json or ndjson", s)),
+        }
+    }
+}
+
+#[derive(EnumIter, Clone, Debug, PartialEq)]
+pub enum ExportColumn {
+    Id,
+    Date,
+    Type,
+    Title,
+    Distance,
+    MovingTime,
+    ElapsedTime,
+    Pace,
+    Speed,
+    HeartRate,
+    MaxHeartRate,
+    Elevation,
+    Cadence,
+    Watts,
+    Kudos,
+    City,
+    Country,
+    Rank,
+}
+
+impl ExportColumn {
+    pub fn to_label(&self) -> &'static str {
+        match self {
+            ExportColumn::Id => "id",
+            ExportColumn::Date => "date",
+            ExportColumn::Type => "type",
+            ExportColumn::Title => "title",
+            ExportColumn::Distance => "distance",
+            ExportColumn::MovingTime => "moving_time",
+            ExportColumn::ElapsedTime => "elapsed_time",
+            ExportColumn::Pace => "pace",
+            ExportColumn::Speed => "speed",
+            ExportColumn::HeartRate => "heartrate",
+            ExportColumn::MaxHeartRate => "max_heartrate",
+            ExportColumn::Elevation => "elevation",
+            ExportColumn::Cadence => "cadence",
+            ExportColumn::Watts => "watts",
+            ExportColumn::Kudos => "kudos",
+            ExportColumn::City => "city",
+            ExportColumn::Country => "country",
+            ExportColumn::Rank => "rank",
+        }
+    }
+
+    pub fn defaults() -> Vec<ExportColumn> {
+        vec![
+            ExportColumn::Date,
+            ExportColumn::Type,
+            ExportColumn::Title,
+            ExportColumn::Distance,
+            ExportColumn::MovingTime,
+            ExportColumn::Pace,
+            ExportColumn::HeartRate,
+            ExportColumn::Elevation,
+            ExportColumn::Rank,
+        ]
+    }
+
+    /// Parse a comma separated list of column names
+    pub fn parse_list(list: &str) -> Result<Vec<ExportColumn>, String> {
+        list.split(',')
+            .map(|name| ExportColumn::from_str(name.trim()))
+            .collect()
+    }
+
+    /// Column name including the unit, e.g. `distance_km`
+    pub fn header(&self, formatter: &UnitFormatter) -> String {
+        match self {
+            ExportColumn::Distance => format!("distance_{}", formatter.distance_unit()),
+            ExportColumn::Pace => format!("pace_s_per_{}", formatter.distance_unit()),
+            ExportColumn::Speed => format!("speed_{}", formatter.speed_unit()),
+            ExportColumn::Elevation => format!("elevation_{}", formatter.elevation_unit()),
+            ExportColumn::MovingTime | ExportColumn::ElapsedTime => {
+                format!("{}_s", self.to_label())
+            }
+            _ => self.to_label().to_string(),
+        }
+    }
+
+    pub fn value(&self, activity: &Activity, formatter: &UnitFormatter) -> Value {
+        match self {
+            ExportColumn::Id => json!(activity.id),
+            ExportColumn::Date => json!(activity
+                .start_date
+                .map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string())),
+            ExportColumn::Type => json!(activity.activity_type),
+            ExportColumn::Title => json!(activity.title),
+            ExportColumn::Distance => json!(round(formatter.distance_value(activity.distance))),
+            ExportColumn::MovingTime => json!(activity.moving_time),
+            ExportColumn::ElapsedTime => json!(activity.elapsed_time),
+            ExportColumn::Pace => {
+                let pace = formatter.pace_value(activity.moving_time, activity.distance);
+                json!(if pace.is_finite() { Some(round(pace)) } else { None })
+            }
+            ExportColumn::Speed => json!(round(formatter.speed_value(activity.meters_per_hour()))),
+            ExportColumn::HeartRate => json!(activity.average_heartrate),
+            ExportColumn::MaxHeartRate => json!(activity.max_heartrate),
+            ExportColumn::Elevation => {
+                json!(round(formatter.elevation_value(activity.total_elevation_gain)))
+            }
+            ExportColumn::Cadence => json!(activity.average_cadence),
+            ExportColumn::Watts => json!(activity.average_watts),
+            ExportColumn::Kudos => json!(activity.kudos),
+            ExportColumn::City => json!(activity.location_city),
+            ExportColumn::Country => json!(activity.location_country),
+            ExportColumn::Rank => json!(activity.rank),
+        }
+    }
+}
+
+impl Display for ExportColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_label())
+    }
+}
+
+impl FromStr for ExportColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ExportColumn::iter()
+            .find(|c| c.to_label() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown column `{}`, expected one of: {}",
+                    s,
+                    ExportColumn::iter()
+                        .map(|c| c.to_label())
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                )
+            })
+    }
+}
+
+pub(crate) fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn csv_field(value: &Value) -> String {
+    let field = match value {
+        Value::Null => return "".to_string(),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+    field
+}
+
+fn record(activity: &Activity, columns: &[ExportColumn], formatter: &UnitFormatter) -> Map<String, Value> {
+    columns
+        .iter()
+        .map(|c| (c.header(formatter), c.value(activity, formatter)))
+        .collect()
+}
+
+/// Write the activities in the given format with one row (or object) per activity
+pub fn export_activities(
+    writer: &mut dyn Write,
+    activities: &Activities,
+    columns: &[ExportColumn],
+    format: &ExportFormat,
+    formatter: &UnitFormatter,
+) -> Result<(), anyhow::Error> {
+    match format {
+        ExportFormat::Csv => {
+            let headers: Vec<String> = columns.iter().map(|c| c.header(formatter)).collect();
+            writeln!(writer, "{}", headers.join(","))?;
+            for activity in activities.to_vec() {
+                let fields: Vec<String> = columns
+                    .iter()
+                    .map(|c| csv_field(&c.value(&activity, formatter)))
+                    .collect();
+                writeln!(writer, "{}", fields.join(","))?;
+            }
+        }
+        ExportFormat::Json => {
+            let records: Vec<Map<String, Value>> = activities
+                .to_vec()
+                .iter()
+                .map(|a| record(a, columns, formatter))
+                .collect();
+            serde_json::to_writer_pretty(&mut *writer, &records)?;
+            writeln!(writer)?;
+        }
+        ExportFormat::Ndjson => {
+            for activity in activities.to_vec() {
+                serde_json::to_writer(&mut *writer, &record(&activity, columns, formatter))?;
+                writeln!(writer)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::component::unit_formatter::UnitSystem;
+
+    fn activities() -> Activities {
+        Activities::from(vec![Activity {
+            id: 1,
+            title: "Morning, \"easy\" run".to_string(),
+            activity_type: "Run".to_string(),
+            distance: 10000.0,
+            moving_time: 3000,
+            ..Activity::default()
+        }])
+    }
+
+    fn export(format: ExportFormat) -> String {
+        let mut buffer = vec![];
+        export_activities(
+            &mut buffer,
+            &activities(),
+            &[ExportColumn::Title, ExportColumn::Distance, ExportColumn::Pace],
+            &format,
+            &UnitFormatter::new(UnitSystem::Metric),
+        )
+        .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn export_csv() {
+        assert_eq!(
+            "title,distance_km,pace_s_per_km\n\"Morning, \"\"easy\"\" run\",10.0,300.0\n",
+            export(ExportFormat::Csv)
+        );
+    }
+
+    #[test]
+    fn export_ndjson() {
+        assert_eq!(
+            "{\"title\":\"Morning, \\\"easy\\\" run\",\"distance_km\":10.0,\"pace_s_per_km\":300.0}\n",
+            export(ExportFormat::Ndjson)
+        );
+    }
+
+    #[test]
+    fn parse_columns() {
+        assert_eq!(
+            Ok(vec![ExportColumn::Date, ExportColumn::Distance]),
+            ExportColumn::parse_list("date, distance")
+        );
+        assert!(ExportColumn::parse_list("date,foo").is_err());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod activities;
+pub mod route;
@@ -0,0 +1,317 @@
+use std::{fmt::Display, io::Write, str::FromStr};
+
+use chrono::{Duration, NaiveDateTime};
+use serde_json::{json, Value};
+
+use crate::{client::Streams, store::activity::Activity};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RouteFormat {
+    Gpx,
+    GeoJson,
+    Kml,
+}
+
+impl RouteFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RouteFormat::Gpx => "gpx",
+            RouteFormat::GeoJson => "geojson",
+            RouteFormat::Kml => "kml",
+        }
+    }
+}
+
+impl Display for RouteFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+impl FromStr for RouteFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gpx" => Ok(RouteFormat::Gpx),
+            "geojson" => Ok(RouteFormat::GeoJson),
+            "kml" => Ok(RouteFormat::Kml),
+            _ => Err(format!("unknown format `{}`, expected gpx, geojson or kml", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutePoint {
+    pub lat: f64,
+    pub lon: f64,
+    pub elevation: Option<f64>,
+    pub time: Option<NaiveDateTime>,
+}
+
+/// Points of the activity's route, using the recorded streams (with elevation
+/// and time) when they were downloaded and the summary polyline otherwise.
+pub fn route_points(activity: &Activity, streams: Option<&Streams>) -> Vec<RoutePoint> {
+    if let Some(streams) = streams {
+        let latlng = streams.latlng.as_ref().map_or(&[][..], |l| &l.data[..]);
+        if !latlng.is_empty() {
+            return latlng
+                .iter()
+                .enumerate()
+                .map(|(i, (lat, lon))| RoutePoint {
+                    lat: *lat,
+                    lon: *lon,
+                    elevation: streams
+                        .altitude
+                        .as_ref()
+                        .and_then(|a| a.data.get(i).copied()),
+                    time: match (activity.start_date, &streams.time) {
+                        (Some(start), Some(time)) => {
+                            time.data.get(i).map(|t| start + Duration::seconds(*t))
+                        }
+                        _ => None,
+                    },
+                })
+                .collect();
+        }
+    }
+
+    match activity.polyline() {
+        Ok(polyline) => polyline
+            .coords()
+            .map(|c| RoutePoint {
+                lat: c.y,
+                lon: c.x,
+                elevation: None,
+                time: None,
+            })
+            .collect(),
+        Err(_) => vec![],
+    }
+}
+
+fn timestamp(time: &NaiveDateTime) -> String {
+    time.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn write_gpx(writer: &mut dyn Write, activity: &Activity, points: &[RoutePoint]) -> Result<(), anyhow::Error> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<gpx version="1.1" creator="strava-rs" xmlns="http://www.topografix.com/GPX/1/1">"#
+    )?;
+    writeln!(writer, "  <metadata>")?;
+    writeln!(writer, "    <name>{}</name>", escape_xml(&activity.title))?;
+    if let Some(start) = &activity.start_date {
+        writeln!(writer, "    <time>{}</time>", timestamp(start))?;
+    }
+    writeln!(writer, "  </metadata>")?;
+    writeln!(writer, "  <trk>")?;
+    writeln!(writer, "    <name>{}</name>", escape_xml(&activity.title))?;
+    writeln!(writer, "    <type>{}</type>", escape_xml(&activity.activity_type))?;
+    writeln!(writer, "    <trkseg>")?;
+    for point in points {
+        write!(writer, r#"      <trkpt lat="{}" lon="{}">"#, point.lat, point.lon)?;
+        if let Some(elevation) = point.elevation {
+            write!(writer, "<ele>{}</ele>", elevation)?;
+        }
+        if let Some(time) = &point.time {
+            write!(writer, "<time>{}</time>", timestamp(time))?;
+        }
+        writeln!(writer, "</trkpt>")?;
+    }
+    writeln!(writer, "    </trkseg>")?;
+    writeln!(writer, "  </trk>")?;
+    writeln!(writer, "</gpx>")?;
+    Ok(())
+}
+
+fn write_geojson(writer: &mut dyn Write, activity: &Activity, points: &[RoutePoint]) -> Result<(), anyhow::Error> {
+    let coordinates: Vec<Value> = points
+        .iter()
+        .map(|p| match p.elevation {
+            Some(elevation) => json!([p.lon, p.lat, elevation]),
+            None => json!([p.lon, p.lat]),
+        })
+        .collect();
+    let mut properties = json!({
+        "id": activity.id,
+        "title": activity.title,
+        "type": activity.activity_type,
+        "sport_type": activity.sport_type,
+        "start_date": activity.start_date.as_ref().map(timestamp),
+        "distance": activity.distance,
+        "moving_time": activity.moving_time,
+        "elapsed_time": activity.elapsed_time,
+        "total_elevation_gain": activity.total_elevation_gain,
+        "average_speed": activity.average_speed,
+        "average_heartrate": activity.average_heartrate,
+        "max_heartrate": activity.max_heartrate,
+        "average_cadence": activity.average_cadence,
+        "average_watts": activity.average_watts,
+        "kudos": activity.kudos,
+    });
+    if points.iter().all(|p| p.time.is_some()) && !points.is_empty() {
+        properties["coordTimes"] = json!(points
+            .iter()
+            .map(|p| p.time.as_ref().map(timestamp))
+            .collect::<Vec<Option<String>>>());
+    }
+    let feature = json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "LineString",
+            "coordinates": coordinates,
+        },
+        "properties": properties,
+    });
+    serde_json::to_writer_pretty(&mut *writer, &feature)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+fn write_kml(writer: &mut dyn Write, activity: &Activity, points: &[RoutePoint]) -> Result<(), anyhow::Error> {
+    let has_elevation = !points.is_empty() && points.iter().all(|p| p.elevation.is_some());
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<kml xmlns="http://www.opengis.net/kml/2.2">"#)?;
+    writeln!(writer, "  <Document>")?;
+    writeln!(writer, "    <name>{}</name>", escape_xml(&activity.title))?;
+    writeln!(writer, "    <Placemark>")?;
+    writeln!(writer, "      <name>{}</name>", escape_xml(&activity.title))?;
+    if !activity.description.is_empty() {
+        writeln!(
+            writer,
+            "      <description>{}</description>",
+            escape_xml(&activity.description)
+        )?;
+    }
+    if let Some(start) = &activity.start_date {
+        writeln!(writer, "      <TimeStamp><when>{}</when></TimeStamp>", timestamp(start))?;
+    }
+    writeln!(writer, "      <LineString>")?;
+    writeln!(
+        writer,
+        "        <altitudeMode>{}</altitudeMode>",
+        if has_elevation { "absolute" } else { "clampToGround" }
+    )?;
+    writeln!(writer, "        <coordinates>")?;
+    for point in points {
+        match (has_elevation, point.elevation) {
+            (true, Some(elevation)) => {
+                writeln!(writer, "          {},{},{}", point.lon, point.lat, elevation)?
+            }
+            _ => writeln!(writer, "          {},{}", point.lon, point.lat)?,
+        }
+    }
+    writeln!(writer, "        </coordinates>")?;
+    writeln!(writer, "      </LineString>")?;
+    writeln!(writer, "    </Placemark>")?;
+    writeln!(writer, "  </Document>")?;
+    writeln!(writer, "</kml>")?;
+    Ok(())
+}
+
+/// Write the route of a single activity in the given format
+pub fn export_route(
+    writer: &mut dyn Write,
+    activity: &Activity,
+    points: &[RoutePoint],
+    format: &RouteFormat,
+) -> Result<(), anyhow::Error> {
+    if points.is_empty() {
+        return Err(anyhow::Error::msg(format!(
+            "activity {} has no route",
+            activity.id
+        )));
+    }
+    match format {
+        RouteFormat::Gpx => write_gpx(writer, activity, points),
+        RouteFormat::GeoJson => write_geojson(writer, activity, points),
+        RouteFormat::Kml => write_kml(writer, activity, points),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::client::Stream;
+
+    fn activity() -> Activity {
+        Activity {
+            id: 1,
+            title: "Fish & Chips".to_string(),
+            activity_type: "Run".to_string(),
+            start_date: NaiveDate::from_ymd_opt(2024, 1, 1)
+                .unwrap()
+                .and_hms_opt(8, 0, 0),
+            summary_polyline: Some("_p~iF~ps|U_ulLnnqC_mqNvxq`@".to_string()),
+            ..Activity::default()
+        }
+    }
+
+    fn export(format: RouteFormat, points: &[RoutePoint]) -> String {
+        let mut buffer = vec![];
+        export_route(&mut buffer, &activity(), points, &format).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn points_from_polyline() {
+        let points = route_points(&activity(), None);
+        assert_eq!(3, points.len());
+        assert_eq!(38.5, points[0].lat);
+        assert_eq!(-120.2, points[0].lon);
+        assert_eq!(None, points[0].time);
+    }
+
+    #[test]
+    fn points_from_streams() {
+        let streams = Streams {
+            time: Some(Stream { data: vec![0, 10] }),
+            latlng: Some(Stream {
+                data: vec![(51.0, 1.0), (51.1, 1.1)],
+            }),
+            altitude: Some(Stream {
+                data: vec![10.0, 12.5],
+            }),
+            ..Streams::default()
+        };
+        let points = route_points(&activity(), Some(&streams));
+        assert_eq!(2, points.len());
+        assert_eq!(Some(12.5), points[1].elevation);
+
+        let gpx = export(RouteFormat::Gpx, &points);
+        assert!(gpx.contains("<name>Fish &amp; Chips</name>"));
+        assert!(gpx.contains(
+            r#"<trkpt lat="51.1" lon="1.1"><ele>12.5</ele><time>2024-01-01T08:00:10Z</time></trkpt>"#
+        ));
+    }
+
+    #[test]
+    fn geojson_line_string() {
+        let points = route_points(&activity(), None);
+        let geojson: Value = serde_json::from_str(&export(RouteFormat::GeoJson, &points)).unwrap();
+        assert_eq!("LineString", geojson["geometry"]["type"]);
+        assert_eq!(json!([-120.2, 38.5]), geojson["geometry"]["coordinates"][0]);
+        assert_eq!("Fish & Chips", geojson["properties"]["title"]);
+        assert!(geojson["properties"].get("coordTimes").is_none());
+    }
+
+    #[test]
+    fn kml_without_elevation() {
+        let points = route_points(&activity(), None);
+        let kml = export(RouteFormat::Kml, &points);
+        assert!(kml.contains("<altitudeMode>clampToGround</altitudeMode>"));
+        assert!(kml.contains("          -120.2,38.5\n"));
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
 
 use chrono::NaiveDate;
 
@@ -24,16 +24,15 @@ impl Evalue {
             Evalue::Date(_) => true,
         }
     }
+}
 
-    fn to_string(&self) -> String {
+impl Display for Evalue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Evalue::Date(d) => d.to_string(),
-            Evalue::String(v) => v.clone(),
-            Evalue::Number(n) => format!("{}", *n),
-            Evalue::Bool(b) => match b {
-                true => "true".to_string(),
-                false => "false".to_string(),
-            },
+            Evalue::Date(d) => write!(f, "{}", d),
+            Evalue::String(v) => write!(f, "{}", v),
+            Evalue::Number(n) => write!(f, "{}", n),
+            Evalue::Bool(b) => write!(f, "{}", b),
         }
     }
 }
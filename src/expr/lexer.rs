@@ -28,17 +28,10 @@ pub enum TokenKind {
 }
 
 fn is_number(c: char) -> bool {
-    match c {
-        '0'..='9' => true,
-        _ => false,
-    }
+    c.is_ascii_digit()
 }
 fn is_name(c: char) -> bool {
-    match c {
-        'a'..='z' => true,
-        'A'..='Z' => true,
-        _ => false,
-    }
+    c.is_ascii_alphabetic()
 }
 
 #[derive(Debug)]
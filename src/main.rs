@@ -1,6 +1,8 @@
 pub mod app;
 pub mod authenticator;
+pub mod cli;
 pub mod expr;
+pub mod export;
 pub mod client;
 pub mod component;
 pub mod config;
@@ -8,14 +10,17 @@ pub mod event;
 pub mod store;
 pub mod sync;
 pub mod ui;
-pub mod util;
 
 use std::{io, panic, process};
 
 use app::App;
 use config::ConfigResult;
 
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
 
 use event::input;
 
@@ -27,10 +32,11 @@ use xdg::BaseDirectories;
 
 use crate::store::activity::ActivityStore;
 use crate::{
+    component::activity_list::columns::ListColumns,
     config::{load_config, Config},
     event::logger::Logger,
-    store::{db::get_pool, migration::run_migrations},
-    sync::spawn_sync,
+    store::{db::get_pool, migration::run_migrations, session::Session},
+    sync::{spawn_sync, webhook::spawn_webhook, SyncConfig, SyncRequest},
 };
 
 #[tokio::main]
@@ -47,12 +53,12 @@ async fn main() -> Result<(), anyhow::Error> {
         .expect("Could not create data directory");
     let pool = get_pool(format!("{}/strava.sqlite", storage_path.display())).await;
     let (event_sender, event_receiver) = mpsc::channel(32);
-    let (sync_sender, sync_receiver) = mpsc::channel::<bool>(32);
+    let (sync_sender, sync_receiver) = mpsc::channel::<SyncRequest>(32);
     let logger = Logger::new(event_sender.clone());
 
     let config_result = load_config();
-    let config: Config = match config_result {
-        ConfigResult::Ok(c) => c,
+    let mut config: Config = match config_result {
+        ConfigResult::Ok(c) => *c,
         ConfigResult::Instructions(m) => {
             println!("{}", m);
             return Ok(());
@@ -68,6 +74,10 @@ async fn main() -> Result<(), anyhow::Error> {
 
     run_migrations(&pool).await;
 
+    if let Some(command) = config.command.take() {
+        return cli::run(command, &pool, &config).await;
+    }
+
     let orig_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
         orig_hook(panic_info);
@@ -78,18 +88,34 @@ async fn main() -> Result<(), anyhow::Error> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal: Terminal<CrosstermBackend<io::Stdout>> = Terminal::new(backend)?;
     enable_raw_mode()?;
+    if config.mouse {
+        execute!(io::stdout(), EnableMouseCapture)?;
+    }
     terminal.clear()?;
 
     // start input thread
     input::start(event_sender.clone());
 
+    // start the webhook listener
+    let webhook_task = config
+        .webhook
+        .take()
+        .map(|webhook| spawn_webhook(webhook, pool.clone(), sync_sender.clone(), logger.clone()));
+
     // start sync async task
     let sync_task = spawn_sync(
         pool.clone(),
         event_sender.clone(),
-        config.client_id,
-        config.client_secret,
-        access_token_path.to_str().unwrap().to_string(),
+        SyncConfig {
+            api_url: config.api_url,
+            oauth_url: config.oauth_url,
+            client_id: config.client_id,
+            client_secret: config.client_secret,
+            access_token_path: access_token_path.to_str().unwrap().to_string(),
+            interval: config.sync_interval,
+            concurrency: config.sync_concurrency,
+            reconcile: config.reconcile,
+        },
         logger,
         sync_receiver,
     )
@@ -103,13 +129,33 @@ async fn main() -> Result<(), anyhow::Error> {
         sync_sender,
     );
     app.send(input::InputEvent::Reload);
-    app.send(input::InputEvent::Reload);
     app.activity_type = config.activity_type;
+    app.ftp = config.ftp;
+    app.export_columns = config.export_columns;
+    app.export_dir = config.export_dir;
+    app.goals = config.goals;
+    app.gear_retirement = config.gear_retirement;
+    app.key_map = config.key_map;
+    app.theme = config.theme;
+    app.unit_formatter = config.units;
+    app.list_columns = ListColumns::load(config.list_columns, dirs.place_state_file("list_columns.json").ok());
+    let session_path = dirs.place_state_file("session.json").ok();
+    app.restore_session(Session::load(session_path.as_deref(), config.session));
     info!("Starting application");
     app.run(&mut terminal).await?;
     sync_task.abort();
+    if let Some(task) = webhook_task {
+        task.abort();
+    }
 
+    if config.mouse {
+        execute!(io::stdout(), DisableMouseCapture)?;
+    }
     disable_raw_mode()?;
 
+    if let Some(path) = session_path {
+        app.session().save(&path)?;
+    }
+
     Ok(())
 }
@@ -1,20 +1,28 @@
-use std::{cmp::Ordering, collections::HashMap, fmt::Display};
+use std::{cmp::Ordering, collections::HashMap, fmt::Display, str::FromStr};
 
 use chrono::NaiveDateTime;
 use crossterm::event::KeyCode;
 use geo_types::LineString;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool};
-use strum::EnumIter;
+use strum::{EnumIter, IntoEnumIterator};
 
+use crate::client::Streams;
+use crate::component::unit_formatter::UnitSystem;
 use crate::expr::{
     evaluator::{Evaluator, Evalue, Vars},
     parser::Expr,
 };
 
-use super::polyline_compare::compare;
+use super::{
+    gear::{Athlete, Gear, GearType},
+    polyline_compare::compare,
+    power_curve::PowerCurve,
+    workout::Workout,
+};
 
-#[derive(EnumIter)]
+#[derive(EnumIter, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SortBy {
     Date,
     Distance,
@@ -22,6 +30,14 @@ pub enum SortBy {
     HeartRate,
     Time,
 }
+/// Parts of an activity which are listed next to its map
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sections {
+    Splits,
+    Laps,
+    Reps,
+}
+
 pub enum SportType {
     Ride,
     Run,
@@ -34,6 +50,20 @@ impl Display for SortBy {
     }
 }
 
+impl FromStr for SortBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SortBy::iter().find(|sort_by| sort_by.to_label() == s).ok_or_else(|| {
+            format!(
+                "unknown sort `{}`, expected one of: {}",
+                s,
+                SortBy::iter().map(|s| s.to_label().to_string()).collect::<Vec<String>>().join(", ")
+            )
+        })
+    }
+}
+
 impl SortBy {
     pub fn to_key(&self) -> char {
         match *self {
@@ -67,11 +97,25 @@ impl SortBy {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SortOrder {
     Asc,
     Desc,
 }
 
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asc" => Ok(SortOrder::Asc),
+            "desc" => Ok(SortOrder::Desc),
+            _ => Err(format!("unknown sort order `{}`, expected asc or desc", s)),
+        }
+    }
+}
+
 impl Display for SortOrder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -219,6 +263,28 @@ impl Activities {
         self.activities.clone()
     }
 
+    /// All efforts of the listed activities grouped by segment ID and ordered by date
+    pub fn segment_attempts(&self) -> HashMap<i64, Vec<SegmentAttempt>> {
+        let mut attempts: HashMap<i64, Vec<SegmentAttempt>> = HashMap::new();
+        for activity in self.activities.iter() {
+            for effort in activity.segment_efforts.iter() {
+                attempts
+                    .entry(effort.segment_id)
+                    .or_default()
+                    .push(SegmentAttempt {
+                        activity_id: activity.id,
+                        activity_title: activity.title.clone(),
+                        start_date: activity.start_date,
+                        effort: effort.clone(),
+                    });
+            }
+        }
+        for efforts in attempts.values_mut() {
+            efforts.sort_by_key(|a| a.start_date);
+        }
+        attempts
+    }
+
     pub(crate) fn by_expr(&self, evaluator: &Evaluator, expr: &Expr) -> Activities {
         self.activities
             .clone()
@@ -248,6 +314,23 @@ impl Activities {
                                 Evalue::Date(a.start_date.unwrap_or_default().into()),
                             ),
                             ("speed".to_string(), Evalue::Number(a.meters_per_hour())),
+                            (
+                                "watts".to_string(),
+                                Evalue::Number(a.average_watts.unwrap_or(0.0)),
+                            ),
+                            (
+                                "gear".to_string(),
+                                Evalue::String(a.gear().unwrap_or_default()),
+                            ),
+                            (
+                                "workout".to_string(),
+                                Evalue::String(
+                                    a.workout
+                                        .as_ref()
+                                        .map(|w| w.summary(&UnitSystem::Metric))
+                                        .unwrap_or_default(),
+                                ),
+                            ),
                         ]),
                     )
                     .unwrap_or_default()
@@ -282,7 +365,7 @@ impl FromIterator<Activity> for Activities {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, FromRow)]
+#[derive(Serialize, Deserialize, Debug, Clone, FromRow, Default)]
 pub struct Activity {
     pub id: i64,
     pub title: String,
@@ -304,7 +387,19 @@ pub struct Activity {
     pub location_state: Option<String>,
     pub location_city: Option<String>,
     pub athletes: i64,
-    pub splits: Vec<ActivitySplit>,
+    pub average_watts: Option<f64>,
+    pub weighted_average_watts: Option<f64>,
+    pub kilojoules: Option<f64>,
+    pub device_watts: bool,
+    pub gear_id: Option<String>,
+    /// Name of the gear, if it was synced
+    pub gear_name: Option<String>,
+    pub power_curve: PowerCurve,
+    /// Reps detected in the velocity stream of a run
+    pub workout: Option<Workout>,
+    pub splits_metric: Vec<ActivitySplit>,
+    pub splits_imperial: Vec<ActivitySplit>,
+    pub laps: Vec<ActivityLap>,
     pub segment_efforts: Vec<ActivitySegmentEffort>,
     pub rank: i64,
 }
@@ -319,6 +414,20 @@ pub struct ActivitySplit {
     pub split: i64,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, FromRow)]
+pub struct ActivityLap {
+    pub lap_index: i64,
+    pub name: String,
+    pub distance: f64,
+    pub moving_time: i64,
+    pub elapsed_time: i64,
+    pub average_speed: f64,
+    pub average_heartrate: Option<f64>,
+    pub max_heartrate: Option<f64>,
+    pub average_cadence: Option<f64>,
+    pub total_elevation_gain: f64,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, FromRow)]
 pub struct ActivitySegmentEffort {
     pub segment_id: i64,
@@ -327,6 +436,14 @@ pub struct ActivitySegmentEffort {
     pub pr_rank: Option<u8>,
     pub kom_rank: Option<u8>,
 }
+#[derive(Debug, Clone)]
+pub struct SegmentAttempt {
+    pub activity_id: i64,
+    pub activity_title: String,
+    pub start_date: Option<NaiveDateTime>,
+    pub effort: ActivitySegmentEffort,
+}
+
 impl ActivitySegmentEffort {
     pub fn meters_per_hour(&self, distance: f64) -> f64 {
         distance / (self.moving_time as f64 / 3600.0)
@@ -343,6 +460,16 @@ impl ActivitySplit {
     }
 }
 
+impl ActivityLap {
+    pub fn seconds_per_meter(&self) -> f64 {
+        self.moving_time as f64 / self.distance
+    }
+
+    pub(crate) fn meters_per_hour(&self) -> f64 {
+        self.distance / (self.moving_time as f64 / 3600.0)
+    }
+}
+
 pub struct ActivityStore<'a> {
     pool: &'a SqlitePool,
 }
@@ -380,30 +507,253 @@ impl ActivityStore<'_> {
             .collect()
     }
 
+    /// Segment efforts of all activities keyed by activity ID
+    pub async fn segment_efforts(&mut self) -> HashMap<i64, Vec<ActivitySegmentEffort>> {
+        let efforts = sqlx::query!(
+            r#"
+            SELECT activity_id, segment_id, elapsed_time, moving_time, pr_rank, kom_rank
+            FROM activity_segment_effort ORDER BY id
+            "#
+        )
+        .fetch_all(self.pool)
+        .await
+        .unwrap();
+
+        let mut by_activity: HashMap<i64, Vec<ActivitySegmentEffort>> = HashMap::new();
+        for rec in efforts {
+            by_activity
+                .entry(rec.activity_id)
+                .or_default()
+                .push(ActivitySegmentEffort {
+                    segment_id: rec.segment_id,
+                    elapsed_time: rec.elapsed_time,
+                    moving_time: rec.moving_time,
+                    pr_rank: rec.pr_rank.map(|r| r as u8),
+                    kom_rank: rec.kom_rank.map(|r| r as u8),
+                });
+        }
+        by_activity
+    }
+
+    /// Metric and imperial splits of all activities keyed by activity ID
+    pub async fn splits(&mut self) -> HashMap<i64, (Vec<ActivitySplit>, Vec<ActivitySplit>)> {
+        let splits = sqlx::query!(
+            r#"
+            SELECT
+                activity_id,
+                unit_system,
+                split,
+                distance,
+                moving_time,
+                elapsed_time,
+                average_speed,
+                elevation_difference
+            FROM activity_split ORDER BY activity_id, split
+            "#
+        )
+        .fetch_all(self.pool)
+        .await
+        .unwrap();
+
+        let mut by_activity: HashMap<i64, (Vec<ActivitySplit>, Vec<ActivitySplit>)> =
+            HashMap::new();
+        for rec in splits {
+            let entry = by_activity.entry(rec.activity_id).or_default();
+            let split = ActivitySplit {
+                distance: rec.distance,
+                moving_time: rec.moving_time,
+                elapsed_time: rec.elapsed_time,
+                average_speed: rec.average_speed,
+                elevation_difference: rec.elevation_difference,
+                split: rec.split,
+            };
+            match rec.unit_system.as_str() {
+                "metric" => entry.0.push(split),
+                "imperial" => entry.1.push(split),
+                other => log::warn!("activity {} has splits of unknown unit system {}", rec.activity_id, other),
+            }
+        }
+        by_activity
+    }
+
+    /// Laps of all activities keyed by activity ID
+    pub async fn laps(&mut self) -> HashMap<i64, Vec<ActivityLap>> {
+        let laps = sqlx::query!(
+            r#"
+            SELECT
+                activity_id,
+                lap_index,
+                name,
+                distance,
+                moving_time,
+                elapsed_time,
+                average_speed,
+                average_heartrate,
+                max_heartrate,
+                average_cadence,
+                total_elevation_gain
+            FROM activity_lap ORDER BY activity_id, lap_index
+            "#
+        )
+        .fetch_all(self.pool)
+        .await
+        .unwrap();
+
+        let mut by_activity: HashMap<i64, Vec<ActivityLap>> = HashMap::new();
+        for rec in laps {
+            by_activity.entry(rec.activity_id).or_default().push(ActivityLap {
+                lap_index: rec.lap_index,
+                name: rec.name,
+                distance: rec.distance,
+                moving_time: rec.moving_time,
+                elapsed_time: rec.elapsed_time,
+                average_speed: rec.average_speed,
+                average_heartrate: rec.average_heartrate,
+                max_heartrate: rec.max_heartrate,
+                average_cadence: rec.average_cadence,
+                total_elevation_gain: rec.total_elevation_gain,
+            });
+        }
+        by_activity
+    }
+
+    /// All efforts on the given segment, fastest first
+    pub async fn segment_efforts_for(&mut self, segment_id: i64) -> Vec<SegmentAttempt> {
+        let attempts = sqlx::query!(
+            r#"
+            SELECT
+                activity.id AS activity_id,
+                activity.title AS activity_title,
+                activity.start_date,
+                effort.segment_id,
+                effort.elapsed_time,
+                effort.moving_time,
+                effort.pr_rank,
+                effort.kom_rank
+            FROM activity_segment_effort effort
+            JOIN activity ON activity.id = effort.activity_id
+            WHERE effort.segment_id = ? AND activity.deleted = false
+            ORDER BY effort.elapsed_time, activity.start_date
+            "#,
+            segment_id
+        )
+        .fetch_all(self.pool)
+        .await
+        .unwrap();
+
+        attempts
+            .into_iter()
+            .map(|rec| SegmentAttempt {
+                activity_id: rec.activity_id,
+                activity_title: rec.activity_title,
+                start_date: rec.start_date,
+                effort: ActivitySegmentEffort {
+                    segment_id: rec.segment_id,
+                    elapsed_time: rec.elapsed_time,
+                    moving_time: rec.moving_time,
+                    pr_rank: rec.pr_rank.map(|r| r as u8),
+                    kom_rank: rec.kom_rank.map(|r| r as u8),
+                },
+            })
+            .collect()
+    }
+
+    /// Synced bikes and shoes
+    pub async fn gear(&mut self) -> Vec<Gear> {
+        let gear = sqlx::query!(
+            r#"
+            SELECT id, gear_type, name, brand_name, model_name, distance, is_primary, retired
+            FROM gear ORDER BY name
+            "#
+        )
+        .fetch_all(self.pool)
+        .await
+        .unwrap();
+
+        gear.into_iter()
+            .map(|rec| Gear {
+                gear_type: GearType::from_label(&rec.gear_type),
+                id: rec.id,
+                name: rec.name,
+                brand_name: rec.brand_name,
+                model_name: rec.model_name,
+                distance: rec.distance,
+                primary: rec.is_primary,
+                retired: rec.retired,
+            })
+            .collect()
+    }
+
+    /// The synced athlete, if any
+    pub async fn athlete(&mut self) -> Option<Athlete> {
+        let rec = sqlx::query!(
+            r#"
+            SELECT id, username, firstname, lastname, city, country, weight, ftp, stats
+            FROM athlete ORDER BY updated_at DESC LIMIT 1
+            "#
+        )
+        .fetch_optional(self.pool)
+        .await
+        .unwrap()?;
+
+        Some(Athlete {
+            id: rec.id,
+            username: rec.username,
+            firstname: rec.firstname,
+            lastname: rec.lastname,
+            city: rec.city,
+            country: rec.country,
+            weight: rec.weight,
+            ftp: rec.ftp,
+            stats: rec.stats.and_then(|s| serde_json::from_str(&s).ok()),
+        })
+    }
+
+    /// Downloaded streams of the activity, if any
+    pub async fn streams(&mut self, activity_id: i64) -> Option<Streams> {
+        let rec = sqlx::query!(
+            r#"
+            SELECT streams FROM raw_activity WHERE id = ?
+            "#,
+            activity_id
+        )
+        .fetch_optional(self.pool)
+        .await
+        .unwrap()?;
+
+        serde_json::from_str(rec.streams?.as_str()).ok()
+    }
+
     pub async fn activities(&mut self) -> Activities {
         let activities = sqlx::query!(
             r#"
-            SELECT * FROM activity ORDER BY start_date DESC
+            SELECT * FROM activity WHERE deleted = false ORDER BY start_date DESC
             "#
         )
         .fetch_all(self.pool)
         .await
         .unwrap();
+        let mut efforts = self.segment_efforts().await;
+        let mut splits = self.splits().await;
+        let mut laps = self.laps().await;
+        let gear_names: HashMap<String, String> = self
+            .gear()
+            .await
+            .into_iter()
+            .map(|g| (g.id, g.name))
+            .collect();
 
-        return activities
+        activities
             .iter()
             .map(|rec| {
-                let splits: Vec<ActivitySplit> = if let Some(splits) = &rec.activity_splits {
-                    serde_json::from_str(splits).unwrap()
-                } else {
-                    vec![]
-                };
-                let efforts: Vec<ActivitySegmentEffort> =
-                    if let Some(efforts) = &rec.segment_efforts {
-                        serde_json::from_str(efforts).unwrap()
-                    } else {
-                        vec![]
-                    };
+                let (splits_metric, splits_imperial) =
+                    splits.remove(&rec.id).unwrap_or_default();
+                let efforts = efforts.remove(&rec.id).unwrap_or_default();
+                let power_curve: PowerCurve = rec
+                    .power_curve
+                    .as_ref()
+                    .and_then(|curve| serde_json::from_str(curve).ok())
+                    .unwrap_or_default();
                 Activity {
                     id: rec.id,
                     title: rec.title.clone(),
@@ -426,11 +776,24 @@ impl ActivityStore<'_> {
                     location_city: rec.location_city.clone(),
                     segment_efforts: efforts,
                     athletes: rec.athletes,
-                    splits,
+                    average_watts: rec.average_watts,
+                    weighted_average_watts: rec.weighted_average_watts,
+                    kilojoules: rec.kilojoules,
+                    device_watts: rec.device_watts,
+                    gear_id: rec.gear_id.clone(),
+                    gear_name: rec.gear_id.as_ref().and_then(|id| gear_names.get(id).cloned()),
+                    power_curve,
+                    workout: rec
+                        .workout
+                        .as_ref()
+                        .and_then(|w| serde_json::from_str(w).ok()),
+                    splits_metric,
+                    splits_imperial,
+                    laps: laps.remove(&rec.id).unwrap_or_default(),
                     rank: 0,
                 }
             })
-            .collect();
+            .collect()
     }
 }
 
@@ -449,6 +812,58 @@ impl Activity {
         self.distance / (self.moving_time as f64 / 3600.0)
     }
 
+    /// Ratio of normalized power to the athlete's FTP
+    pub fn intensity_factor(&self, ftp: f64) -> Option<f64> {
+        if ftp <= 0.0 {
+            return None;
+        }
+        self.weighted_average_watts.map(|np| np / ftp)
+    }
+
+    /// Training stress score (TSS) based on normalized power and FTP
+    pub fn training_stress_score(&self, ftp: f64) -> Option<f64> {
+        let np = self.weighted_average_watts?;
+        let intensity = self.intensity_factor(ftp)?;
+        Some((self.moving_time as f64 * np * intensity) / (ftp * 3600.0) * 100.0)
+    }
+
+    /// Name of the gear, or its ID if the gear was not synced
+    pub fn gear(&self) -> Option<String> {
+        self.gear_name.clone().or(self.gear_id.clone())
+    }
+
+    /// Splits per kilometer or per mile
+    pub fn splits(&self, system: &UnitSystem) -> &Vec<ActivitySplit> {
+        match system {
+            UnitSystem::Metric => &self.splits_metric,
+            UnitSystem::Imperial => &self.splits_imperial,
+        }
+    }
+
+    /// Number of splits, laps or reps
+    pub fn section_count(&self, system: &UnitSystem, sections: &Sections) -> usize {
+        match sections {
+            Sections::Splits => self.splits(system).len(),
+            Sections::Laps => self.laps.len(),
+            Sections::Reps => self.workout.as_ref().map_or(0, |w| w.reps.len()),
+        }
+    }
+
+    /// Distance from the start at which the selected split, lap or rep begins and ends (meters)
+    pub fn section(&self, system: &UnitSystem, sections: &Sections, index: usize) -> Option<(f64, f64)> {
+        let distances: Vec<f64> = match sections {
+            Sections::Splits => self.splits(system).iter().map(|s| s.distance).collect(),
+            Sections::Laps => self.laps.iter().map(|l| l.distance).collect(),
+            Sections::Reps => {
+                let rep = self.workout.as_ref()?.reps.get(index)?;
+                return Some((rep.start, rep.start + rep.distance));
+            }
+        };
+        let distance = *distances.get(index)?;
+        let start: f64 = distances.iter().take(index).sum();
+        Some((start, start + distance))
+    }
+
     pub fn activity_category(&self) -> SportType {
         match self.activity_type.as_str() {
             "Ride" => SportType::Ride,
@@ -479,3 +894,199 @@ impl Activity {
         Err("No polyline".to_string())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        component::unit_formatter::UnitFormatter,
+        store::{migration::memory_pool, workout::Interval},
+    };
+
+    use super::*;
+
+    fn lap(distance: f64) -> ActivityLap {
+        ActivityLap {
+            lap_index: 0,
+            name: "Lap".to_string(),
+            distance,
+            moving_time: 180,
+            elapsed_time: 180,
+            average_speed: distance / 180.0,
+            average_heartrate: None,
+            max_heartrate: None,
+            average_cadence: None,
+            total_elevation_gain: 0.0,
+        }
+    }
+
+    fn split(distance: f64) -> ActivitySplit {
+        ActivitySplit {
+            distance,
+            moving_time: 300,
+            elapsed_time: 300,
+            average_speed: distance / 300.0,
+            elevation_difference: 0.0,
+            split: 0,
+        }
+    }
+
+    #[test]
+    fn sections() {
+        let rep = |start: f64| Interval {
+            start,
+            distance: 400.0,
+            time: 80,
+            average_heartrate: None,
+        };
+        let activity = Activity {
+            splits_metric: vec![split(1000.0), split(1000.0), split(400.0)],
+            laps: vec![lap(1600.0), lap(800.0)],
+            workout: Some(Workout {
+                reps: vec![rep(500.0), rep(1100.0)],
+                recoveries: vec![rep(900.0)],
+            }),
+            ..Activity::default()
+        };
+        let metric = UnitSystem::Metric;
+        assert_eq!(Some((1000.0, 2000.0)), activity.section(&metric, &Sections::Splits, 1));
+        assert_eq!(Some((2000.0, 2400.0)), activity.section(&metric, &Sections::Splits, 2));
+        assert_eq!(Some((1600.0, 2400.0)), activity.section(&metric, &Sections::Laps, 1));
+        assert_eq!(None, activity.section(&metric, &Sections::Laps, 2));
+        assert_eq!(Some((1100.0, 1500.0)), activity.section(&metric, &Sections::Reps, 1));
+        assert_eq!(None, activity.section(&UnitSystem::Imperial, &Sections::Splits, 0));
+        assert_eq!(2, activity.section_count(&metric, &Sections::Reps));
+    }
+
+    #[tokio::test]
+    async fn splits() {
+        let pool = memory_pool().await;
+        sqlx::query(
+            r#"
+            INSERT INTO activity (
+                id, title, description, activity_type, distance, moving_time, elapsed_time,
+                total_elevation_gain, sport_type, kudos, athletes
+            ) VALUES (1, 'Run', '', 'Run', 2500, 750, 750, 0, 'Run', 0, 1);
+            INSERT INTO activity_split (
+                activity_id, unit_system, split, distance, moving_time, elapsed_time, average_speed, elevation_difference
+            ) VALUES
+                (1, 'metric', 2, 1000, 300, 300, 3.33, 1),
+                (1, 'metric', 1, 1000, 290, 290, 3.45, 2),
+                (1, 'metric', 3, 500, 160, 160, 3.13, 0),
+                (1, 'imperial', 1, 1609.3, 470, 470, 3.42, 3),
+                (1, 'imperial', 2, 890.7, 280, 280, 3.18, 0),
+                (1, 'nautical', 1, 1852, 550, 550, 3.37, 0);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let activities = ActivityStore::new(&pool).activities().await;
+        let activity = activities.find(1).unwrap();
+        // ordered by split, splits of an unknown unit system are ignored
+        assert_eq!(
+            vec![(1, 1000.0), (2, 1000.0), (3, 500.0)],
+            activity.splits_metric.iter().map(|s| (s.split, s.distance)).collect::<Vec<(i64, f64)>>()
+        );
+        assert_eq!(
+            vec![(1, 1609.3), (2, 890.7)],
+            activity.splits_imperial.iter().map(|s| (s.split, s.distance)).collect::<Vec<(i64, f64)>>()
+        );
+
+        // the splits follow the unit toggle
+        let formatter = UnitFormatter::imperial();
+        assert_eq!(2, activity.splits(&formatter.system).len());
+        assert_eq!(3, activity.splits(&formatter.toggle().system).len());
+        assert_eq!(2, activity.splits(&formatter.toggle().toggle().system).len());
+    }
+
+    #[test]
+    fn training_stress() {
+        let activity = Activity {
+            moving_time: 3600,
+            weighted_average_watts: Some(200.0),
+            ..Activity::default()
+        };
+        assert_eq!(Some(0.8), activity.intensity_factor(250.0));
+        assert_eq!(Some(64.0), activity.training_stress_score(250.0).map(f64::round));
+        // an unset or invalid FTP gives no IF or TSS instead of inf or NaN
+        assert_eq!(None, activity.intensity_factor(0.0));
+        assert_eq!(None, activity.training_stress_score(0.0));
+        assert_eq!(None, activity.training_stress_score(-100.0));
+    }
+
+    #[tokio::test]
+    async fn invalid_json_columns() {
+        let pool = memory_pool().await;
+        sqlx::query(
+            r#"
+            INSERT INTO activity (
+                id, title, description, activity_type, distance, moving_time, elapsed_time,
+                total_elevation_gain, sport_type, kudos, athletes, power_curve, workout
+            ) VALUES (1, 'Ride', '', 'Ride', 20000, 2400, 2400, 0, 'Ride', 0, 1, 'not json', '{');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let activities = ActivityStore::new(&pool).activities().await;
+        let activity = activities.find(1).unwrap();
+        assert!(activity.power_curve.is_empty());
+        assert!(activity.workout.is_none());
+    }
+
+    #[tokio::test]
+    async fn segment_attempts() {
+        let pool = memory_pool().await;
+        sqlx::query(
+            r#"
+            INSERT INTO activity (
+                id, title, description, activity_type, distance, moving_time, elapsed_time,
+                total_elevation_gain, sport_type, kudos, athletes, start_date
+            ) VALUES
+                (1, 'Later', '', 'Run', 5000, 1500, 1500, 0, 'Run', 0, 1, '2024-02-01 08:00:00'),
+                (2, 'Earlier', '', 'Run', 5000, 1500, 1500, 0, 'Run', 0, 1, '2024-01-01 08:00:00');
+            INSERT INTO activity (
+                id, title, description, activity_type, distance, moving_time, elapsed_time,
+                total_elevation_gain, sport_type, kudos, athletes, start_date, deleted
+            ) VALUES (3, 'Deleted', '', 'Run', 5000, 1500, 1500, 0, 'Run', 0, 1, '2024-03-01 08:00:00', true);
+            INSERT INTO activity_segment_effort (activity_id, segment_id, elapsed_time, moving_time, pr_rank)
+            VALUES (1, 10, 120, 118, 1), (1, 11, 300, 300, NULL), (2, 10, 130, 125, NULL), (3, 10, 100, 100, NULL);
+            INSERT INTO segment (id, name, activity_type, distance) VALUES (10, 'Hill', 'Run', 400);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut store = ActivityStore::new(&pool);
+        assert_eq!(vec!["Hill"], store.segments().await.values().map(|s| s.name.clone()).collect::<Vec<String>>());
+        let efforts = store.segment_efforts().await;
+        assert_eq!(vec![10, 11], efforts[&1].iter().map(|e| e.segment_id).collect::<Vec<i64>>());
+        assert_eq!(Some(1), efforts[&1][0].pr_rank);
+
+        // fastest first, without the efforts of deleted activities
+        assert_eq!(
+            vec![(1, 120), (2, 130)],
+            store
+                .segment_efforts_for(10)
+                .await
+                .iter()
+                .map(|a| (a.activity_id, a.effort.elapsed_time))
+                .collect::<Vec<(i64, i64)>>()
+        );
+        assert_eq!("Later", store.segment_efforts_for(11).await[0].activity_title);
+        assert!(store.segment_efforts_for(12).await.is_empty());
+
+        let attempts = store.activities().await.segment_attempts();
+        assert_eq!(
+            vec![(2, 130), (1, 120)],
+            attempts[&10]
+                .iter()
+                .map(|a| (a.activity_id, a.effort.elapsed_time))
+                .collect::<Vec<(i64, i64)>>()
+        );
+        assert_eq!(1, attempts[&11].len());
+    }
+}
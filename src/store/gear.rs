@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+
+use crate::client::AthleteStats;
+
+use super::activity::Activities;
+
+/// Distance after which shoes are due for retirement if not configured (meters)
+pub const DEFAULT_SHOE_RETIREMENT: f64 = 800_000.0;
+
+/// Share of the retirement distance from which the gear is highlighted
+pub const RETIREMENT_WARNING: f64 = 0.9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GearType {
+    Bike,
+    Shoe,
+}
+
+impl GearType {
+    /// Strava prefixes the IDs of bikes with `b` and the IDs of shoes with `g`
+    pub fn from_id(id: &str) -> GearType {
+        match id.starts_with('b') {
+            true => GearType::Bike,
+            false => GearType::Shoe,
+        }
+    }
+
+    pub fn from_label(label: &str) -> GearType {
+        match label {
+            "bike" => GearType::Bike,
+            _ => GearType::Shoe,
+        }
+    }
+
+    pub fn to_label(&self) -> &str {
+        match self {
+            GearType::Bike => "bike",
+            GearType::Shoe => "shoe",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Gear {
+    pub id: String,
+    pub gear_type: GearType,
+    pub name: String,
+    pub brand_name: Option<String>,
+    pub model_name: Option<String>,
+    /// Distance recorded with the gear on Strava, including activities which were not synced (meters)
+    pub distance: f64,
+    pub primary: bool,
+    pub retired: bool,
+}
+
+impl Gear {
+    /// Brand and model, e.g. `Nike Pegasus 40`
+    pub fn model(&self) -> String {
+        [&self.brand_name, &self.model_name]
+            .iter()
+            .filter_map(|s| s.as_deref())
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Athlete {
+    pub id: i64,
+    pub username: Option<String>,
+    pub firstname: String,
+    pub lastname: String,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub weight: Option<f64>,
+    pub ftp: Option<i64>,
+    pub stats: Option<AthleteStats>,
+}
+
+impl Athlete {
+    pub fn name(&self) -> String {
+        format!("{} {}", self.firstname, self.lastname).trim().to_string()
+    }
+}
+
+/// Distances after which gear is due for retirement (meters)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetirementThresholds {
+    pub shoe: Option<f64>,
+    pub bike: Option<f64>,
+    /// Thresholds of individual gear by ID or name, 0 for none
+    pub gear: HashMap<String, f64>,
+}
+
+impl Default for RetirementThresholds {
+    fn default() -> Self {
+        Self {
+            shoe: Some(DEFAULT_SHOE_RETIREMENT),
+            bike: None,
+            gear: HashMap::new(),
+        }
+    }
+}
+
+impl RetirementThresholds {
+    pub fn threshold(&self, gear: &Gear) -> Option<f64> {
+        match self.gear.get(&gear.id).or(self.gear.get(&gear.name)) {
+            Some(distance) => (*distance > 0.0).then_some(*distance),
+            None => match gear.gear_type {
+                GearType::Shoe => self.shoe,
+                GearType::Bike => self.bike,
+            },
+        }
+    }
+}
+
+/// Use of a bike or pair of shoes in the synced activities
+#[derive(Debug, Clone)]
+pub struct GearUsage {
+    pub gear: Gear,
+    pub activities: usize,
+    /// Distance of the synced activities (meters)
+    pub distance: f64,
+    pub moving_time: i64,
+    pub last_used: Option<NaiveDateTime>,
+    pub threshold: Option<f64>,
+}
+
+impl GearUsage {
+    /// Share of the retirement distance which has been covered
+    pub fn wear(&self) -> Option<f64> {
+        self.threshold.map(|t| self.gear.distance / t)
+    }
+
+    /// True if the gear is in use and has (almost) reached its retirement distance
+    pub fn is_worn(&self) -> bool {
+        !self.gear.retired && self.wear().is_some_and(|w| w >= RETIREMENT_WARNING)
+    }
+
+    pub fn is_due(&self) -> bool {
+        !self.gear.retired && self.wear().is_some_and(|w| w >= 1.0)
+    }
+}
+
+/// Usage of each bike and pair of shoes, gear in use first and then by distance.
+/// Gear which is used by activities but was not synced is listed by its ID.
+pub fn gear_usage(
+    gear: &[Gear],
+    activities: &Activities,
+    thresholds: &RetirementThresholds,
+) -> Vec<GearUsage> {
+    let mut usage: Vec<GearUsage> = gear
+        .iter()
+        .map(|g| GearUsage {
+            gear: g.clone(),
+            activities: 0,
+            distance: 0.0,
+            moving_time: 0,
+            last_used: None,
+            threshold: thresholds.threshold(g),
+        })
+        .collect();
+
+    for activity in activities.to_vec() {
+        let id = match &activity.gear_id {
+            Some(id) => id,
+            None => continue,
+        };
+        let index = match usage.iter().position(|u| &u.gear.id == id) {
+            Some(index) => index,
+            None => {
+                let unknown = Gear {
+                    id: id.clone(),
+                    gear_type: GearType::from_id(id),
+                    name: id.clone(),
+                    brand_name: None,
+                    model_name: None,
+                    distance: 0.0,
+                    primary: false,
+                    retired: false,
+                };
+                usage.push(GearUsage {
+                    threshold: thresholds.threshold(&unknown),
+                    gear: unknown,
+                    activities: 0,
+                    distance: 0.0,
+                    moving_time: 0,
+                    last_used: None,
+                });
+                usage.len() - 1
+            }
+        };
+        let entry = &mut usage[index];
+        entry.activities += 1;
+        entry.distance += activity.distance;
+        entry.moving_time += activity.moving_time;
+        entry.last_used = entry.last_used.max(activity.start_date);
+    }
+
+    for entry in usage.iter_mut() {
+        // the distance on Strava is unknown for gear which was not synced
+        entry.gear.distance = entry.gear.distance.max(entry.distance);
+    }
+    usage.sort_by(|a, b| {
+        a.gear
+            .retired
+            .cmp(&b.gear.retired)
+            .then(b.gear.distance.total_cmp(&a.gear.distance))
+    });
+    usage
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use crate::store::activity::Activity;
+
+    use super::*;
+
+    fn gear(id: &str, name: &str, distance: f64, retired: bool) -> Gear {
+        Gear {
+            id: id.to_string(),
+            gear_type: GearType::from_id(id),
+            name: name.to_string(),
+            brand_name: None,
+            model_name: None,
+            distance,
+            primary: false,
+            retired,
+        }
+    }
+
+    fn activity(gear_id: Option<&str>, day: u32, distance: f64) -> Activity {
+        Activity {
+            gear_id: gear_id.map(|g| g.to_string()),
+            distance,
+            moving_time: 1800,
+            start_date: NaiveDate::from_ymd_opt(2024, 5, day)
+                .unwrap()
+                .and_hms_opt(8, 0, 0),
+            ..Activity::default()
+        }
+    }
+
+    #[test]
+    fn thresholds() {
+        let thresholds = RetirementThresholds {
+            shoe: Some(800_000.0),
+            bike: None,
+            gear: HashMap::from([("Racers".to_string(), 400_000.0), ("g3".to_string(), 0.0)]),
+        };
+        assert_eq!(Some(800_000.0), thresholds.threshold(&gear("g1", "Trainers", 0.0, false)));
+        assert_eq!(Some(400_000.0), thresholds.threshold(&gear("g2", "Racers", 0.0, false)));
+        assert_eq!(None, thresholds.threshold(&gear("b1", "Roadie", 0.0, false)));
+        assert_eq!(None, thresholds.threshold(&gear("g3", "Spikes", 0.0, false)));
+    }
+
+    #[test]
+    fn usage_per_gear() {
+        let usage = gear_usage(
+            &[
+                gear("g1", "Trainers", 790_000.0, false),
+                gear("g2", "Old", 900_000.0, true),
+                gear("b1", "Roadie", 5_000_000.0, false),
+            ],
+            &Activities::from(vec![
+                activity(Some("g1"), 1, 10_000.0),
+                activity(Some("g1"), 3, 5_000.0),
+                activity(Some("g3"), 2, 8_000.0),
+                activity(None, 4, 1_000.0),
+            ]),
+            &RetirementThresholds::default(),
+        );
+
+        assert_eq!(
+            vec!["b1", "g1", "g3", "g2"],
+            usage.iter().map(|u| u.gear.id.as_str()).collect::<Vec<&str>>()
+        );
+        let trainers = &usage[1];
+        assert_eq!(2, trainers.activities);
+        assert_eq!(15_000.0, trainers.distance);
+        assert_eq!(3600, trainers.moving_time);
+        assert_eq!(
+            NaiveDate::from_ymd_opt(2024, 5, 3).unwrap().and_hms_opt(8, 0, 0),
+            trainers.last_used
+        );
+        assert!(trainers.is_worn());
+        assert!(!trainers.is_due());
+
+        // gear which was not synced is known by its ID and synced distance
+        assert_eq!("g3", usage[2].gear.name);
+        assert_eq!(8_000.0, usage[2].gear.distance);
+        // retired gear is never due
+        assert!(!usage[3].is_due());
+        assert!(usage[0].wear().is_none());
+    }
+}
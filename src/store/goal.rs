@@ -0,0 +1,254 @@
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::expr::evaluator::Evaluator;
+
+use super::activity::{Activities, Activity};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalPeriod {
+    Week,
+    Month,
+    Year,
+}
+
+impl GoalPeriod {
+    pub fn to_label(&self) -> &str {
+        match self {
+            GoalPeriod::Week => "week",
+            GoalPeriod::Month => "month",
+            GoalPeriod::Year => "year",
+        }
+    }
+
+    /// First day of the period containing the given date
+    pub fn start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            GoalPeriod::Week => date - Days::new(date.weekday().num_days_from_monday() as u64),
+            GoalPeriod::Month => date.with_day(1).unwrap(),
+            GoalPeriod::Year => date.with_ordinal(1).unwrap(),
+        }
+    }
+
+    /// First day of the period following the one starting on `start`
+    pub fn next(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            GoalPeriod::Week => start + Days::new(7),
+            GoalPeriod::Month => start + Months::new(1),
+            GoalPeriod::Year => start + Months::new(12),
+        }
+    }
+
+    /// First day of the period preceding the one starting on `start`
+    pub fn previous(&self, start: NaiveDate) -> NaiveDate {
+        self.start(start - Days::new(1))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalMetric {
+    Distance,
+    Time,
+    Elevation,
+}
+
+impl GoalMetric {
+    pub fn value(&self, activity: &Activity) -> f64 {
+        match self {
+            GoalMetric::Distance => activity.distance,
+            GoalMetric::Time => activity.moving_time as f64,
+            GoalMetric::Elevation => activity.total_elevation_gain,
+        }
+    }
+}
+
+/// A distance (meters), time (seconds) or elevation (meters) target to reach
+/// in each week, month or year.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Goal {
+    pub name: String,
+    pub period: GoalPeriod,
+    pub metric: GoalMetric,
+    pub target: f64,
+    /// Only activities matching this filter expression count towards the goal
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodResult {
+    pub start: NaiveDate,
+    pub total: f64,
+    pub hit: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct GoalProgress {
+    pub goal: Goal,
+    pub current: f64,
+    /// Total at the end of the period if the current rate is kept up
+    pub projected: f64,
+    /// Completed periods, oldest first
+    pub history: Vec<PeriodResult>,
+}
+
+impl GoalProgress {
+    pub fn percent(&self) -> f64 {
+        match self.goal.target {
+            t if t > 0.0 => self.current / t * 100.0,
+            _ => 0.0,
+        }
+    }
+}
+
+impl Goal {
+    /// Progress of the period containing `now` and the results of the `history` periods before it.
+    /// Periods start at midnight in the time zone of `now`.
+    pub fn progress<Tz: TimeZone>(&self, activities: &Activities, now: DateTime<Tz>, history: usize) -> GoalProgress {
+        let timezone = now.timezone();
+        let now = now.naive_local();
+        let activities = match &self.filter {
+            Some(filter) => {
+                let mut evaluator = Evaluator::new();
+                match evaluator.parse(filter) {
+                    Ok(expr) => activities.by_expr(&evaluator, &expr),
+                    Err(_) => Activities::new(),
+                }
+            }
+            None => activities.clone(),
+        };
+        let total = |from: NaiveDate, to: NaiveDate| -> f64 {
+            activities
+                .to_vec()
+                .iter()
+                .filter(|a| {
+                    a.start_date
+                        .map(|d| timezone.from_utc_datetime(&d).date_naive())
+                        .is_some_and(|d| d >= from && d < to)
+                })
+                .map(|a| self.metric.value(a))
+                .sum()
+        };
+
+        let start = self.period.start(now.date());
+        let end = self.period.next(start);
+        let current = total(start, end);
+        let period_seconds = (end - start).num_seconds() as f64;
+        let elapsed_seconds = (now - start.and_hms_opt(0, 0, 0).unwrap()).num_seconds() as f64;
+        let projected = match elapsed_seconds {
+            s if s > 0.0 => current * period_seconds / s,
+            _ => current,
+        };
+
+        let mut results = vec![];
+        let mut period_start = start;
+        for _ in 0..history {
+            let period_end = period_start;
+            period_start = self.period.previous(period_start);
+            let total = total(period_start, period_end);
+            results.push(PeriodResult {
+                start: period_start,
+                total,
+                hit: total >= self.target,
+            });
+        }
+        results.reverse();
+
+        GoalProgress {
+            goal: self.clone(),
+            current,
+            projected,
+            history: results,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::FixedOffset;
+
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn run(day: NaiveDate, distance: f64) -> Activity {
+        Activity {
+            activity_type: "Run".to_string(),
+            distance,
+            start_date: day.and_hms_opt(8, 0, 0),
+            ..Activity::default()
+        }
+    }
+
+    #[test]
+    fn period_boundaries() {
+        // 2024-03-14 is a Thursday
+        assert_eq!(date(2024, 3, 11), GoalPeriod::Week.start(date(2024, 3, 14)));
+        assert_eq!(date(2024, 3, 1), GoalPeriod::Month.start(date(2024, 3, 14)));
+        assert_eq!(date(2024, 4, 1), GoalPeriod::Month.next(date(2024, 3, 1)));
+        assert_eq!(date(2024, 2, 1), GoalPeriod::Month.previous(date(2024, 3, 1)));
+        assert_eq!(date(2023, 1, 1), GoalPeriod::Year.previous(date(2024, 1, 1)));
+    }
+
+    #[test]
+    fn weekly_progress() {
+        let goal = Goal {
+            name: "Running".to_string(),
+            period: GoalPeriod::Week,
+            metric: GoalMetric::Distance,
+            target: 20000.0,
+            filter: Some("type = \"Run\"".to_string()),
+        };
+        let mut ride = run(date(2024, 3, 12), 50000.0);
+        ride.activity_type = "Ride".to_string();
+        let activities = Activities::from(vec![
+            run(date(2024, 3, 11), 5000.0),
+            run(date(2024, 3, 12), 5000.0),
+            ride,
+            run(date(2024, 3, 5), 25000.0),
+            run(date(2024, 2, 27), 10000.0),
+        ]);
+
+        // half way through the week
+        let now = date(2024, 3, 14).and_hms_opt(12, 0, 0).unwrap().and_utc();
+        let progress = goal.progress(&activities, now, 2);
+
+        assert_eq!(10000.0, progress.current);
+        assert_eq!(20000.0, progress.projected);
+        assert_eq!(50.0, progress.percent());
+        assert_eq!(
+            vec![(date(2024, 2, 26), false), (date(2024, 3, 4), true)],
+            progress
+                .history
+                .iter()
+                .map(|r| (r.start, r.hit))
+                .collect::<Vec<(NaiveDate, bool)>>()
+        );
+    }
+
+    #[test]
+    fn local_periods() {
+        let goal = Goal {
+            name: "Running".to_string(),
+            period: GoalPeriod::Week,
+            metric: GoalMetric::Distance,
+            target: 20000.0,
+            filter: None,
+        };
+        // Sunday night in UTC is already Monday two hours east of it
+        let mut late = run(date(2024, 3, 10), 5000.0);
+        late.start_date = date(2024, 3, 10).and_hms_opt(23, 30, 0);
+        let activities = Activities::from(vec![late]);
+
+        let now = date(2024, 3, 14).and_hms_opt(12, 0, 0).unwrap();
+        let utc = goal.progress(&activities, now.and_utc(), 1);
+        assert_eq!((0.0, 5000.0), (utc.current, utc.history[0].total));
+
+        let east = FixedOffset::east_opt(2 * 3600).unwrap();
+        let local = goal.progress(&activities, east.from_local_datetime(&now).unwrap(), 1);
+        assert_eq!((5000.0, 0.0), (local.current, local.history[0].total));
+    }
+}
@@ -1,5 +1,3 @@
-
-
 use sqlx::SqlitePool;
 
 pub async fn run_migrations(pool: &SqlitePool) {
@@ -8,3 +6,122 @@ pub async fn run_migrations(pool: &SqlitePool) {
 
 
 }
+
+/// A migrated in-memory database
+#[cfg(test)]
+pub(crate) async fn memory_pool() -> SqlitePool {
+    // every connection would have its own in-memory database
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    run_migrations(&pool).await;
+    pool
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use sqlx::{migrate::Migrator, sqlite::SqlitePoolOptions};
+
+    use super::*;
+
+    /// An in-memory database with the migrations older than the given version
+    async fn pool_before(version: i64) -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let migrator = sqlx::migrate!("./migrations");
+        let migrations = migrator
+            .migrations
+            .iter()
+            .filter(|m| m.version < version)
+            .cloned()
+            .collect();
+        Migrator {
+            migrations: Cow::Owned(migrations),
+            ..migrator
+        }
+        .run(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn segment_efforts_are_backfilled() {
+        let pool = pool_before(20241202000000).await;
+        sqlx::query(
+            r#"
+            INSERT INTO activity (
+                id, title, description, activity_type, distance, moving_time, elapsed_time,
+                total_elevation_gain, sport_type, kudos, athletes, segment_efforts
+            ) VALUES (
+                1, 'Run', '', 'Run', 5000, 1500, 1600, 20, 'Run', 0, 1,
+                '[{"segment_id": 10, "elapsed_time": 120, "moving_time": 118, "pr_rank": 1, "kom_rank": null},
+                  {"segment_id": 11, "elapsed_time": 300, "moving_time": 300, "pr_rank": null, "kom_rank": null}]'
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        run_migrations(&pool).await;
+
+        let efforts: Vec<(i64, i64, i64, i64, Option<i64>)> = sqlx::query_as(
+            "SELECT activity_id, segment_id, elapsed_time, moving_time, pr_rank FROM activity_segment_effort ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            vec![(1, 10, 120, 118, Some(1)), (1, 11, 300, 300, None)],
+            efforts
+        );
+    }
+
+    #[tokio::test]
+    async fn power_is_backfilled() {
+        let pool = pool_before(20241201000000).await;
+        sqlx::query(
+            r#"
+            INSERT INTO activity (
+                id, title, description, activity_type, distance, moving_time, elapsed_time,
+                total_elevation_gain, sport_type, kudos, athletes
+            ) VALUES
+                (1, 'Ride', '', 'Ride', 40000, 5000, 5000, 300, 'Ride', 0, 1),
+                (2, 'Run', '', 'Run', 10000, 3000, 3000, 50, 'Run', 0, 1);
+            INSERT INTO raw_activity (id, listed, activity, synced, created_at) VALUES
+                (1, '{"average_watts": 180}',
+                 '{"average_watts": 185.5, "weighted_average_watts": 200, "kilojoules": 925, "device_watts": true}',
+                 true, '2024-01-02 08:00:00'),
+                (2, '{}', NULL, true, '2024-01-01 08:00:00');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        run_migrations(&pool).await;
+
+        type Power = (i64, Option<f64>, Option<f64>, Option<f64>, bool);
+        let power: Vec<Power> = sqlx::query_as(
+            "SELECT id, average_watts, weighted_average_watts, kilojoules, device_watts FROM activity ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            vec![
+                (1, Some(185.5), Some(200.0), Some(925.0), true),
+                (2, None, None, None, false)
+            ],
+            power
+        );
+    }
+}
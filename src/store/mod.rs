@@ -1,4 +1,10 @@
 pub mod activity;
 pub mod db;
+pub mod gear;
+pub mod goal;
 pub mod migration;
 pub mod polyline_compare;
+pub mod power_curve;
+pub mod session;
+pub mod stats;
+pub mod workout;
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+
+/// Durations (in seconds) at which the mean-maximal power is recorded
+pub const POWER_CURVE_DURATIONS: [i64; 12] = [1, 5, 10, 30, 60, 120, 300, 600, 1200, 1800, 3600, 7200];
+
+/// Samples further apart than this are considered a pause and padded with zero watts
+const MAX_SAMPLE_GAP: i64 = 5;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PowerCurvePoint {
+    pub duration: i64,
+    pub watts: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct PowerCurve {
+    pub points: Vec<PowerCurvePoint>,
+}
+
+impl PowerCurve {
+    /// Calculate the mean-maximal power for each duration from a 1Hz watts series
+    pub fn from_watts(watts: &[f64]) -> PowerCurve {
+        let mut sums = vec![0.0; watts.len() + 1];
+        for (i, w) in watts.iter().enumerate() {
+            sums[i + 1] = sums[i] + w;
+        }
+
+        let points = POWER_CURVE_DURATIONS
+            .iter()
+            .filter(|d| **d as usize <= watts.len())
+            .map(|d| {
+                let d = *d as usize;
+                let best = (d..sums.len())
+                    .map(|end| sums[end] - sums[end - d])
+                    .fold(0.0, f64::max);
+                PowerCurvePoint {
+                    duration: d as i64,
+                    watts: best / d as f64,
+                }
+            })
+            .collect();
+
+        PowerCurve { points }
+    }
+
+    /// Calculate the curve from a (possibly irregular) Strava time and watts stream
+    pub fn from_stream(time: &[i64], watts: &[Option<f64>]) -> PowerCurve {
+        PowerCurve::from_watts(&resample(time, watts))
+    }
+
+    /// Combine curves taking the best power at each duration
+    pub fn best_of<'a>(curves: impl Iterator<Item = &'a PowerCurve>) -> PowerCurve {
+        let mut best = PowerCurve::default();
+        for curve in curves {
+            for point in curve.points.iter() {
+                match best.points.iter_mut().find(|p| p.duration == point.duration) {
+                    Some(p) => p.watts = p.watts.max(point.watts),
+                    None => best.points.push(point.clone()),
+                }
+            }
+        }
+        best.points.sort_by_key(|p| p.duration);
+        best
+    }
+
+    pub fn watts_at(&self, duration: i64) -> Option<f64> {
+        self.points
+            .iter()
+            .find(|p| p.duration == duration)
+            .map(|p| p.watts)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+fn resample(time: &[i64], watts: &[Option<f64>]) -> Vec<f64> {
+    let mut resampled = vec![];
+    for (i, (t, w)) in time.iter().zip(watts.iter()).enumerate() {
+        let next = time.get(i + 1).copied().unwrap_or(t + 1);
+        let gap = next - t;
+        let value = w.unwrap_or(0.0);
+        for s in 0..gap.max(0) {
+            resampled.push(if gap > MAX_SAMPLE_GAP && s > 0 { 0.0 } else { value });
+        }
+    }
+    resampled
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn power_curve_from_watts() {
+        let curve = PowerCurve::from_watts(&[100.0, 300.0, 200.0, 100.0, 100.0, 100.0]);
+        assert_eq!(Some(300.0), curve.watts_at(1));
+        assert_eq!(Some(160.0), curve.watts_at(5));
+        assert_eq!(None, curve.watts_at(10));
+    }
+
+    #[test]
+    pub fn power_curve_from_irregular_stream() {
+        let curve = PowerCurve::from_stream(
+            &[0, 1, 3, 10],
+            &[Some(100.0), Some(200.0), None, Some(50.0)],
+        );
+        // 100, 200, 200, 0 (null), 0 x 6 (pause), 50
+        assert_eq!(Some(200.0), curve.watts_at(1));
+        assert_eq!(Some(100.0), curve.watts_at(5));
+        assert_eq!(Some(50.0), curve.watts_at(10));
+    }
+
+    #[test]
+    pub fn power_curve_best_of() {
+        let c1 = PowerCurve::from_watts(&[300.0, 100.0, 100.0, 100.0, 100.0]);
+        let c2 = PowerCurve::from_watts(&[200.0, 200.0, 200.0, 200.0, 200.0]);
+        let best = PowerCurve::best_of([c1, c2].iter());
+        assert_eq!(Some(300.0), best.watts_at(1));
+        assert_eq!(Some(200.0), best.watts_at(5));
+    }
+}
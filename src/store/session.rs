@@ -0,0 +1,82 @@
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::component::unit_formatter::UnitSystem;
+
+use super::activity::{SortBy, SortOrder};
+
+/// State of the activity list which is saved on quit and restored on start
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Session {
+    pub sort_by: SortBy,
+    pub sort_order: SortOrder,
+    pub rank_by: SortBy,
+    pub rank_order: SortOrder,
+    pub filter: String,
+    pub anchor_tolerance: f64,
+    pub unit_system: UnitSystem,
+    /// ID of the activity the list is anchored to
+    pub anchored: Option<i64>,
+    /// Index of the selected row in the activity list
+    pub selected: Option<usize>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            sort_by: SortBy::Date,
+            sort_order: SortOrder::Desc,
+            rank_by: SortBy::Pace,
+            rank_order: SortOrder::Desc,
+            filter: "".to_string(),
+            anchor_tolerance: 0.005,
+            unit_system: UnitSystem::Imperial,
+            anchored: None,
+            selected: None,
+        }
+    }
+}
+
+impl Session {
+    /// Session saved at `path`, or the defaults if there is no (valid) saved session
+    pub fn load(path: Option<&Path>, defaults: Session) -> Session {
+        path.and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or(defaults)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), anyhow::Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("strava-rs-session-{}.json", std::process::id()));
+        let defaults = Session {
+            unit_system: UnitSystem::Metric,
+            ..Session::default()
+        };
+        assert_eq!(defaults, Session::load(Some(&path), defaults.clone()));
+
+        let session = Session {
+            sort_by: SortBy::HeartRate,
+            filter: "distance > 10km".to_string(),
+            anchored: Some(1234),
+            selected: Some(3),
+            ..defaults.clone()
+        };
+        session.save(&path).unwrap();
+        assert_eq!(session, Session::load(Some(&path), defaults.clone()));
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(defaults, Session::load(Some(&path), defaults.clone()));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,242 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use crossterm::event::KeyCode;
+use strum::{EnumIter, IntoEnumIterator};
+
+use super::activity::{Activities, Activity};
+
+#[derive(EnumIter, Clone, Debug, PartialEq)]
+pub enum GroupBy {
+    Week,
+    Month,
+    Year,
+    Type,
+    Gear,
+}
+
+impl GroupBy {
+    pub fn to_key(&self) -> char {
+        match *self {
+            GroupBy::Week => 'w',
+            GroupBy::Month => 'm',
+            GroupBy::Year => 'y',
+            GroupBy::Type => 't',
+            GroupBy::Gear => 'g',
+        }
+    }
+
+    pub fn to_label(&self) -> &str {
+        match *self {
+            GroupBy::Week => "week",
+            GroupBy::Month => "month",
+            GroupBy::Year => "year",
+            GroupBy::Type => "type",
+            GroupBy::Gear => "gear",
+        }
+    }
+
+    pub fn from_key(key: KeyCode) -> Option<GroupBy> {
+        GroupBy::iter().find(|g| KeyCode::Char(g.to_key()) == key)
+    }
+
+    /// Key of the group the activity belongs to
+    pub fn key(&self, activity: &Activity) -> Option<String> {
+        match self {
+            GroupBy::Week => activity.start_date.map(|d| d.format("%G-W%V").to_string()),
+            GroupBy::Month => activity.start_date.map(|d| d.format("%Y-%m").to_string()),
+            GroupBy::Year => activity.start_date.map(|d| d.format("%Y").to_string()),
+            GroupBy::Type => Some(activity.activity_type.clone()),
+            GroupBy::Gear => Some(activity.gear().unwrap_or("none".to_string())),
+        }
+    }
+
+    /// Key of the same period in the previous year, only for the date groupings
+    pub fn previous_year_key(&self, key: &str) -> Option<String> {
+        match self {
+            GroupBy::Week | GroupBy::Month | GroupBy::Year => {
+                let (year, rest) = key.split_at(4.min(key.len()));
+                let year: i32 = year.parse().ok()?;
+                Some(format!("{}{}", year - 1, rest))
+            }
+            GroupBy::Type | GroupBy::Gear => None,
+        }
+    }
+
+    fn is_chronological(&self) -> bool {
+        matches!(self, GroupBy::Week | GroupBy::Month | GroupBy::Year)
+    }
+}
+
+impl Display for GroupBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_label())
+    }
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GroupBy::iter().find(|g| g.to_label() == s).ok_or_else(|| {
+            format!(
+                "unknown grouping `{}`, expected one of: {}",
+                s,
+                GroupBy::iter().map(|g| g.to_label().to_string()).collect::<Vec<String>>().join(", ")
+            )
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GroupStats {
+    pub key: String,
+    pub count: usize,
+    pub distance: f64,
+    pub moving_time: i64,
+    pub elevation: f64,
+    heartrate_time: i64,
+    heartrate_total: f64,
+}
+
+impl GroupStats {
+    fn new(key: String) -> GroupStats {
+        GroupStats {
+            key,
+            ..GroupStats::default()
+        }
+    }
+
+    fn add(&mut self, activity: &Activity) {
+        self.count += 1;
+        self.distance += activity.distance;
+        self.moving_time += activity.moving_time;
+        self.elevation += activity.total_elevation_gain;
+        if let Some(heartrate) = activity.average_heartrate {
+            self.heartrate_time += activity.moving_time;
+            self.heartrate_total += heartrate * activity.moving_time as f64;
+        }
+    }
+
+    pub fn average_distance(&self) -> f64 {
+        self.distance / self.count as f64
+    }
+
+    pub fn average_time(&self) -> i64 {
+        self.moving_time / self.count as i64
+    }
+
+    pub fn average_elevation(&self) -> f64 {
+        self.elevation / self.count as f64
+    }
+
+    /// Average heart rate weighted by the moving time of the activities which recorded it
+    pub fn average_heartrate(&self) -> Option<f64> {
+        match self.heartrate_time {
+            0 => None,
+            t => Some(self.heartrate_total / t as f64),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupedStats {
+    pub group: GroupStats,
+    pub previous_year: Option<GroupStats>,
+}
+
+impl GroupedStats {
+    /// Change in distance compared to the same period in the previous year as a percentage
+    pub fn distance_change(&self) -> Option<f64> {
+        let previous = self.previous_year.as_ref()?;
+        if previous.distance == 0.0 {
+            return None;
+        }
+        Some((self.group.distance - previous.distance) / previous.distance * 100.0)
+    }
+}
+
+/// Aggregate the activities into groups, most recent (or largest) first
+pub fn group_stats(activities: &Activities, group_by: &GroupBy) -> Vec<GroupedStats> {
+    let mut groups: HashMap<String, GroupStats> = HashMap::new();
+    for activity in activities.to_vec() {
+        if let Some(key) = group_by.key(&activity) {
+            groups
+                .entry(key.clone())
+                .or_insert_with(|| GroupStats::new(key))
+                .add(&activity);
+        }
+    }
+
+    let mut stats: Vec<GroupedStats> = groups
+        .values()
+        .map(|group| GroupedStats {
+            group: group.clone(),
+            previous_year: group_by
+                .previous_year_key(&group.key)
+                .and_then(|key| groups.get(&key).cloned()),
+        })
+        .collect();
+
+    match group_by.is_chronological() {
+        true => stats.sort_by(|a, b| b.group.key.cmp(&a.group.key)),
+        false => stats.sort_by(|a, b| {
+            b.group
+                .distance
+                .total_cmp(&a.group.distance)
+                .then(a.group.key.cmp(&b.group.key))
+        }),
+    }
+    stats
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn activity(date: (i32, u32, u32), distance: f64, heartrate: Option<f64>) -> Activity {
+        Activity {
+            activity_type: "Run".to_string(),
+            distance,
+            moving_time: 1000,
+            average_heartrate: heartrate,
+            start_date: NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+                .unwrap()
+                .and_hms_opt(8, 0, 0),
+            ..Activity::default()
+        }
+    }
+
+    #[test]
+    fn group_by_month_with_previous_year() {
+        let activities = Activities::from(vec![
+            activity((2024, 3, 1), 10000.0, Some(150.0)),
+            activity((2024, 3, 20), 5000.0, None),
+            activity((2024, 2, 1), 8000.0, Some(140.0)),
+            activity((2023, 3, 5), 10000.0, Some(160.0)),
+        ]);
+        let stats = group_stats(&activities, &GroupBy::Month);
+
+        assert_eq!(
+            vec!["2024-03", "2024-02", "2023-03"],
+            stats.iter().map(|s| s.group.key.as_str()).collect::<Vec<&str>>()
+        );
+        assert_eq!(2, stats[0].group.count);
+        assert_eq!(7500.0, stats[0].group.average_distance());
+        assert_eq!(Some(150.0), stats[0].group.average_heartrate());
+        assert_eq!(Some(50.0), stats[0].distance_change());
+        assert!(stats[1].previous_year.is_none());
+    }
+
+    #[test]
+    fn week_keys() {
+        let a = activity((2024, 1, 1), 1000.0, None);
+        assert_eq!(Some("2024-W01".to_string()), GroupBy::Week.key(&a));
+        assert_eq!(
+            Some("2023-W01".to_string()),
+            GroupBy::Week.previous_year_key("2024-W01")
+        );
+        assert_eq!(None, GroupBy::Type.previous_year_key("Run"));
+    }
+}
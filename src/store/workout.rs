@@ -0,0 +1,421 @@
+use serde::{Deserialize, Serialize};
+
+use crate::component::unit_formatter::{UnitSystem, KILOMETER_TO_MILE};
+
+/// Samples within this many seconds of each other are used to smooth the GPS noise
+const SMOOTHING_WINDOW: i64 = 5;
+
+/// Slower speeds (m/s) are standing still and ignored when finding the work and recovery paces
+const MIN_MOVING_SPEED: f64 = 1.0;
+
+/// Work or recovery sections shorter than this (seconds) are merged into their neighbours
+const MIN_SECTION_TIME: i64 = 20;
+
+/// Ratio of the work to the recovery speed below which the run is considered steady
+const MIN_CONTRAST: f64 = 1.25;
+
+/// Reps required to call a run a workout
+const MIN_REPS: usize = 3;
+
+/// A rep or a recovery between two reps
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Interval {
+    /// Distance from the start of the activity (meters)
+    pub start: f64,
+    pub distance: f64,
+    pub time: i64,
+    pub average_heartrate: Option<f64>,
+}
+
+impl Interval {
+    pub fn seconds_per_meter(&self) -> f64 {
+        self.time as f64 / self.distance
+    }
+
+    pub fn meters_per_second(&self) -> f64 {
+        self.distance / self.time as f64
+    }
+}
+
+/// Work and recovery intervals detected in the velocity stream of a run
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Workout {
+    pub reps: Vec<Interval>,
+    /// Recoveries between the reps, the warm up and cool down are not included
+    pub recoveries: Vec<Interval>,
+}
+
+impl Workout {
+    /// Detect the reps of a workout from the Strava time, distance, velocity
+    /// and (optional) heart rate streams, `None` if the pace was too steady
+    pub fn detect(
+        time: &[i64],
+        distance: &[f64],
+        velocity: &[f64],
+        heartrate: Option<&[f64]>,
+    ) -> Option<Workout> {
+        let len = time.len().min(distance.len()).min(velocity.len());
+        if len < 2 {
+            return None;
+        }
+        let (time, distance) = (&time[..len], &distance[..len]);
+        let velocity = smooth(&time[..len], &velocity[..len]);
+
+        let mut moving: Vec<f64> = velocity.iter().copied().filter(|v| *v >= MIN_MOVING_SPEED).collect();
+        moving.sort_by(f64::total_cmp);
+        let (easy, fast) = (percentile(&moving, 0.25)?, percentile(&moving, 0.9)?);
+        if fast < easy * MIN_CONTRAST {
+            return None;
+        }
+        let threshold = (easy + fast) / 2.0;
+
+        let mut sections: Vec<(bool, usize)> = vec![];
+        for (i, v) in velocity.iter().enumerate().take(len - 1) {
+            let work = *v >= threshold;
+            if sections.last().map(|s| s.0) != Some(work) {
+                sections.push((work, i));
+            }
+        }
+        merge_short_sections(&mut sections, time);
+
+        // a section starts where the last sample of the previous one was taken
+        let boundary = |i: usize| sections.get(i).map_or(len - 1, |s| s.1.saturating_sub(1));
+        let interval = |i: usize| {
+            let (from, to) = (boundary(i), boundary(i + 1));
+            Interval {
+                start: distance[from],
+                distance: distance[to] - distance[from],
+                time: time[to] - time[from],
+                average_heartrate: heartrate
+                    .map(|hr| &hr[from.min(hr.len())..to.min(hr.len())])
+                    .filter(|hr| !hr.is_empty())
+                    .map(|hr| hr.iter().sum::<f64>() / hr.len() as f64),
+            }
+        };
+        let first = sections.iter().position(|s| s.0)?;
+        let last = sections.iter().rposition(|s| s.0)?;
+        let workout = Workout {
+            reps: (first..=last).filter(|i| sections[*i].0).map(interval).collect(),
+            recoveries: (first..=last).filter(|i| !sections[*i].0).map(interval).collect(),
+        };
+
+        if workout.reps.len() < MIN_REPS || workout.contrast() < MIN_CONTRAST {
+            return None;
+        }
+        Some(workout)
+    }
+
+    /// e.g. `6 × 800m @ 3:05/km, 400m jog` or `8 × 1:00 @ 3:20/km, 1:00 rest`
+    pub fn summary(&self, system: &UnitSystem) -> String {
+        // GPS distances are less precise than times, reps are only named by
+        // their time if their distances vary a lot more
+        let by_distance = variation(self.reps.iter().map(|r| r.distance))
+            <= 2.0 * variation(self.reps.iter().map(|r| r.time as f64)) + 0.01;
+        let label = |interval: &Interval| match by_distance {
+            true => distance_label(interval.distance),
+            false => time_label(interval.time),
+        };
+
+        let mut labels: Vec<(f64, String)> = self
+            .reps
+            .iter()
+            .map(|r| (if by_distance { r.distance } else { r.time as f64 }, label(r)))
+            .collect();
+        labels.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let reps = match (labels.first(), labels.last()) {
+            (Some(min), Some(max)) if min.1 != max.1 => {
+                format!("{} × {}–{}", self.reps.len(), min.1, max.1)
+            }
+            (Some(min), _) => format!("{} × {}", self.reps.len(), min.1),
+            _ => return "".to_string(),
+        };
+        let mut summary = format!("{} @ {}", reps, pace_label(self.seconds_per_meter(), system));
+
+        let mut recoveries = self.recoveries.clone();
+        recoveries.sort_by_key(|r| r.time);
+        if let Some(recovery) = recoveries.get(recoveries.len() / 2) {
+            summary.push_str(&match recovery.meters_per_second() < MIN_MOVING_SPEED {
+                true => format!(", {} rest", time_label(recovery.time)),
+                false => format!(", {} jog", label(recovery)),
+            });
+        }
+        summary
+    }
+
+    /// Average pace of all reps
+    pub fn seconds_per_meter(&self) -> f64 {
+        let time: i64 = self.reps.iter().map(|r| r.time).sum();
+        let distance: f64 = self.reps.iter().map(|r| r.distance).sum();
+        time as f64 / distance
+    }
+
+    /// Standard deviation of the rep paces relative to their mean
+    pub fn pace_variation(&self) -> f64 {
+        variation(self.reps.iter().map(|r| r.seconds_per_meter()))
+    }
+
+    /// Change of the pace from the first to the last rep, positive if the last rep was slower
+    pub fn fade(&self) -> f64 {
+        match (self.reps.first(), self.reps.last()) {
+            (Some(first), Some(last)) => last.seconds_per_meter() / first.seconds_per_meter() - 1.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Change of the heart rate from the first to the last rep
+    pub fn heartrate_drift(&self) -> Option<f64> {
+        Some(self.reps.last()?.average_heartrate? - self.reps.first()?.average_heartrate?)
+    }
+
+    /// Index of the fastest rep
+    pub fn fastest(&self) -> Option<usize> {
+        (0..self.reps.len()).min_by(|a, b| {
+            self.reps[*a]
+                .seconds_per_meter()
+                .total_cmp(&self.reps[*b].seconds_per_meter())
+        })
+    }
+
+    /// Index of the slowest rep
+    pub fn slowest(&self) -> Option<usize> {
+        (0..self.reps.len()).max_by(|a, b| {
+            self.reps[*a]
+                .seconds_per_meter()
+                .total_cmp(&self.reps[*b].seconds_per_meter())
+        })
+    }
+
+    /// Recovery following the given rep
+    pub fn recovery_after(&self, rep: usize) -> Option<&Interval> {
+        self.recoveries.get(rep)
+    }
+
+    fn contrast(&self) -> f64 {
+        let speed = |intervals: &Vec<Interval>| {
+            intervals.iter().map(|i| i.distance).sum::<f64>()
+                / intervals.iter().map(|i| i.time).sum::<i64>() as f64
+        };
+        speed(&self.reps) / speed(&self.recoveries)
+    }
+}
+
+/// Median of the samples within the smoothing window of each sample, which
+/// removes GPS spikes but keeps the changes of pace sharp
+fn smooth(time: &[i64], velocity: &[f64]) -> Vec<f64> {
+    let (mut from, mut to) = (0, 0);
+    time.iter()
+        .map(|t| {
+            while time[from] < t - SMOOTHING_WINDOW {
+                from += 1;
+            }
+            while to < time.len() && time[to] <= t + SMOOTHING_WINDOW {
+                to += 1;
+            }
+            let mut window = velocity[from..to].to_vec();
+            window.sort_by(f64::total_cmp);
+            window[window.len() / 2]
+        })
+        .collect()
+}
+
+fn percentile(sorted: &[f64], share: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    sorted
+        .get(((sorted.len() - 1) as f64 * share).round() as usize)
+        .copied()
+}
+
+/// Flip the shortest section to the kind of its neighbours until every
+/// section is long enough, e.g. a rep slowed down by a sharp corner
+fn merge_short_sections(sections: &mut Vec<(bool, usize)>, time: &[i64]) {
+    let end = time.len() - 1;
+    loop {
+        let duration =
+            |i: usize| time[sections.get(i + 1).map_or(end, |s| s.1)] - time[sections[i].1];
+        let shortest = (0..sections.len())
+            .filter(|i| duration(*i) < MIN_SECTION_TIME)
+            .min_by_key(|i| duration(*i));
+        let index = match shortest {
+            Some(index) if sections.len() > 1 => index,
+            _ => return,
+        };
+        sections[index].0 = !sections[index].0;
+        let mut merged: Vec<(bool, usize)> = vec![];
+        for section in sections.iter() {
+            if merged.last().map(|s| s.0) != Some(section.0) {
+                merged.push(*section);
+            }
+        }
+        *sections = merged;
+    }
+}
+
+/// Standard deviation relative to the mean
+fn variation(values: impl Iterator<Item = f64>) -> f64 {
+    let values: Vec<f64> = values.collect();
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    variance.sqrt() / mean
+}
+
+/// Rep distances are named the way they are on the track, e.g. `400m`, `1km` or `1600m`
+fn distance_label(meters: f64) -> String {
+    let step = match meters {
+        m if m < 400.0 => 50.0,
+        m if m < 2000.0 => 100.0,
+        _ => 500.0,
+    };
+    let rounded = (meters / step).round() * step;
+    match rounded {
+        r if r >= 1000.0 && r % 1000.0 == 0.0 => format!("{}km", r / 1000.0),
+        r if r >= 2000.0 => format!("{:.1}km", r / 1000.0),
+        r => format!("{}m", r),
+    }
+}
+
+/// Duration rounded to 15 seconds, e.g. `1:30`
+fn time_label(seconds: i64) -> String {
+    let rounded = ((seconds as f64 / 15.0).round() * 15.0) as i64;
+    format!("{}:{:0>2}", rounded / 60, rounded % 60)
+}
+
+fn pace_label(seconds_per_meter: f64, system: &UnitSystem) -> String {
+    let (seconds, unit) = match system {
+        UnitSystem::Metric => (seconds_per_meter * 1000.0, "km"),
+        UnitSystem::Imperial => (seconds_per_meter * 1000.0 / KILOMETER_TO_MILE, "mi"),
+    };
+    let seconds = seconds.round() as i64;
+    format!("{}:{:0>2}/{}", seconds / 60, seconds % 60, unit)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// 1Hz streams of a run at the given speeds (m/s) for the given durations
+    fn run(sections: &[(f64, i64)]) -> (Vec<i64>, Vec<f64>, Vec<f64>) {
+        let (mut time, mut distance, mut velocity) = (vec![0], vec![0.0], vec![sections[0].0]);
+        for (speed, seconds) in sections {
+            for _ in 0..*seconds {
+                time.push(time.last().unwrap() + 1);
+                distance.push(distance.last().unwrap() + speed);
+                velocity.push(*speed);
+            }
+        }
+        (time, distance, velocity)
+    }
+
+    fn reps(count: usize, rep: (f64, i64), recovery: (f64, i64)) -> Vec<(f64, i64)> {
+        let mut sections = vec![(3.0, 600)];
+        for i in 0..count {
+            if i > 0 {
+                sections.push(recovery);
+            }
+            sections.push(rep);
+        }
+        sections.push((3.0, 600));
+        sections
+    }
+
+    #[test]
+    fn distance_reps() {
+        // 6 × 800m in 2:30 with 400m jogs in 2:40
+        let (time, distance, velocity) = run(&reps(6, (800.0 / 150.0, 150), (2.5, 160)));
+        let heartrate: Vec<f64> = (0..time.len()).map(|i| 140.0 + i as f64 / 100.0).collect();
+        let workout = Workout::detect(&time, &distance, &velocity, Some(&heartrate)).unwrap();
+
+        assert_eq!(6, workout.reps.len());
+        assert_eq!(5, workout.recoveries.len());
+        assert_eq!(1800.0, workout.reps[0].start);
+        assert_eq!(150, workout.reps[0].time);
+        assert_eq!("6 × 800m @ 3:08/km, 400m jog", workout.summary(&UnitSystem::Metric));
+        assert_eq!("6 × 800m @ 5:02/mi, 400m jog", workout.summary(&UnitSystem::Imperial));
+        assert!(workout.pace_variation() < 0.01);
+        assert!(workout.fade().abs() < 0.01);
+        assert!(workout.heartrate_drift().unwrap() > 15.0);
+    }
+
+    #[test]
+    fn gps_spikes() {
+        let (time, distance, mut velocity) = run(&reps(6, (800.0 / 150.0, 150), (2.5, 160)));
+        for (i, v) in velocity.iter_mut().enumerate() {
+            match (i % 13, i % 17) {
+                (0, _) => *v = 0.0,
+                (_, 0) => *v += 3.0,
+                _ => *v += (i % 3) as f64 * 0.1,
+            }
+        }
+        let workout = Workout::detect(&time, &distance, &velocity, None).unwrap();
+
+        assert_eq!(6, workout.reps.len());
+        assert_eq!("6 × 800m @ 3:08/km, 400m jog", workout.summary(&UnitSystem::Metric));
+    }
+
+    #[test]
+    fn time_reps_with_rest() {
+        // 8 × 1:00 at varying speeds, standing for 1:30 in between
+        let mut sections = vec![(3.0, 600)];
+        for i in 0..8 {
+            if i > 0 {
+                sections.push((0.0, 90));
+            }
+            sections.push((4.5 + i as f64 * 0.1, 60));
+        }
+        sections.push((3.0, 300));
+        let (time, distance, velocity) = run(&sections);
+        let workout = Workout::detect(&time, &distance, &velocity, None).unwrap();
+
+        assert_eq!(8, workout.reps.len());
+        assert_eq!("8 × 1:00 @ 3:26/km, 1:30 rest", workout.summary(&UnitSystem::Metric));
+        assert_eq!(Some(7), workout.fastest());
+        assert_eq!(Some(0), workout.slowest());
+        assert!(workout.fade() < -0.1);
+        assert_eq!(None, workout.heartrate_drift());
+    }
+
+    #[test]
+    fn steady_runs() {
+        let (time, distance, velocity) = run(&[(3.0, 1200), (3.3, 600), (2.9, 600)]);
+        assert_eq!(None, Workout::detect(&time, &distance, &velocity, None));
+
+        // two surges are not a workout
+        let (time, distance, velocity) = run(&reps(2, (5.0, 120), (2.5, 120)));
+        assert_eq!(None, Workout::detect(&time, &distance, &velocity, None));
+        assert_eq!(None, Workout::detect(&[], &[], &[], None));
+    }
+
+    #[test]
+    fn short_dips_are_merged() {
+        // the pace drops for a few seconds in the middle of each rep
+        let mut sections = vec![(3.0, 600)];
+        for i in 0..4 {
+            if i > 0 {
+                sections.push((2.5, 120));
+            }
+            sections.extend([(5.0, 90), (2.5, 6), (5.0, 90)]);
+        }
+        sections.push((3.0, 600));
+        let (time, distance, velocity) = run(&sections);
+        let workout = Workout::detect(&time, &distance, &velocity, None).unwrap();
+
+        assert_eq!(4, workout.reps.len());
+        assert_eq!(186, workout.reps[0].time);
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!("150m", distance_label(160.0));
+        assert_eq!("400m", distance_label(412.0));
+        assert_eq!("1km", distance_label(996.0));
+        assert_eq!("1600m", distance_label(1589.0));
+        assert_eq!("2.5km", distance_label(2480.0));
+        assert_eq!("3km", distance_label(3020.0));
+        assert_eq!("1:30", time_label(93));
+        assert_eq!("0:45", time_label(44));
+    }
+}
@@ -4,11 +4,16 @@ use sqlx::SqlitePool;
 
 use crate::client;
 use crate::client::Segment;
+use crate::component::unit_formatter::UnitSystem;
 use crate::event::input::EventSender;
 use crate::event::input::InputEvent;
 use crate::event::logger::Logger;
 use crate::store::activity::Activity;
 use crate::store::activity::ActivitySegmentEffort;
+use crate::store::activity::SportType;
+use crate::store::power_curve::PowerCurve;
+use crate::store::workout::Workout;
+use crate::sync::status::{SyncPhase, SyncProgress};
 
 pub struct ActivityConverter<'a> {
     pool: &'a SqlitePool,
@@ -28,10 +33,10 @@ impl ActivityConverter<'_> {
             logger,
         }
     }
-    pub async fn convert(&mut self) -> Result<(), anyhow::Error> {
+    pub async fn convert(&mut self, progress: &mut SyncProgress) -> Result<(), anyhow::Error> {
         let raw_activities = sqlx::query!(
             r#"
-            SELECT activity, listed FROM raw_activity WHERE synced = false
+            SELECT activity, listed, streams FROM raw_activity WHERE synced = false
             "#
         )
         .fetch_all(self.pool)
@@ -39,8 +44,8 @@ impl ActivityConverter<'_> {
 
         let mut segments: HashMap<String, Segment> = HashMap::new();
         self.logger.info("Converting activities".to_string()).await;
-        let mut i = 0;
-        for raw_activity in raw_activities {
+        progress.start(SyncPhase::Converting, raw_activities.len()).await;
+        for (i, raw_activity) in raw_activities.into_iter().enumerate() {
             let listed: client::Activity = serde_json::from_str(match &raw_activity.activity {
                 Some(a) => a.as_str(),
                 None => raw_activity.listed.as_str(),
@@ -51,7 +56,6 @@ impl ActivityConverter<'_> {
                     .info(format!("Converting activity {}", listed.name))
                     .await;
             }
-            i += 1;
             let activity = Activity {
                 id: listed.id,
                 title: listed.name.clone(),
@@ -76,7 +80,17 @@ impl ActivityConverter<'_> {
                 location_state: listed.location_state.clone(),
                 location_city: listed.location_city.clone(),
                 athletes: listed.athlete_count,
-                splits: vec![],
+                average_watts: listed.average_watts,
+                weighted_average_watts: listed.weighted_average_watts,
+                kilojoules: listed.kilojoules,
+                device_watts: listed.device_watts,
+                gear_id: listed.gear_id.clone(),
+                gear_name: None,
+                power_curve: PowerCurve::default(),
+                workout: None,
+                splits_metric: vec![],
+                splits_imperial: vec![],
+                laps: vec![],
                 segment_efforts: vec![],
                 rank: 0,
             };
@@ -102,9 +116,37 @@ impl ActivityConverter<'_> {
                     location_country,
                     location_state,
                     location_city,
-                    athletes
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,?)
-                ON CONFLICT DO NOTHING
+                    athletes,
+                    average_watts,
+                    weighted_average_watts,
+                    kilojoules,
+                    device_watts,
+                    gear_id
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(id) DO UPDATE SET
+                    title = excluded.title,
+                    description = excluded.description,
+                    activity_type = excluded.activity_type,
+                    distance = excluded.distance,
+                    moving_time = excluded.moving_time,
+                    elapsed_time = excluded.elapsed_time,
+                    total_elevation_gain = excluded.total_elevation_gain,
+                    sport_type = excluded.sport_type,
+                    average_heartrate = excluded.average_heartrate,
+                    max_heartrate = excluded.max_heartrate,
+                    start_date = excluded.start_date,
+                    summary_polyline = excluded.summary_polyline,
+                    average_cadence = excluded.average_cadence,
+                    kudos = excluded.kudos,
+                    location_country = excluded.location_country,
+                    location_state = excluded.location_state,
+                    location_city = excluded.location_city,
+                    athletes = excluded.athletes,
+                    average_watts = excluded.average_watts,
+                    weighted_average_watts = excluded.weighted_average_watts,
+                    kilojoules = excluded.kilojoules,
+                    device_watts = excluded.device_watts,
+                    gear_id = excluded.gear_id
                 "#,
                 activity.id,
                 activity.title,
@@ -125,54 +167,232 @@ impl ActivityConverter<'_> {
                 activity.location_state,
                 activity.location_city,
                 activity.athletes,
+                activity.average_watts,
+                activity.weighted_average_watts,
+                activity.kilojoules,
+                activity.device_watts,
+                activity.gear_id,
             )
             .execute(self.pool)
             .await?;
 
+            let is_run = matches!(activity.activity_category(), SportType::Run);
             if let Some(full_activity) = raw_activity.activity {
                 let activity: client::Activity =
                     serde_json::from_str(full_activity.as_str()).expect("Could not decode JSON");
 
-                let efforts_json = match &activity.segment_efforts {
+                let efforts: Vec<ActivitySegmentEffort> = match &activity.segment_efforts {
                     Some(efforts) => {
                         for effort in efforts.iter() {
                             segments.entry(effort.segment.id.to_string()).or_insert(effort.segment.clone());
                         }
-                        let a_se: Vec<ActivitySegmentEffort> = efforts.iter().map(|se| ActivitySegmentEffort {
+                        efforts.iter().map(|se| ActivitySegmentEffort {
                             segment_id: se.segment.id,
                             elapsed_time: se.elapsed_time,
                             moving_time: se.moving_time,
                             pr_rank: se.pr_rank,
                             kom_rank: se.kom_rank,
-                        }).collect();
-                        serde_json::to_string(&a_se).unwrap()
+                        }).collect()
                     },
-                    None => "[]".to_string(),
+                    None => vec![],
                 };
-                let splits_json = match &activity.splits_standard {
-                    Some(s) => {
-                        serde_json::to_string(s).unwrap()
-                    },
-                    None => "[]".to_string(),
+                self.update_segment_efforts(activity.id, &efforts).await?;
+                self.update_splits(activity.id, UnitSystem::Metric, &activity.splits_metric).await?;
+                self.update_splits(activity.id, UnitSystem::Imperial, &activity.splits_standard).await?;
+                self.update_laps(activity.id, &activity.laps).await?;
+                let streams: client::Streams = match &raw_activity.streams {
+                    Some(s) => serde_json::from_str(s.as_str()).unwrap_or_default(),
+                    None => client::Streams::default(),
+                };
+                let power_curve = match (&streams.time, &streams.watts) {
+                    (Some(time), Some(watts)) => PowerCurve::from_stream(&time.data, &watts.data),
+                    _ => PowerCurve::default(),
                 };
+                let power_curve_json = serde_json::to_string(&power_curve).unwrap();
+                let workout = match (&streams.time, &streams.distance, &streams.velocity_smooth) {
+                    (Some(time), Some(distance), Some(velocity)) if is_run => Workout::detect(
+                        &time.data,
+                        &distance.data,
+                        &velocity.data,
+                        streams.heartrate.as_ref().map(|hr| hr.data.as_slice()),
+                    ),
+                    _ => None,
+                };
+                let workout_json = workout.map(|w| serde_json::to_string(&w).unwrap());
 
                 sqlx::query!(
                     r#"
-                    UPDATE activity SET activity_splits = ?, segment_efforts = ? WHERE id = ?
+                    UPDATE activity SET
+                        average_watts = ?,
+                        weighted_average_watts = ?,
+                        kilojoules = ?,
+                        device_watts = ?,
+                        power_curve = ?,
+                        workout = ?,
+                        gear_id = ?
+                    WHERE id = ?
                     "#,
-                    splits_json,
-                    efforts_json,
+                    activity.average_watts,
+                    activity.weighted_average_watts,
+                    activity.kilojoules,
+                    activity.device_watts,
+                    power_curve_json,
+                    workout_json,
+                    activity.gear_id,
                     activity.id
                 )
                 .execute(self.pool)
                 .await?;
             }
+            sqlx::query!(
+                r#"
+                UPDATE raw_activity SET synced = true WHERE id = ?
+                "#,
+                listed.id
+            )
+            .execute(self.pool)
+            .await?;
+            progress.advance().await;
         }
-        self.event_sender.send(InputEvent::Reload).await?;
         self.logger.info("Updating segments".to_string()).await;
-        self.update_segments(&segments).await.unwrap();
+        self.update_segments(&segments).await?;
         self.logger.info("Done converting".to_string()).await;
+        self.event_sender.send(InputEvent::Reload).await?;
+
+        Ok(())
+    }
+
+    async fn update_laps(
+        &mut self,
+        activity_id: i64,
+        laps: &Option<Vec<client::Lap>>,
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM activity_lap WHERE activity_id = ?
+            "#,
+            activity_id
+        )
+        .execute(self.pool)
+        .await?;
+
+        for lap in laps.iter().flatten() {
+            sqlx::query!(
+                r#"
+                INSERT INTO activity_lap (
+                    activity_id,
+                    lap_index,
+                    name,
+                    distance,
+                    moving_time,
+                    elapsed_time,
+                    average_speed,
+                    average_heartrate,
+                    max_heartrate,
+                    average_cadence,
+                    total_elevation_gain
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                activity_id,
+                lap.lap_index,
+                lap.name,
+                lap.distance,
+                lap.moving_time,
+                lap.elapsed_time,
+                lap.average_speed,
+                lap.average_heartrate,
+                lap.max_heartrate,
+                lap.average_cadence,
+                lap.total_elevation_gain,
+            )
+            .execute(self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn update_splits(
+        &mut self,
+        activity_id: i64,
+        system: UnitSystem,
+        splits: &Option<Vec<client::Split>>,
+    ) -> Result<(), anyhow::Error> {
+        let unit_system = system.to_string();
+        sqlx::query!(
+            r#"
+            DELETE FROM activity_split WHERE activity_id = ? AND unit_system = ?
+            "#,
+            activity_id,
+            unit_system
+        )
+        .execute(self.pool)
+        .await?;
+
+        for split in splits.iter().flatten() {
+            sqlx::query!(
+                r#"
+                INSERT INTO activity_split (
+                    activity_id,
+                    unit_system,
+                    split,
+                    distance,
+                    moving_time,
+                    elapsed_time,
+                    average_speed,
+                    elevation_difference
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                activity_id,
+                unit_system,
+                split.split,
+                split.distance,
+                split.moving_time,
+                split.elapsed_time,
+                split.average_speed,
+                split.elevation_difference,
+            )
+            .execute(self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn update_segment_efforts(
+        &mut self,
+        activity_id: i64,
+        efforts: &[ActivitySegmentEffort],
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM activity_segment_effort WHERE activity_id = ?
+            "#,
+            activity_id
+        )
+        .execute(self.pool)
+        .await?;
 
+        for effort in efforts {
+            sqlx::query!(
+                r#"
+                INSERT INTO activity_segment_effort (
+                    activity_id,
+                    segment_id,
+                    elapsed_time,
+                    moving_time,
+                    pr_rank,
+                    kom_rank
+                ) VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+                activity_id,
+                effort.segment_id,
+                effort.elapsed_time,
+                effort.moving_time,
+                effort.pr_rank,
+                effort.kom_rank,
+            )
+            .execute(self.pool)
+            .await?;
+        }
         Ok(())
     }
 
@@ -4,6 +4,7 @@ use chrono::{NaiveDateTime};
 use sqlx::{SqlitePool};
 
 use crate::event::logger::Logger;
+use crate::sync::status::{SyncPhase, SyncProgress};
 use crate::{
     client::StravaClient,
 };
@@ -23,7 +24,7 @@ impl IngestActivitiesTask<'_> {
     ) -> IngestActivitiesTask<'a> {
         IngestActivitiesTask { client, pool, logger }
     }
-    pub async fn execute(&mut self) -> Result<(), anyhow::Error> {
+    pub async fn execute(&mut self, progress: &mut SyncProgress) -> Result<(), anyhow::Error> {
         let mut page: u32 = 0;
         const PAGE_SIZE: u32 = 100;
         let last = sqlx::query!(
@@ -32,6 +33,7 @@ SELECT MAX(created_at) as epoch FROM raw_activity LIMIT 1
             "#
         ).fetch_one(self.pool).await?;
 
+        progress.start(SyncPhase::Listing, 0).await;
         loop {
             page += 1;
             let s_activities = match self
@@ -41,6 +43,7 @@ SELECT MAX(created_at) as epoch FROM raw_activity LIMIT 1
                     Ok(a) => a,
                     Err(e) => {
                         self.logger.error(format!("Error: {}", e)).await;
+                        progress.error(e.to_string()).await;
                         return Ok(())
                     },
                 };
@@ -52,7 +55,7 @@ SELECT MAX(created_at) as epoch FROM raw_activity LIMIT 1
 
             for s_activity in s_activities {
                 self.logger.info(format!("[{}] {}", s_activity["id"], s_activity["name"])).await;
-                sqlx::query(
+                let result = sqlx::query(
                     r#"
 INSERT INTO raw_activity (id, created_at, listed, synced) VALUES (?, ?, ?, false) ON CONFLICT(id) DO NOTHING
                     "#
@@ -68,6 +71,10 @@ INSERT INTO raw_activity (id, created_at, listed, synced) VALUES (?, ?, ?, false
                 ).bind(
                     s_activity.to_string()
                 ).execute(self.pool).await?;
+                match result.rows_affected() {
+                    0 => progress.advance().await,
+                    _ => progress.new_activity().await,
+                }
             }
         }
         Ok(())
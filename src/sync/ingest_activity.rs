@@ -1,12 +1,25 @@
+use futures_util::{stream, StreamExt};
 use sqlx::SqlitePool;
 
 use crate::event::logger::Logger;
+use crate::sync::{
+    created_at,
+    status::{SyncPhase, SyncProgress},
+};
 use crate::{client::StravaClient};
 
+/// Failed downloads after which an activity is skipped until it is reconciled
+pub const MAX_ATTEMPTS: i64 = 5;
+
+/// Requests left for the next listing when the rate limit is close
+pub const RATE_LIMIT_RESERVE: u32 = 10;
+
 pub struct IngestActivityTask<'a> {
     client: &'a StravaClient,
     pool: &'a SqlitePool,
     logger: Logger,
+    /// Activities downloaded at the same time
+    concurrency: usize,
 }
 
 impl IngestActivityTask<'_> {
@@ -14,43 +27,203 @@ impl IngestActivityTask<'_> {
         client: &'a StravaClient,
         pool: &'a SqlitePool,
         logger: Logger,
+        concurrency: usize,
     ) -> IngestActivityTask<'a> {
         IngestActivityTask {
             client,
             pool,
             logger,
+            concurrency: concurrency.max(1),
         }
     }
-    pub async fn execute(&mut self) -> Result<(), anyhow::Error> {
+    pub async fn execute(&mut self, progress: &mut SyncProgress) -> Result<(), anyhow::Error> {
+        // activities which failed before are retried after the others
         let activity_records = sqlx::query!(
             r#"
-            SELECT id FROM raw_activity WHERE activity IS NULL
-            "#
+            SELECT id FROM raw_activity WHERE activity IS NULL AND attempts < ?
+            ORDER BY attempts, created_at DESC
+            "#,
+            MAX_ATTEMPTS
+        ).fetch_all(self.pool).await?;
+
+        let mut ids: Vec<i64> = activity_records.iter().map(|r| r.id).collect();
+        if let Some(rate_limit) = self.client.rate_limit() {
+            let budget = rate_limit.remaining().saturating_sub(RATE_LIMIT_RESERVE) as usize;
+            if budget < ids.len() {
+                self.logger.info(format!(
+                    "Rate limit: downloading {} of {} activities, the rest will be downloaded on a later sync",
+                    budget,
+                    ids.len()
+                )).await;
+                ids.truncate(budget);
+            }
+        }
+
+        let total = ids.len();
+        progress.start(SyncPhase::Downloading, total).await;
+        let client = self.client;
+        let mut downloads = stream::iter(ids)
+            .map(|id| async move { (id, client.athlete_activity(format!("{}", id)).await) })
+            .buffer_unordered(self.concurrency);
+        let mut done = 0;
+        while let Some((id, result)) = downloads.next().await {
+            done += 1;
+            match result {
+                Ok(s_activity) => {
+                    let s_activity = s_activity.to_string();
+                    sqlx::query!(
+                        r#"
+                        UPDATE raw_activity SET activity = ?, synced = false, attempts = 0, last_error = NULL WHERE id = ?
+                        "#,
+                        s_activity,
+                        id
+                    ).execute(self.pool).await?;
+                    self.logger.info(format!("[{}%] Downloaded full activity {}", done * 100 / total, id)).await;
+                }
+                Err(err) => {
+                    self.record_error(id, &err).await?;
+                    self.logger.error(format!("[{}%] ERROR activity {}: {}", done * 100 / total, id, err)).await;
+                    progress.error(format!("activity {}: {}", id, err)).await;
+                }
+            }
+            progress.advance().await;
+
+            if client.rate_limit().is_some_and(|r| r.remaining() == 0) {
+                self.logger.error("Rate limit reached, the remaining activities will be downloaded on a later sync".to_string()).await;
+                return Ok(());
+            }
+        }
+
+        self.ingest_streams(progress).await
+    }
+
+    /// Download the activity with the given ID, e.g. after it was created or updated on Strava
+    pub async fn execute_for(&mut self, id: i64, progress: &mut SyncProgress) -> Result<(), anyhow::Error> {
+        progress.start(SyncPhase::Downloading, 1).await;
+        self.logger.info(format!("Downloading full actiity {}", id)).await;
+        let s_activity = match self.client.athlete_activity(format!("{}", id)).await {
+            Ok(a) => a,
+            Err(err) => {
+                self.record_error(id, &err).await?;
+                self.logger.error(format!("ERROR activity {}: {}", id, err)).await;
+                progress.error(format!("activity {}: {}", id, err)).await;
+                return Ok(());
+            }
+        };
+
+        // the details are a superset of the listed activity
+        let created_at = created_at(&s_activity);
+        let s_activity = s_activity.to_string();
+        sqlx::query!(
+            r#"
+            INSERT INTO raw_activity (id, created_at, listed, activity, synced) VALUES (?, ?, ?, ?, false)
+            ON CONFLICT(id) DO UPDATE SET activity = excluded.activity, synced = false, attempts = 0, last_error = NULL
+            "#,
+            id,
+            created_at,
+            s_activity,
+            s_activity
+        )
+        .execute(self.pool)
+        .await?;
+        progress.advance().await;
+
+        self.ingest_streams(progress).await
+    }
+
+    /// Count the failed download so that an activity which keeps failing is
+    /// retried last and eventually skipped
+    async fn record_error(&self, id: i64, err: &anyhow::Error) -> Result<(), anyhow::Error> {
+        let message = err.to_string();
+        sqlx::query!(
+            r#"
+            UPDATE raw_activity SET attempts = attempts + 1, last_error = ? WHERE id = ?
+            "#,
+            message,
+            id
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Count the failed streams download, see `record_error`
+    async fn record_stream_error(&self, id: i64, err: &anyhow::Error) -> Result<(), anyhow::Error> {
+        let message = err.to_string();
+        sqlx::query!(
+            r#"
+            UPDATE raw_activity SET stream_attempts = stream_attempts + 1, stream_error = ? WHERE id = ?
+            "#,
+            message,
+            id
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Download the streams of rides with power data for the power curve and
+    /// of runs to detect their reps
+    async fn ingest_streams(&mut self, progress: &mut SyncProgress) -> Result<(), anyhow::Error> {
+        let mut activity_records = sqlx::query!(
+            r#"
+            SELECT id FROM raw_activity
+            WHERE streams IS NULL
+            AND (
+                json_extract(activity, '$.device_watts') = true
+                OR json_extract(activity, '$.sport_type') IN ('Run', 'TrailRun', 'VirtualRun')
+            )
+            AND stream_attempts < ?
+            ORDER BY stream_attempts, created_at DESC
+            "#,
+            MAX_ATTEMPTS
         ).fetch_all(self.pool).await?;
 
+        if let Some(rate_limit) = self.client.rate_limit() {
+            let budget = rate_limit.remaining().saturating_sub(RATE_LIMIT_RESERVE) as usize;
+            if budget < activity_records.len() {
+                self.logger.info(format!(
+                    "Rate limit: downloading {} of {} streams, the rest will be downloaded on a later sync",
+                    budget,
+                    activity_records.len()
+                )).await;
+                activity_records.truncate(budget);
+            }
+        }
+
+        progress.start(SyncPhase::Downloading, activity_records.len()).await;
         for activity_record in activity_records {
-            self.logger.info(format!("Downloading full actiity {}", activity_record.id)).await;
+            self.logger.info(format!("Downloading streams for activity {}", activity_record.id)).await;
 
-            let s_activity = match self
+            let streams = match self
                 .client
-                .athlete_activity(format!("{}", activity_record.id))
+                .activity_streams(format!("{}", activity_record.id))
                 .await {
-                    Ok(a) => a,
+                    Ok(s) => s,
                     Err(err) => {
-                        self.logger.info(format!("ERROR activity {}: {}", activity_record.id, err)).await;
-                        return Ok(())
+                        self.record_stream_error(activity_record.id, &err).await?;
+                        self.logger.error(format!("ERROR streams {}: {}", activity_record.id, err)).await;
+                        progress.error(format!("streams {}: {}", activity_record.id, err)).await;
+                        progress.advance().await;
+                        continue;
                     }
                 };
 
             sqlx::query(
                 r#"
-                UPDATE raw_activity SET activity = ? WHERE id = ?
+                UPDATE raw_activity SET streams = ?, synced = false, stream_attempts = 0, stream_error = NULL WHERE id = ?
                 "#,
             ).bind(
-                s_activity.to_string(),
+                streams.to_string(),
             ).bind(
                 activity_record.id,
             ).execute(self.pool).await?;
+            progress.advance().await;
+
+            if self.client.rate_limit().is_some_and(|r| r.remaining() == 0) {
+                self.logger.error("Rate limit reached, the remaining streams will be downloaded on a later sync".to_string()).await;
+                return Ok(());
+            }
         }
         Ok(())
     }
@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::client::{self, StravaClient};
+use crate::event::logger::Logger;
+use crate::store::gear::GearType;
+use crate::sync::status::{SyncPhase, SyncProgress};
+
+/// Downloads the profile and stats of the athlete and their bikes and shoes
+pub struct IngestAthleteTask<'a> {
+    client: &'a StravaClient,
+    pool: &'a SqlitePool,
+    logger: Logger,
+}
+
+impl IngestAthleteTask<'_> {
+    pub fn new<'a>(
+        client: &'a StravaClient,
+        pool: &'a SqlitePool,
+        logger: Logger,
+    ) -> IngestAthleteTask<'a> {
+        IngestAthleteTask { client, pool, logger }
+    }
+
+    pub async fn execute(&mut self, progress: &mut SyncProgress) -> Result<(), anyhow::Error> {
+        progress.start(SyncPhase::Athlete, 0).await;
+        let athlete: client::Athlete = match self.client.athlete().await {
+            Ok(a) => serde_json::from_value(a)?,
+            Err(err) => {
+                self.logger.error(format!("ERROR athlete: {}", err)).await;
+                progress.error(format!("athlete: {}", err)).await;
+                return Ok(());
+            }
+        };
+        let stats = match self.client.athlete_stats(athlete.id).await {
+            Ok(s) => Some(s.to_string()),
+            Err(err) => {
+                progress.error(format!("athlete stats: {}", err)).await;
+                None
+            }
+        };
+
+        let updated_at = Utc::now().naive_utc();
+        sqlx::query!(
+            r#"
+            INSERT INTO athlete (id, username, firstname, lastname, city, state, country, weight, ftp, stats, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                username = excluded.username,
+                firstname = excluded.firstname,
+                lastname = excluded.lastname,
+                city = excluded.city,
+                state = excluded.state,
+                country = excluded.country,
+                weight = excluded.weight,
+                ftp = excluded.ftp,
+                stats = COALESCE(excluded.stats, athlete.stats),
+                updated_at = excluded.updated_at
+            "#,
+            athlete.id,
+            athlete.username,
+            athlete.firstname,
+            athlete.lastname,
+            athlete.city,
+            athlete.state,
+            athlete.country,
+            athlete.weight,
+            athlete.ftp,
+            stats,
+            updated_at
+        )
+        .execute(self.pool)
+        .await?;
+        progress.advance().await;
+
+        let stored: HashSet<String> = sqlx::query_scalar::<_, String>("SELECT id FROM gear")
+            .fetch_all(self.pool)
+            .await?
+            .into_iter()
+            .collect();
+        for (gear, gear_type) in athlete
+            .bikes
+            .iter()
+            .map(|g| (g, GearType::Bike))
+            .chain(athlete.shoes.iter().map(|g| (g, GearType::Shoe)))
+        {
+            self.store_gear(gear, &gear_type).await?;
+        }
+
+        // the brand and model are only part of the details, retired gear is
+        // not listed on the profile but may still be used by activities
+        let listed: HashSet<String> = athlete
+            .bikes
+            .iter()
+            .chain(athlete.shoes.iter())
+            .map(|g| g.id.clone())
+            .collect();
+        let used: HashSet<String> = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT DISTINCT json_extract(COALESCE(activity, listed), '$.gear_id')
+            FROM raw_activity
+            WHERE json_extract(COALESCE(activity, listed), '$.gear_id') IS NOT NULL
+            "#,
+        )
+        .fetch_all(self.pool)
+        .await?
+        .into_iter()
+        .collect();
+        let mut unknown: Vec<String> = listed
+            .union(&used)
+            .filter(|id| !stored.contains(*id))
+            .cloned()
+            .collect();
+        unknown.sort();
+
+        progress.start(SyncPhase::Athlete, unknown.len()).await;
+        for id in unknown {
+            self.logger.info(format!("Downloading gear {}", id)).await;
+            match self.client.gear(id.clone()).await {
+                Ok(gear) => {
+                    let gear: client::Gear = serde_json::from_value(gear)?;
+                    let gear_type = match athlete.bikes.iter().any(|b| b.id == id) {
+                        true => GearType::Bike,
+                        false => GearType::from_id(&id),
+                    };
+                    self.store_gear(&gear, &gear_type).await?;
+                }
+                Err(err) => {
+                    self.logger.error(format!("ERROR gear {}: {}", id, err)).await;
+                    progress.error(format!("gear {}: {}", id, err)).await;
+                }
+            }
+            progress.advance().await;
+        }
+        Ok(())
+    }
+
+    async fn store_gear(&self, gear: &client::Gear, gear_type: &GearType) -> Result<(), anyhow::Error> {
+        let gear_type = gear_type.to_label();
+        sqlx::query!(
+            r#"
+            INSERT INTO gear (id, gear_type, name, brand_name, model_name, distance, is_primary, retired)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                gear_type = excluded.gear_type,
+                name = excluded.name,
+                brand_name = COALESCE(excluded.brand_name, gear.brand_name),
+                model_name = COALESCE(excluded.model_name, gear.model_name),
+                distance = excluded.distance,
+                is_primary = excluded.is_primary,
+                retired = excluded.retired
+            "#,
+            gear.id,
+            gear_type,
+            gear.name,
+            gear.brand_name,
+            gear.model_name,
+            gear.distance,
+            gear.primary,
+            gear.retired
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+}
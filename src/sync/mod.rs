@@ -1,69 +1,192 @@
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
 use hyper::Client;
-use hyper_rustls::HttpsConnectorBuilder;
+use serde_json::Value;
 use sqlx::SqlitePool;
-use tokio::{sync::mpsc::Receiver, task};
+use tokio::{sync::mpsc::Receiver, task, time};
 
 use crate::{
     authenticator::Authenticator,
-    client::{new_strava_client, StravaConfig},
-    event::{input::EventSender, logger::Logger},
+    client::{connector, new_strava_client, StravaClient, StravaConfig},
+    event::{
+        input::{EventSender, InputEvent},
+        logger::Logger,
+    },
 };
 
 use self::{
     convert::ActivityConverter, ingest_activities::IngestActivitiesTask,
-    ingest_activity::IngestActivityTask,
+    ingest_activity::IngestActivityTask, ingest_athlete::IngestAthleteTask,
+    reconcile::{delete_activity, ReconcileOptions, ReconcileTask},
+    status::SyncProgress,
 };
 
 pub mod convert;
 pub mod ingest_activities;
 pub mod ingest_activity;
+pub mod ingest_athlete;
+pub mod reconcile;
+pub mod status;
+pub mod webhook;
+
+#[cfg(test)]
+mod test;
+
+pub struct SyncConfig {
+    /// Base URL of the Strava API, e.g. `https://www.strava.com/api`
+    pub api_url: String,
+    /// Base URL of the OAuth endpoints, e.g. `https://www.strava.com/oauth`
+    pub oauth_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub access_token_path: String,
+    /// Time between automatic syncs, only sync on start and when requested if not set
+    pub interval: Option<Duration>,
+    /// Activity details downloaded at the same time
+    pub concurrency: usize,
+    pub reconcile: ReconcileOptions,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncRequest {
+    /// Fetch the activities created since the last sync
+    Sync,
+    /// Also apply the changes and deletions made on Strava
+    Reconcile,
+    /// Download a single activity which was created or updated
+    Activity(i64),
+    /// Delete a single activity
+    Delete(i64),
+    /// The athlete revoked the access of the application
+    Deauthorize,
+}
+
+/// Start date of a listed or detailed activity
+pub(crate) fn created_at(s_activity: &Value) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(
+        s_activity["start_date"].as_str().unwrap_or_default(),
+        "%Y-%m-%dT%H:%M:%SZ",
+    )
+    .unwrap_or_default()
+}
 
-pub async fn spawn_sync<'a>(
+pub async fn spawn_sync(
     pool: SqlitePool,
     event_sender: EventSender,
-    client_id: String,
-    client_secret: String,
-    access_token_path: String,
+    config: SyncConfig,
     logger: Logger,
-    mut sync_receiver: Receiver<bool>,
+    mut sync_receiver: Receiver<SyncRequest>,
 ) -> task::JoinHandle<()> {
-    let connector = HttpsConnectorBuilder::new()
-        .with_native_roots()
-        .https_only()
-        .enable_http1()
-        .build();
+    let connector = connector(&config.oauth_url);
     let event_sender = event_sender;
 
     task::spawn(async move {
         let client = Client::builder().build(connector);
         let mut authenticator = Authenticator::new(
             client,
-            client_id,
-            client_secret,
-            access_token_path,
+            config.oauth_url.clone(),
+            config.client_id.clone(),
+            config.client_secret.clone(),
+            config.access_token_path.clone(),
             logger.clone(),
         );
+        let mut progress = SyncProgress::new(event_sender.clone());
+        let mut request = SyncRequest::Sync;
         loop {
-            let api_config = StravaConfig {
-                base_url: "https://www.strava.com/api".to_string(),
-                access_token: authenticator.access_token().await.unwrap(),
-            };
-            let client = new_strava_client(api_config, logger.clone());
-            IngestActivitiesTask::new(&client, &pool, logger.clone())
-                .execute()
-                .await
-                .unwrap();
-            IngestActivityTask::new(&client, &pool, logger.clone())
-                .execute()
-                .await
-                .unwrap();
-            ActivityConverter::new(&pool, event_sender.clone(), logger.clone())
-                .convert()
-                .await
-                .unwrap();
-            if sync_receiver.recv().await.is_some() {
-                continue;
+            match request {
+                SyncRequest::Delete(id) => {
+                    logger.info(format!("Activity {} was deleted", id)).await;
+                    match delete_activity(&pool, id, config.reconcile.remove_deleted).await {
+                        Ok(()) => {
+                            // the UI may already have quit
+                            let _ = event_sender.send(InputEvent::Reload).await;
+                        }
+                        Err(err) => report(&logger, &mut progress, "delete", err).await,
+                    }
+                }
+                SyncRequest::Deauthorize => match authenticator.forget() {
+                    Ok(()) => {
+                        logger
+                            .error("Access to Strava was revoked, sync to authenticate again".to_string())
+                            .await
+                    }
+                    Err(err) => report(&logger, &mut progress, "deauthorize", err).await,
+                },
+                SyncRequest::Sync | SyncRequest::Reconcile | SyncRequest::Activity(_) => {
+                    progress.begin().await;
+                    let downloaded = match authenticator.access_token().await {
+                        Ok(access_token) => {
+                            let api_config = StravaConfig {
+                                base_url: config.api_url.clone(),
+                                access_token,
+                            };
+                            let client = new_strava_client(api_config, logger.clone());
+                            download(&request, &client, &pool, &config, &logger, &mut progress).await
+                        }
+                        Err(err) => Err(err),
+                    };
+                    if let Err(err) = downloaded {
+                        report(&logger, &mut progress, "sync", err).await;
+                    }
+                    // activities downloaded before an error are converted nevertheless
+                    if let Err(err) = ActivityConverter::new(&pool, event_sender.clone(), logger.clone())
+                        .convert(&mut progress)
+                        .await
+                    {
+                        report(&logger, &mut progress, "convert", err).await;
+                    }
+                    progress.finish().await;
+                }
             }
+
+            // sync again when requested or when the interval has elapsed
+            let next = match config.interval {
+                Some(interval) => time::timeout(interval, sync_receiver.recv())
+                    .await
+                    .unwrap_or(Some(SyncRequest::Sync)),
+                None => sync_receiver.recv().await,
+            };
+            request = match next {
+                Some(request) => request,
+                None => break,
+            };
         }
     })
 }
+
+/// Run the download tasks of the sync request
+async fn download(
+    request: &SyncRequest,
+    client: &StravaClient,
+    pool: &SqlitePool,
+    config: &SyncConfig,
+    logger: &Logger,
+    progress: &mut SyncProgress,
+) -> Result<(), anyhow::Error> {
+    if let SyncRequest::Activity(id) = request {
+        return IngestActivityTask::new(client, pool, logger.clone(), config.concurrency)
+            .execute_for(*id, progress)
+            .await;
+    }
+    if *request == SyncRequest::Reconcile || config.reconcile.on_sync {
+        ReconcileTask::new(client, pool, logger.clone(), &config.reconcile)
+            .execute(progress)
+            .await?;
+    }
+    IngestActivitiesTask::new(client, pool, logger.clone())
+        .execute(progress)
+        .await?;
+    IngestActivityTask::new(client, pool, logger.clone(), config.concurrency)
+        .execute(progress)
+        .await?;
+    IngestAthleteTask::new(client, pool, logger.clone())
+        .execute(progress)
+        .await
+}
+
+/// Log the error and show it in the status bar, the next sync is tried nevertheless
+async fn report(logger: &Logger, progress: &mut SyncProgress, action: &str, err: anyhow::Error) {
+    logger.error(format!("ERROR {}: {}", action, err)).await;
+    progress.error(format!("{}: {}", action, err)).await;
+}
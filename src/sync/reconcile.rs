@@ -0,0 +1,310 @@
+use std::collections::HashSet;
+
+use chrono::{Days, NaiveDateTime, Utc};
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+use crate::client::StravaClient;
+use crate::event::logger::Logger;
+use crate::sync::{
+    created_at,
+    ingest_activity::{IngestActivityTask, RATE_LIMIT_RESERVE},
+    status::{SyncPhase, SyncProgress},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct ReconcileOptions {
+    /// Only re-list the activities started in the last N days, all activities if not set
+    pub days: Option<u64>,
+    /// Reconcile on every sync instead of only when requested
+    pub on_sync: bool,
+    /// Remove activities deleted on Strava instead of marking them as deleted
+    pub remove_deleted: bool,
+}
+
+/// Re-lists the activities on Strava to apply edits made since they were
+/// synced and to mark (or remove) the activities which have been deleted.
+pub struct ReconcileTask<'a> {
+    client: &'a StravaClient,
+    pool: &'a SqlitePool,
+    logger: Logger,
+    options: &'a ReconcileOptions,
+}
+
+impl ReconcileTask<'_> {
+    pub fn new<'a>(
+        client: &'a StravaClient,
+        pool: &'a SqlitePool,
+        logger: Logger,
+        options: &'a ReconcileOptions,
+    ) -> ReconcileTask<'a> {
+        ReconcileTask {
+            client,
+            pool,
+            logger,
+            options,
+        }
+    }
+
+    pub async fn execute(&mut self, progress: &mut SyncProgress) -> Result<(), anyhow::Error> {
+        const PAGE_SIZE: u32 = 100;
+        let after: Option<NaiveDateTime> = self
+            .options
+            .days
+            .and_then(|days| Utc::now().naive_utc().checked_sub_days(Days::new(days)));
+
+        progress.start(SyncPhase::Reconciling, 0).await;
+        // give the activities which failed to download another chance
+        sqlx::query!(
+            r#"
+            UPDATE raw_activity SET attempts = 0 WHERE activity IS NULL
+            "#
+        )
+        .execute(self.pool)
+        .await?;
+        sqlx::query!(
+            r#"
+            UPDATE raw_activity SET stream_attempts = 0 WHERE streams IS NULL
+            "#
+        )
+        .execute(self.pool)
+        .await?;
+        let mut remote_ids: HashSet<i64> = HashSet::new();
+        let mut updated = 0;
+        let mut refresh: Vec<i64> = vec![];
+        let mut page: u32 = 0;
+        loop {
+            page += 1;
+            let s_activities = match self.client.athlete_activities(page, PAGE_SIZE, after).await {
+                Ok(a) => a,
+                Err(e) => {
+                    // without the complete listing deleted activities can not be detected
+                    self.logger.error(format!("Error: {}", e)).await;
+                    progress.error(e.to_string()).await;
+                    return Ok(());
+                }
+            };
+            if s_activities.is_empty() {
+                break;
+            }
+
+            for s_activity in s_activities {
+                let id = s_activity["id"].as_i64().expect("could not parse 64 bit ID");
+                remote_ids.insert(id);
+                let stored = sqlx::query!(
+                    r#"
+                    SELECT listed FROM raw_activity WHERE id = ?
+                    "#,
+                    id
+                )
+                .fetch_optional(self.pool)
+                .await?;
+
+                match stored {
+                    None => {
+                        let created_at = created_at(&s_activity);
+                        let listed = s_activity.to_string();
+                        sqlx::query!(
+                            r#"
+                            INSERT INTO raw_activity (id, created_at, listed, synced) VALUES (?, ?, ?, false)
+                            "#,
+                            id,
+                            created_at,
+                            listed
+                        )
+                        .execute(self.pool)
+                        .await?;
+                        progress.new_activity().await;
+                        continue;
+                    }
+                    Some(stored) if is_changed(&stored.listed, &s_activity) => {
+                        self.logger
+                            .info(format!("[{}] {} was changed", id, s_activity["name"]))
+                            .await;
+                        // the details are downloaded again as they contain the edited fields
+                        let listed = s_activity.to_string();
+                        sqlx::query!(
+                            r#"
+                            UPDATE raw_activity SET listed = ?, activity = NULL, synced = false, attempts = 0, last_error = NULL WHERE id = ?
+                            "#,
+                            listed,
+                            id
+                        )
+                        .execute(self.pool)
+                        .await?;
+                        updated += 1;
+                    }
+                    Some(stored) if is_listing_changed(&stored.listed, &s_activity) => {
+                        // the description is not listed, the details are downloaded again
+                        // in case it was edited as well
+                        let listed = s_activity.to_string();
+                        sqlx::query!(
+                            r#"
+                            UPDATE raw_activity SET listed = ? WHERE id = ?
+                            "#,
+                            listed,
+                            id
+                        )
+                        .execute(self.pool)
+                        .await?;
+                        refresh.push(id);
+                    }
+                    Some(_) => (),
+                }
+                sqlx::query!(
+                    r#"
+                    UPDATE activity SET deleted = false WHERE id = ? AND deleted = true
+                    "#,
+                    id
+                )
+                .execute(self.pool)
+                .await?;
+                progress.advance().await;
+            }
+        }
+
+        let since = after.unwrap_or_default();
+        let local = sqlx::query!(
+            r#"
+            SELECT id FROM raw_activity WHERE created_at >= ?
+            "#,
+            since
+        )
+        .fetch_all(self.pool)
+        .await?;
+        let deleted: Vec<i64> = local
+            .iter()
+            .map(|r| r.id)
+            .filter(|id| !remote_ids.contains(id))
+            .collect();
+        for id in deleted.iter() {
+            delete_activity(self.pool, *id, self.options.remove_deleted).await?;
+        }
+
+        let mut refreshed = 0;
+        for id in refresh.iter() {
+            if self
+                .client
+                .rate_limit()
+                .is_some_and(|r| r.remaining() <= RATE_LIMIT_RESERVE)
+            {
+                self.logger
+                    .info("Rate limit: the remaining details will be refreshed on a later reconcile".to_string())
+                    .await;
+                break;
+            }
+            IngestActivityTask::new(self.client, self.pool, self.logger.clone(), 1)
+                .execute_for(*id, progress)
+                .await?;
+            refreshed += 1;
+        }
+
+        self.logger
+            .info(format!(
+                "Reconciled {} activities: {} changed, {} refreshed, {} deleted",
+                remote_ids.len(),
+                updated,
+                refreshed,
+                deleted.len()
+            ))
+            .await;
+        Ok(())
+    }
+}
+
+/// Mark the activity as deleted, or remove it with its splits, laps and segment efforts
+pub async fn delete_activity(pool: &SqlitePool, id: i64, remove: bool) -> Result<(), anyhow::Error> {
+    if !remove {
+        sqlx::query!(
+            r#"
+            UPDATE activity SET deleted = true WHERE id = ?
+            "#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        return Ok(());
+    }
+    sqlx::query!("DELETE FROM activity_split WHERE activity_id = ?", id)
+        .execute(pool)
+        .await?;
+    sqlx::query!("DELETE FROM activity_lap WHERE activity_id = ?", id)
+        .execute(pool)
+        .await?;
+    sqlx::query!("DELETE FROM activity_segment_effort WHERE activity_id = ?", id)
+        .execute(pool)
+        .await?;
+    sqlx::query!("DELETE FROM activity WHERE id = ?", id)
+        .execute(pool)
+        .await?;
+    sqlx::query!("DELETE FROM raw_activity WHERE id = ?", id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Fields of the listing which can be edited on Strava. A change to them
+/// discards the stored details until they are downloaded again. Other changes
+/// to the listing (e.g. kudos) refresh the details in place, which also picks
+/// up edited descriptions as they are not part of the listing.
+const EDITABLE_FIELDS: [&str; 14] = [
+    "name",
+    "sport_type",
+    "type",
+    "distance",
+    "moving_time",
+    "elapsed_time",
+    "start_date",
+    "total_elevation_gain",
+    "gear_id",
+    "private",
+    "visibility",
+    "commute",
+    "trainer",
+    "workout_type",
+];
+
+/// True if an editable field of the listed activity differs from the one which was stored
+fn is_changed(stored: &str, remote: &Value) -> bool {
+    match serde_json::from_str::<Value>(stored) {
+        Ok(stored) => EDITABLE_FIELDS.iter().any(|f| stored.get(f) != remote.get(f)),
+        Err(_) => true,
+    }
+}
+
+/// True if anything in the listed activity differs from the one which was stored
+fn is_listing_changed(stored: &str, remote: &Value) -> bool {
+    match serde_json::from_str::<Value>(stored) {
+        Ok(stored) => stored != *remote,
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn detect_changes() {
+        let remote = json!({"id": 1, "name": "Evening Run", "sport_type": "Run"});
+        assert!(!is_changed(
+            r#"{"sport_type": "Run", "id": 1, "name": "Evening Run"}"#,
+            &remote
+        ));
+        assert!(is_changed(
+            r#"{"id": 1, "name": "Morning Run", "sport_type": "Run"}"#,
+            &remote
+        ));
+        assert!(!is_changed(
+            r#"{"id": 1, "name": "Evening Run", "sport_type": "Run", "kudos_count": 3}"#,
+            &remote
+        ));
+        assert!(is_changed(
+            r#"{"id": 1, "name": "Evening Run", "sport_type": "Run", "gear_id": "g1"}"#,
+            &remote
+        ));
+        assert!(is_changed("", &remote));
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Local};
+
+use crate::event::input::{EventSender, InputEvent};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SyncPhase {
+    #[default]
+    Idle,
+    Reconciling,
+    Listing,
+    Downloading,
+    Athlete,
+    Converting,
+}
+
+impl SyncPhase {
+    pub fn to_label(&self) -> &str {
+        match self {
+            SyncPhase::Idle => "idle",
+            SyncPhase::Reconciling => "reconciling",
+            SyncPhase::Listing => "listing activities",
+            SyncPhase::Downloading => "downloading details",
+            SyncPhase::Athlete => "updating athlete and gear",
+            SyncPhase::Converting => "converting",
+        }
+    }
+}
+
+/// State of the current (or last) sync as shown in the status bar
+#[derive(Debug, Clone, Default)]
+pub struct SyncStatus {
+    pub phase: SyncPhase,
+    pub last_sync: Option<DateTime<Local>>,
+    /// Items processed in the current phase
+    pub done: usize,
+    /// Items to process in the current phase, 0 if not known up front
+    pub total: usize,
+    /// Activities found which were not synced before
+    pub new_activities: usize,
+    pub errors: usize,
+    pub last_error: Option<String>,
+}
+
+impl SyncStatus {
+    pub fn summary(&self) -> String {
+        let mut summary = match (&self.phase, self.total) {
+            (SyncPhase::Idle, _) => match &self.last_sync {
+                Some(time) => format!(
+                    "synced {}, {} new",
+                    time.format("%H:%M"),
+                    self.new_activities
+                ),
+                None => "not synced".to_string(),
+            },
+            (phase, 0) => format!("⟳ {} {}", phase.to_label(), self.done),
+            (phase, total) => format!("⟳ {} {}/{}", phase.to_label(), self.done, total),
+        };
+        match self.errors {
+            0 => (),
+            1 => summary.push_str(", 1 error"),
+            n => summary.push_str(&format!(", {} errors", n)),
+        }
+        summary
+    }
+}
+
+/// Tracks the progress of a sync and sends each change to the app
+pub struct SyncProgress {
+    status: SyncStatus,
+    event_sender: EventSender,
+}
+
+impl SyncProgress {
+    pub fn new(event_sender: EventSender) -> Self {
+        Self {
+            status: SyncStatus::default(),
+            event_sender,
+        }
+    }
+
+    /// Reset the counts at the start of a sync
+    pub async fn begin(&mut self) {
+        self.status = SyncStatus {
+            last_sync: self.status.last_sync,
+            ..SyncStatus::default()
+        };
+        self.send().await;
+    }
+
+    pub async fn start(&mut self, phase: SyncPhase, total: usize) {
+        self.status.phase = phase;
+        self.status.done = 0;
+        self.status.total = total;
+        self.send().await;
+    }
+
+    pub async fn advance(&mut self) {
+        self.status.done += 1;
+        self.send().await;
+    }
+
+    pub async fn new_activity(&mut self) {
+        self.status.new_activities += 1;
+        self.advance().await;
+    }
+
+    pub async fn error(&mut self, message: String) {
+        self.status.errors += 1;
+        self.status.last_error = Some(message);
+        self.send().await;
+    }
+
+    pub async fn finish(&mut self) {
+        self.status.phase = SyncPhase::Idle;
+        self.status.last_sync = Some(Local::now());
+        self.send().await;
+    }
+
+    async fn send(&self) {
+        // ignore errors - the app may already have quit
+        let _ = self
+            .event_sender
+            .send(InputEvent::SyncStatus(self.status.clone()))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn summary() {
+        let mut status = SyncStatus::default();
+        assert_eq!("not synced", status.summary());
+
+        status.phase = SyncPhase::Listing;
+        status.done = 12;
+        assert_eq!("⟳ listing activities 12", status.summary());
+
+        status.phase = SyncPhase::Downloading;
+        status.done = 3;
+        status.total = 10;
+        status.errors = 2;
+        assert_eq!("⟳ downloading details 3/10, 2 errors", status.summary());
+
+        status.phase = SyncPhase::Idle;
+        status.new_activities = 10;
+        status.errors = 0;
+        status.last_sync = Local.with_ymd_and_hms(2024, 3, 1, 14, 5, 0).single();
+        assert_eq!("synced 14:05, 10 new", status.summary());
+    }
+}
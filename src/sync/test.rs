@@ -0,0 +1,459 @@
+//! Runs the sync tasks against a fake Strava API serving the fixtures in
+//! `src/sync/fixtures` and an in-memory database.
+
+use std::{
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tokio::sync::mpsc;
+use url::form_urlencoded;
+
+use crate::{
+    client::{new_strava_client, StravaClient, StravaConfig},
+    component::unit_formatter::UnitSystem,
+    event::{input::EventSender, logger::Logger},
+    expr::evaluator::Evaluator,
+    store::{activity::ActivityStore, gear::GearType, migration::memory_pool},
+};
+
+use super::{
+    convert::ActivityConverter,
+    ingest_activities::IngestActivitiesTask,
+    ingest_activity::IngestActivityTask,
+    ingest_athlete::IngestAthleteTask,
+    reconcile::{ReconcileOptions, ReconcileTask},
+    status::SyncProgress,
+};
+
+/// Fields of a detailed activity which are not part of the listing
+const DETAIL_FIELDS: [&str; 5] = [
+    "description",
+    "splits_metric",
+    "splits_standard",
+    "laps",
+    "segment_efforts",
+];
+
+#[derive(Default)]
+struct FakeState {
+    /// Detailed activities, most recent first
+    activities: Vec<Value>,
+    streams: HashMap<i64, Value>,
+    athlete: Value,
+    athlete_stats: Value,
+    gear: Vec<Value>,
+    /// Activities for which the details can not be downloaded
+    failing: HashSet<i64>,
+    /// 15 minute usage reported in the rate limit headers
+    usage: Option<u32>,
+    /// Paths (and queries) of the received requests
+    requests: Vec<String>,
+}
+
+/// A local server answering the requests of the `StravaClient`
+struct FakeStrava {
+    address: SocketAddr,
+    state: Arc<Mutex<FakeState>>,
+}
+
+impl FakeStrava {
+    fn start() -> FakeStrava {
+        let state = Arc::new(Mutex::new(FakeState {
+            activities: serde_json::from_str(include_str!("fixtures/activities.json")).unwrap(),
+            streams: HashMap::from([
+                (1, serde_json::from_str(include_str!("fixtures/streams_1.json")).unwrap()),
+                (2, serde_json::from_str(include_str!("fixtures/streams_2.json")).unwrap()),
+            ]),
+            athlete: serde_json::from_str(include_str!("fixtures/athlete.json")).unwrap(),
+            athlete_stats: serde_json::from_str(include_str!("fixtures/athlete_stats.json")).unwrap(),
+            gear: serde_json::from_str(include_str!("fixtures/gear.json")).unwrap(),
+            ..FakeState::default()
+        }));
+
+        let service_state = state.clone();
+        let make_svc = make_service_fn(move |_con| {
+            let state = service_state.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let state = state.clone();
+                    async move { Ok::<_, Infallible>(respond(&req, &mut state.lock().unwrap())) }
+                }))
+            }
+        });
+        let server = Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(make_svc);
+        let address = server.local_addr();
+        tokio::spawn(server);
+
+        FakeStrava { address, state }
+    }
+
+    fn client(&self, logger: Logger) -> StravaClient {
+        new_strava_client(
+            StravaConfig {
+                base_url: format!("http://{}/api", self.address),
+                access_token: "token".to_string(),
+            },
+            logger,
+        )
+    }
+
+    fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+fn respond(req: &Request<Body>, state: &mut FakeState) -> Response<Body> {
+    state.requests.push(req.uri().to_string());
+    let path: Vec<&str> = req.uri().path().trim_start_matches("/api/v3/").split('/').collect();
+    let json = match path[..] {
+        ["athlete", "activities"] => {
+            let page = form_urlencoded::parse(req.uri().query().unwrap_or_default().as_bytes())
+                .find(|(k, _)| k == "page")
+                .and_then(|(_, v)| v.parse::<usize>().ok())
+                .unwrap_or(1);
+            let listed: Vec<Value> = match page {
+                1 => state
+                    .activities
+                    .iter()
+                    .map(|a| {
+                        let mut listed = a.clone();
+                        for field in DETAIL_FIELDS {
+                            listed.as_object_mut().unwrap().remove(field);
+                        }
+                        listed
+                    })
+                    .collect(),
+                _ => vec![],
+            };
+            Some(Value::from(listed))
+        }
+        ["activities", id] if state.failing.iter().any(|f| f.to_string() == id) => {
+            return Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::empty())
+                .unwrap()
+        }
+        ["activities", id] => state
+            .activities
+            .iter()
+            .find(|a| a["id"].to_string().as_str() == id)
+            .cloned(),
+        ["athlete"] => Some(state.athlete.clone()),
+        ["athletes", id, "stats"] if state.athlete["id"].to_string().as_str() == id => Some(state.athlete_stats.clone()),
+        ["gear", id] => state.gear.iter().find(|g| g["id"] == id).cloned(),
+        ["activities", id, "streams"] => id.parse().ok().and_then(|id| state.streams.get(&id).cloned()),
+        _ => None,
+    };
+    let mut res = match json {
+        Some(json) => Response::new(Body::from(json.to_string())),
+        None => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .unwrap(),
+    };
+    if let Some(usage) = state.usage {
+        let headers = res.headers_mut();
+        headers.insert("X-RateLimit-Limit", "100,1000".parse().unwrap());
+        headers.insert("X-RateLimit-Usage", format!("{},{}", usage, usage).parse().unwrap());
+    }
+    res
+}
+
+/// Event sender for the logger and progress which discards the events
+fn event_sender() -> EventSender {
+    let (sender, mut receiver) = mpsc::channel(32);
+    tokio::spawn(async move { while receiver.recv().await.is_some() {} });
+    sender
+}
+
+async fn sync(client: &StravaClient, pool: &SqlitePool, sender: &EventSender, progress: &mut SyncProgress) {
+    let logger = Logger::new(sender.clone());
+    IngestActivitiesTask::new(client, pool, logger.clone())
+        .execute(progress)
+        .await
+        .unwrap();
+    IngestActivityTask::new(client, pool, logger.clone(), 2)
+        .execute(progress)
+        .await
+        .unwrap();
+    IngestAthleteTask::new(client, pool, logger.clone())
+        .execute(progress)
+        .await
+        .unwrap();
+    ActivityConverter::new(pool, sender.clone(), logger)
+        .convert(progress)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn sync_pipeline() {
+    let strava = FakeStrava::start();
+    let pool = memory_pool().await;
+    let sender = event_sender();
+    let client = strava.client(Logger::new(sender.clone()));
+    let mut progress = SyncProgress::new(sender.clone());
+
+    sync(&client, &pool, &sender, &mut progress).await;
+
+    let activities = ActivityStore::new(&pool).activities().await;
+    assert_eq!(
+        vec!["Lunch Ride", "Morning Run"],
+        activities.to_vec().iter().map(|a| a.title.as_str()).collect::<Vec<&str>>()
+    );
+    let ride = activities.find(2).unwrap();
+    assert_eq!("Intervals on the hill", ride.description);
+    assert_eq!(Some("b1234".to_string()), ride.gear_id);
+    assert_eq!(300.0, ride.power_curve.points[0].watts);
+    let run = activities.find(1).unwrap();
+    assert_eq!(2, run.splits_metric.len());
+    assert_eq!(1609.3, run.splits_imperial[0].distance);
+    assert_eq!(
+        vec![(1, 1200.0, Some(158.0)), (2, 800.0, Some(150.0))],
+        run.laps
+            .iter()
+            .map(|l| (l.lap_index, l.distance, l.average_heartrate))
+            .collect::<Vec<(i64, f64, Option<f64>)>>()
+    );
+    assert!(ride.laps.is_empty());
+    assert_eq!(1, run.segment_efforts.len());
+    assert_eq!(
+        vec!["Park Loop"],
+        ActivityStore::new(&pool)
+            .segments()
+            .await
+            .values()
+            .map(|s| s.name.clone())
+            .collect::<Vec<String>>()
+    );
+
+    // streams are downloaded for rides with power data and for runs
+    let requests = strava.requests();
+    assert!(requests.iter().any(|r| r.starts_with("/api/v3/activities/2/streams")));
+    assert!(requests.iter().any(|r| r.starts_with("/api/v3/activities/1/streams")));
+    assert_eq!(
+        Some("4 × 300m @ 3:20/km, 150m jog".to_string()),
+        run.workout.as_ref().map(|w| w.summary(&UnitSystem::Metric))
+    );
+    assert!(ride.workout.is_none());
+    let mut evaluator = Evaluator::new();
+    let expr = evaluator.parse("workout ~ \"300m @\"").unwrap();
+    assert_eq!(1, activities.by_expr(&evaluator, &expr).len());
+
+    // only the listing (first and empty second page) and the athlete are
+    // requested on the next sync
+    let count = requests.len();
+    sync(&client, &pool, &sender, &mut progress).await;
+    assert_eq!(count + 4, strava.requests().len());
+    assert_eq!(2, ActivityStore::new(&pool).activities().await.len());
+}
+
+#[tokio::test]
+async fn athlete_and_gear() {
+    let strava = FakeStrava::start();
+    let pool = memory_pool().await;
+    let sender = event_sender();
+    let client = strava.client(Logger::new(sender.clone()));
+    let mut progress = SyncProgress::new(sender.clone());
+
+    sync(&client, &pool, &sender, &mut progress).await;
+
+    let mut store = ActivityStore::new(&pool);
+    let athlete = store.athlete().await.unwrap();
+    assert_eq!("Marianne Teutenberg", athlete.name());
+    assert_eq!(Some(240), athlete.ftp);
+    let stats = athlete.stats.unwrap();
+    assert_eq!(400, stats.all_run_totals.count);
+    assert_eq!(0, stats.ytd_ride_totals.count);
+
+    let gear = store.gear().await;
+    assert_eq!(
+        vec!["Old Trainers", "Roadie", "Trainers"],
+        gear.iter().map(|g| g.name.as_str()).collect::<Vec<&str>>()
+    );
+    // retired gear is not on the profile and found through the activities
+    assert!(gear[0].retired);
+    assert_eq!("Canyon Endurace", gear[1].model());
+    assert_eq!(GearType::Bike, gear[1].gear_type);
+    assert_eq!(GearType::Shoe, gear[2].gear_type);
+
+    let activities = store.activities().await;
+    assert_eq!(Some("Roadie".to_string()), activities.find(2).unwrap().gear());
+    assert_eq!(Some("Old Trainers".to_string()), activities.find(1).unwrap().gear());
+    let mut evaluator = Evaluator::new();
+    let expr = evaluator.parse("gear ~ \"Trainers\"").unwrap();
+    assert_eq!(1, activities.by_expr(&evaluator, &expr).len());
+
+    // the details of known gear are not downloaded again
+    sync(&client, &pool, &sender, &mut progress).await;
+    assert_eq!(
+        3,
+        strava.requests().iter().filter(|r| r.starts_with("/api/v3/gear/")).count()
+    );
+}
+
+#[tokio::test]
+async fn reconcile_edits_and_deletions() {
+    let strava = FakeStrava::start();
+    let pool = memory_pool().await;
+    let sender = event_sender();
+    let client = strava.client(Logger::new(sender.clone()));
+    let mut progress = SyncProgress::new(sender.clone());
+    sync(&client, &pool, &sender, &mut progress).await;
+
+    {
+        let mut state = strava.state.lock().unwrap();
+        state.activities.retain(|a| a["id"] != 2);
+        state.activities[0]["name"] = Value::from("Easy Run");
+        state.activities[0]["sport_type"] = Value::from("TrailRun");
+    }
+    ReconcileTask::new(&client, &pool, Logger::new(sender.clone()), &ReconcileOptions::default())
+        .execute(&mut progress)
+        .await
+        .unwrap();
+    sync(&client, &pool, &sender, &mut progress).await;
+
+    let activities = ActivityStore::new(&pool).activities().await;
+    assert_eq!(1, activities.len());
+    let run = activities.find(1).unwrap();
+    assert_eq!("Easy Run", run.title);
+    assert_eq!("TrailRun", run.activity_type);
+}
+
+#[tokio::test]
+async fn reconcile_refreshes_descriptions() {
+    let strava = FakeStrava::start();
+    let pool = memory_pool().await;
+    let sender = event_sender();
+    let client = strava.client(Logger::new(sender.clone()));
+    let mut progress = SyncProgress::new(sender.clone());
+    sync(&client, &pool, &sender, &mut progress).await;
+    let details = |requests: Vec<String>| {
+        requests
+            .iter()
+            .filter(|r| r.starts_with("/api/v3/activities/2?") || r.as_str() == "/api/v3/activities/2")
+            .count()
+    };
+    let reconcile = || async {
+        ReconcileTask::new(&client, &pool, Logger::new(sender.clone()), &ReconcileOptions::default())
+            .execute(&mut SyncProgress::new(sender.clone()))
+            .await
+            .unwrap();
+    };
+
+    // an unchanged listing does not download the details again
+    reconcile().await;
+    assert_eq!(1, details(strava.requests()));
+
+    {
+        let mut state = strava.state.lock().unwrap();
+        let ride = state.activities.iter_mut().find(|a| a["id"] == 2).unwrap();
+        ride["description"] = Value::from("Hill repeats in the rain");
+        ride["kudos_count"] = Value::from(7);
+    }
+    reconcile().await;
+    sync(&client, &pool, &sender, &mut progress).await;
+
+    assert_eq!(2, details(strava.requests()));
+    let activities = ActivityStore::new(&pool).activities().await;
+    let ride = activities.find(2).unwrap();
+    assert_eq!("Hill repeats in the rain", ride.description);
+    assert_eq!(7, ride.kudos);
+}
+
+#[tokio::test]
+async fn failed_downloads_are_recorded() {
+    let strava = FakeStrava::start();
+    strava.state.lock().unwrap().failing.insert(1);
+    let pool = memory_pool().await;
+    let sender = event_sender();
+    let client = strava.client(Logger::new(sender.clone()));
+    let mut progress = SyncProgress::new(sender.clone());
+
+    sync(&client, &pool, &sender, &mut progress).await;
+    sync(&client, &pool, &sender, &mut progress).await;
+
+    let attempts: Vec<(i64, i64, Option<String>)> =
+        sqlx::query_as("SELECT id, attempts, last_error FROM raw_activity ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(2, attempts[0].1);
+    assert!(attempts[0].2.as_deref().unwrap().contains("500"));
+    assert_eq!((2, 0, None), attempts[1]);
+    assert_eq!(
+        "Intervals on the hill",
+        ActivityStore::new(&pool).activities().await.find(2).unwrap().description
+    );
+
+    // the activity is downloaded once it does not fail anymore
+    strava.state.lock().unwrap().failing.clear();
+    sync(&client, &pool, &sender, &mut progress).await;
+    let (attempts, last_error): (i64, Option<String>) =
+        sqlx::query_as("SELECT attempts, last_error FROM raw_activity WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!((0, None), (attempts, last_error));
+}
+
+#[tokio::test]
+async fn failed_stream_downloads_are_recorded() {
+    let strava = FakeStrava::start();
+    strava.state.lock().unwrap().streams.remove(&1);
+    let pool = memory_pool().await;
+    let sender = event_sender();
+    let client = strava.client(Logger::new(sender.clone()));
+    let mut progress = SyncProgress::new(sender.clone());
+
+    sync(&client, &pool, &sender, &mut progress).await;
+    sync(&client, &pool, &sender, &mut progress).await;
+
+    // the failing run does not keep the ride from being downloaded
+    let streams: Vec<(i64, bool, i64, Option<String>)> = sqlx::query_as(
+        "SELECT id, streams IS NOT NULL, stream_attempts, stream_error FROM raw_activity ORDER BY id",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!((1, false, 2), (streams[0].0, streams[0].1, streams[0].2));
+    assert!(streams[0].3.as_deref().unwrap().contains("404"));
+    assert_eq!((2, true, 0, None), streams[1]);
+}
+
+#[tokio::test]
+async fn downloads_stay_within_the_rate_limit() {
+    let strava = FakeStrava::start();
+    // one request left after the listing and the reserve
+    strava.state.lock().unwrap().usage = Some(89);
+    let pool = memory_pool().await;
+    let sender = event_sender();
+    let client = strava.client(Logger::new(sender.clone()));
+    let mut progress = SyncProgress::new(sender.clone());
+
+    sync(&client, &pool, &sender, &mut progress).await;
+
+    let downloaded = |requests: Vec<String>| {
+        requests
+            .iter()
+            .filter(|r| r.starts_with("/api/v3/activities/") && !r.contains("streams"))
+            .count()
+    };
+    let streams = |requests: Vec<String>| requests.iter().filter(|r| r.contains("streams")).count();
+    assert_eq!(1, downloaded(strava.requests()));
+    assert_eq!(1, streams(strava.requests()));
+
+    strava.state.lock().unwrap().usage = Some(0);
+    sync(&client, &pool, &sender, &mut progress).await;
+    assert_eq!(2, downloaded(strava.requests()));
+    assert_eq!(2, streams(strava.requests()));
+}
@@ -0,0 +1,270 @@
+use std::{collections::HashMap, convert::Infallible, net::SocketAddr};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use tokio::{sync::mpsc::Sender, task};
+use url::form_urlencoded;
+
+use crate::event::logger::Logger;
+
+use super::SyncRequest;
+
+const PATH: &str = "/webhook";
+
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub address: SocketAddr,
+    /// Token sent by Strava when validating the subscription
+    pub verify_token: String,
+    /// ID of the push subscription, events of other subscriptions are dropped
+    pub subscription_id: Option<i64>,
+}
+
+/// Event pushed by Strava to the subscription's callback URL
+#[derive(Deserialize, Debug)]
+pub struct WebhookEvent {
+    pub object_type: String,
+    pub object_id: i64,
+    pub aspect_type: String,
+    pub owner_id: i64,
+    pub subscription_id: Option<i64>,
+    #[serde(default)]
+    pub updates: HashMap<String, Value>,
+}
+
+impl WebhookEvent {
+    /// Events are not signed, only those of the synced athlete (and the
+    /// configured subscription) are trusted
+    pub fn is_trusted(&self, config: &WebhookConfig, athlete_id: Option<i64>) -> bool {
+        let subscribed = match config.subscription_id {
+            Some(id) => self.subscription_id == Some(id),
+            None => true,
+        };
+        subscribed && athlete_id == Some(self.owner_id)
+    }
+
+    pub fn request(&self) -> Option<SyncRequest> {
+        match (self.object_type.as_str(), self.aspect_type.as_str()) {
+            ("activity", "create" | "update") => Some(SyncRequest::Activity(self.object_id)),
+            ("activity", "delete") => Some(SyncRequest::Delete(self.object_id)),
+            ("athlete", "update") if self.updates.get("authorized") == Some(&json!("false")) => {
+                Some(SyncRequest::Deauthorize)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Listen for the events of a Strava push subscription and request the sync
+/// of the created, updated or deleted activities.
+pub fn spawn_webhook(
+    config: WebhookConfig,
+    pool: SqlitePool,
+    sync_sender: Sender<SyncRequest>,
+    logger: Logger,
+) -> task::JoinHandle<()> {
+    task::spawn(async move {
+        let address = config.address;
+        let make_svc = make_service_fn(move |_con| {
+            let config = config.clone();
+            let pool = pool.clone();
+            let sync_sender = sync_sender.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let config = config.clone();
+                    let pool = pool.clone();
+                    let sync_sender = sync_sender.clone();
+                    async move {
+                        let athlete_id = athlete_id(&pool).await;
+                        Ok::<_, Infallible>(handle(req, &config, athlete_id, &sync_sender).await)
+                    }
+                }))
+            }
+        });
+
+        let server = match Server::try_bind(&address) {
+            Ok(builder) => builder.serve(make_svc),
+            Err(e) => {
+                logger
+                    .error(format!("Could not start webhook listener on {}: {}", address, e))
+                    .await;
+                return;
+            }
+        };
+        log::info!("listening for webhook events at {}{}", address, PATH);
+        if let Err(e) = server.await {
+            logger.error(format!("Webhook listener error: {}", e)).await;
+        }
+    })
+}
+
+/// ID of the synced athlete, events can not be verified before the first sync
+async fn athlete_id(pool: &SqlitePool) -> Option<i64> {
+    sqlx::query_scalar::<_, i64>("SELECT id FROM athlete ORDER BY updated_at DESC LIMIT 1")
+        .fetch_optional(pool)
+        .await
+        .ok()
+        .flatten()
+}
+
+async fn handle(
+    req: Request<Body>,
+    config: &WebhookConfig,
+    athlete_id: Option<i64>,
+    sync_sender: &Sender<SyncRequest>,
+) -> Response<Body> {
+    if req.uri().path() != PATH {
+        return status(StatusCode::NOT_FOUND);
+    }
+    match *req.method() {
+        Method::GET => validate(req.uri().query().unwrap_or_default(), &config.verify_token),
+        Method::POST => {
+            let bytes = match hyper::body::to_bytes(req.into_body()).await {
+                Ok(b) => b,
+                Err(_) => return status(StatusCode::BAD_REQUEST),
+            };
+            let event: WebhookEvent = match serde_json::from_slice(&bytes) {
+                Ok(e) => e,
+                Err(_) => return status(StatusCode::BAD_REQUEST),
+            };
+            log::info!("webhook event: {:?}", event);
+            if !event.is_trusted(config, athlete_id) {
+                log::warn!("dropped webhook event of owner {} (subscription {:?})", event.owner_id, event.subscription_id);
+                return status(StatusCode::FORBIDDEN);
+            }
+            if let Some(request) = event.request() {
+                if sync_sender.send(request).await.is_err() {
+                    return status(StatusCode::SERVICE_UNAVAILABLE);
+                }
+            }
+            status(StatusCode::OK)
+        }
+        _ => status(StatusCode::METHOD_NOT_ALLOWED),
+    }
+}
+
+/// Echo the challenge when Strava validates the callback URL of a new subscription
+fn validate(query: &str, verify_token: &str) -> Response<Body> {
+    let params: HashMap<String, String> = form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    let param = |name: &str| params.get(name).map(|v| v.as_str());
+    match (param("hub.mode"), param("hub.verify_token"), param("hub.challenge")) {
+        (Some("subscribe"), Some(token), Some(challenge)) if token == verify_token => Response::builder()
+            .header("Content-Type", "application/json")
+            .body(Body::from(json!({ "hub.challenge": challenge }).to_string()))
+            .unwrap(),
+        _ => status(StatusCode::FORBIDDEN),
+    }
+}
+
+fn status(code: StatusCode) -> Response<Body> {
+    Response::builder().status(code).body(Body::empty()).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::sync::mpsc::{self, Receiver};
+
+    use super::*;
+
+    const ATHLETE_ID: i64 = 134815;
+
+    fn config(subscription_id: Option<i64>) -> WebhookConfig {
+        WebhookConfig {
+            address: "127.0.0.1:8113".parse().unwrap(),
+            verify_token: "secret".to_string(),
+            subscription_id,
+        }
+    }
+
+    async fn post_to(config: &WebhookConfig, athlete_id: Option<i64>, body: &str) -> (StatusCode, Receiver<SyncRequest>) {
+        let (sender, receiver) = mpsc::channel(1);
+        let req = Request::post(PATH).body(Body::from(body.to_string())).unwrap();
+        (handle(req, config, athlete_id, &sender).await.status(), receiver)
+    }
+
+    async fn post(body: &str) -> (StatusCode, Receiver<SyncRequest>) {
+        post_to(&config(None), Some(ATHLETE_ID), body).await
+    }
+
+    #[tokio::test]
+    async fn validates_subscription() {
+        let (sender, _receiver) = mpsc::channel(1);
+        let req = Request::get("/webhook?hub.mode=subscribe&hub.challenge=15f7d1a91c1f40f8&hub.verify_token=secret")
+            .body(Body::empty())
+            .unwrap();
+        let res = handle(req, &config(None), None, &sender).await;
+        assert_eq!(StatusCode::OK, res.status());
+        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(r#"{"hub.challenge":"15f7d1a91c1f40f8"}"#, body);
+
+        let req = Request::get("/webhook?hub.mode=subscribe&hub.challenge=1&hub.verify_token=wrong")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(StatusCode::FORBIDDEN, handle(req, &config(None), None, &sender).await.status());
+    }
+
+    #[tokio::test]
+    async fn activity_events() {
+        let (code, mut receiver) = post(
+            r#"{"aspect_type": "update", "event_time": 1516126040, "object_id": 1360128428, "object_type": "activity", "owner_id": 134815, "subscription_id": 120475, "updates": {"title": "Messy"}}"#,
+        )
+        .await;
+        assert_eq!(StatusCode::OK, code);
+        assert_eq!(Some(SyncRequest::Activity(1360128428)), receiver.recv().await);
+
+        let (_, mut receiver) = post(
+            r#"{"aspect_type": "delete", "object_id": 1360128428, "object_type": "activity", "owner_id": 134815}"#,
+        )
+        .await;
+        assert_eq!(Some(SyncRequest::Delete(1360128428)), receiver.recv().await);
+
+        let (code, _) = post("not json").await;
+        assert_eq!(StatusCode::BAD_REQUEST, code);
+    }
+
+    #[tokio::test]
+    async fn deauthorization_event() {
+        let (_, mut receiver) = post(
+            r#"{"aspect_type": "update", "object_id": 134815, "object_type": "athlete", "owner_id": 134815, "updates": {"authorized": "false"}}"#,
+        )
+        .await;
+        assert_eq!(Some(SyncRequest::Deauthorize), receiver.recv().await);
+    }
+
+    #[tokio::test]
+    async fn rejects_untrusted_events() {
+        let deauthorize = r#"{"aspect_type": "update", "object_id": 1, "object_type": "athlete", "owner_id": 1, "subscription_id": 120475, "updates": {"authorized": "false"}}"#;
+        let (code, mut receiver) = post(deauthorize).await;
+        assert_eq!(StatusCode::FORBIDDEN, code);
+        assert!(receiver.try_recv().is_err());
+
+        let delete = r#"{"aspect_type": "delete", "object_id": 1360128428, "object_type": "activity", "owner_id": 134815, "subscription_id": 999}"#;
+        let (code, mut receiver) = post_to(&config(Some(120475)), Some(ATHLETE_ID), delete).await;
+        assert_eq!(StatusCode::FORBIDDEN, code);
+        assert!(receiver.try_recv().is_err());
+
+        // the owner can not be verified before the athlete was synced
+        let (code, _) = post_to(&config(None), None, delete).await;
+        assert_eq!(StatusCode::FORBIDDEN, code);
+
+        // events without an owner are not accepted
+        let (code, _) = post(r#"{"aspect_type": "delete", "object_id": 1, "object_type": "activity"}"#).await;
+        assert_eq!(StatusCode::BAD_REQUEST, code);
+
+        let (code, mut receiver) = post_to(
+            &config(Some(120475)),
+            Some(ATHLETE_ID),
+            r#"{"aspect_type": "delete", "object_id": 1360128428, "object_type": "activity", "owner_id": 134815, "subscription_id": 120475}"#,
+        )
+        .await;
+        assert_eq!(StatusCode::OK, code);
+        assert_eq!(Some(SyncRequest::Delete(1360128428)), receiver.recv().await);
+    }
+}
@@ -1,20 +1,171 @@
+use std::str::FromStr;
+
 use tui::style::Color;
 
-pub enum ColorTheme {
-    Orange, // TODO: rename after role not color
-    Dialog,
+pub const THEMES: [&str; 4] = ["dark", "light", "high-contrast", "colour-blind"];
+
+const STRAVA_ORANGE: Color = Color::Rgb(252, 76, 2);
+
+/// Colours of every widget, named after the role they play rather than the colour
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// Key hints, dialog options, bars and the current activity
+    pub accent: Color,
+    /// Text drawn on top of the accent colour
+    pub on_accent: Color,
+    pub dialog: Color,
+    pub text: Color,
+    pub muted: Color,
+    /// Table headers and secondary information
+    pub dim: Color,
+    pub background: Color,
+    pub axis_title: Color,
+    pub chart_line: Color,
+    pub chart_points: Color,
+    pub chart_marker: Color,
+    pub chart_curve: Color,
+    pub good: Color,
+    pub bad: Color,
+    pub warning: Color,
+    pub info: Color,
+    pub trace: Color,
+    /// Colour scale of the splits and the route, from fastest to slowest
+    pub gradient_start: Rgb,
+    pub gradient_end: Rgb,
+    /// Colour scale of the selected split on the route
+    pub highlight_start: Rgb,
+    pub highlight_end: Rgb,
 }
 
-impl ColorTheme {
-    pub fn to_color(&self) -> Color {
-        match *self {
-            ColorTheme::Orange => Color::Rgb(252, 76, 2),
-            ColorTheme::Dialog => Color::Rgb(252, 76, 2),
+impl Theme {
+    pub fn dark() -> Theme {
+        Theme {
+            accent: STRAVA_ORANGE,
+            on_accent: Color::Black,
+            dialog: STRAVA_ORANGE,
+            text: Color::White,
+            muted: Color::Gray,
+            dim: Color::DarkGray,
+            background: Color::Black,
+            axis_title: Color::Red,
+            chart_line: Color::Blue,
+            chart_points: Color::Magenta,
+            chart_marker: Color::Green,
+            chart_curve: Color::Yellow,
+            good: Color::Green,
+            bad: Color::Red,
+            warning: Color::Yellow,
+            info: Color::Cyan,
+            trace: Color::Magenta,
+            gradient_start: Rgb::new(0, 255, 0),
+            gradient_end: Rgb::new(255, 0, 0),
+            highlight_start: Rgb::new(50, 255, 255),
+            highlight_end: Rgb::new(255, 50, 255),
+        }
+    }
+
+    pub fn light() -> Theme {
+        Theme {
+            accent: Color::Rgb(204, 60, 0),
+            on_accent: Color::White,
+            dialog: Color::Rgb(204, 60, 0),
+            text: Color::Black,
+            muted: Color::DarkGray,
+            dim: Color::Gray,
+            background: Color::Reset,
+            axis_title: Color::Rgb(160, 0, 0),
+            chart_line: Color::Rgb(0, 60, 180),
+            chart_points: Color::Rgb(140, 0, 140),
+            chart_marker: Color::Rgb(0, 130, 0),
+            chart_curve: Color::Rgb(170, 110, 0),
+            good: Color::Rgb(0, 130, 0),
+            bad: Color::Rgb(190, 0, 0),
+            warning: Color::Rgb(170, 110, 0),
+            info: Color::Rgb(0, 110, 140),
+            trace: Color::Rgb(140, 0, 140),
+            gradient_start: Rgb::new(0, 150, 0),
+            gradient_end: Rgb::new(200, 0, 0),
+            highlight_start: Rgb::new(0, 120, 200),
+            highlight_end: Rgb::new(160, 0, 160),
+        }
+    }
+
+    pub fn high_contrast() -> Theme {
+        Theme {
+            accent: Color::Yellow,
+            on_accent: Color::Black,
+            dialog: Color::White,
+            text: Color::White,
+            muted: Color::White,
+            dim: Color::Yellow,
+            background: Color::Black,
+            axis_title: Color::Yellow,
+            chart_line: Color::Cyan,
+            chart_points: Color::White,
+            chart_marker: Color::Yellow,
+            chart_curve: Color::Cyan,
+            good: Color::LightGreen,
+            bad: Color::LightRed,
+            warning: Color::Yellow,
+            info: Color::Cyan,
+            trace: Color::White,
+            gradient_start: Rgb::new(0, 255, 0),
+            gradient_end: Rgb::new(255, 0, 0),
+            highlight_start: Rgb::new(0, 255, 255),
+            highlight_end: Rgb::new(255, 255, 0),
         }
     }
+
+    /// Dark theme avoiding red/green contrasts, using the Okabe-Ito palette
+    pub fn colour_blind() -> Theme {
+        Theme {
+            chart_line: Color::Rgb(0, 114, 178),
+            chart_points: Color::Rgb(204, 121, 167),
+            chart_marker: Color::Rgb(240, 228, 66),
+            chart_curve: Color::Rgb(230, 159, 0),
+            axis_title: Color::Rgb(86, 180, 233),
+            good: Color::Rgb(0, 114, 178),
+            bad: Color::Rgb(230, 159, 0),
+            gradient_start: Rgb::new(0, 114, 178),
+            gradient_end: Rgb::new(230, 159, 0),
+            highlight_start: Rgb::new(86, 180, 233),
+            highlight_end: Rgb::new(240, 228, 66),
+            ..Theme::dark()
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Theme, String> {
+        match name {
+            "dark" => Ok(Theme::dark()),
+            "light" => Ok(Theme::light()),
+            "high-contrast" => Ok(Theme::high_contrast()),
+            "colour-blind" => Ok(Theme::colour_blind()),
+            _ => Err(format!(
+                "unknown theme `{}`, expected one of: {}",
+                name,
+                THEMES.join(", ")
+            )),
+        }
+    }
+
+    /// Colour at `offset` on the gradient scale of the given size
+    pub fn gradient(&self, offset: f64, size: f64) -> Color {
+        gradient(self.gradient_start, self.gradient_end, offset, size).to_color()
+    }
+
+    /// Colour at `offset` on the highlight scale of the given size
+    pub fn highlight(&self, offset: f64, size: f64) -> Color {
+        gradient(self.highlight_start, self.highlight_end, offset, size).to_color()
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rgb {
     pub red: u8,
     pub green: u8,
@@ -22,11 +173,30 @@ pub struct Rgb {
 }
 
 impl Rgb {
+    pub const fn new(red: u8, green: u8, blue: u8) -> Rgb {
+        Rgb { red, green, blue }
+    }
+
     pub fn to_color(&self) -> Color {
         Color::Rgb(self.red, self.green, self.blue)
     }
 }
 
+impl FromStr for Rgb {
+    type Err = String;
+
+    /// Parse a hex colour, e.g. `#fc4c02`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid colour `{}`, expected a hex colour like #fc4c02", s);
+        let hex = s.strip_prefix('#').ok_or_else(error)?;
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(error());
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| error());
+        Ok(Rgb::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
 pub fn gradient(start: Rgb, end: Rgb, offset: f64, size: f64) -> Rgb {
     let rdiff = (end.red as f64 - start.red as f64) / size;
     let gdiff = (end.green as f64 - start.green as f64) / size;
@@ -41,7 +211,7 @@ pub fn gradient(start: Rgb, end: Rgb, offset: f64, size: f64) -> Rgb {
 
 #[cfg(test)]
 mod tests {
-    use super::{gradient, Rgb};
+    use super::{gradient, Rgb, Theme};
 
     #[test]
     fn test_gradient() {
@@ -63,4 +233,18 @@ mod tests {
         assert_eq!(0, rgb.red);
         assert_eq!(51, rgb.blue);
     }
+
+    #[test]
+    fn test_parse_rgb() {
+        assert_eq!(Ok(Rgb::new(252, 76, 2)), "#fc4c02".parse());
+        assert!("fc4c02".parse::<Rgb>().is_err());
+        assert!("#fc4c0".parse::<Rgb>().is_err());
+        assert!("#fc4czz".parse::<Rgb>().is_err());
+    }
+
+    #[test]
+    fn test_theme_from_name() {
+        assert_eq!(Ok(Theme::light()), Theme::from_name("light"));
+        assert!(Theme::from_name("neon").is_err());
+    }
 }
@@ -0,0 +1,95 @@
+use tui::{
+    layout::{Margin, Rect},
+    prelude::Buffer,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::{app::App, event::keymap::StravaEvent};
+
+const CATEGORIES: [&str; 5] = ["Navigation", "Views", "Activities", "Display", "Actions"];
+
+/// Lines listing the keys of the given events grouped by category
+pub fn help_lines(app: &App, mut events: Vec<StravaEvent>) -> Vec<Line<'static>> {
+    let view = app.active_page().name();
+    events.sort();
+    events.dedup();
+
+    let mut lines = vec![];
+    for category in CATEGORIES {
+        let bound: Vec<(String, &StravaEvent)> = events
+            .iter()
+            .filter(|e| StravaEvent::category(e) == category)
+            .filter_map(|e| {
+                let keys = app.key_map.keys(e, view);
+                match keys.is_empty() {
+                    true => None,
+                    false => Some((
+                        keys.iter().map(|k| k.to_string()).collect::<Vec<String>>().join(", "),
+                        e,
+                    )),
+                }
+            })
+            .collect();
+        if bound.is_empty() {
+            continue;
+        }
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            category,
+            Style::default()
+                .fg(app.theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )));
+        let width = bound.iter().map(|(keys, _)| keys.len()).max().unwrap_or(0);
+        for (keys, event) in bound {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:width$}  ", keys, width = width), Style::default().fg(app.theme.text)),
+                Span::styled(StravaEvent::describe(event), Style::default().fg(app.theme.muted)),
+            ]));
+        }
+    }
+    lines
+}
+
+fn footer(app: &App) -> String {
+    let view = app.active_page().name();
+    let key = |event: &StravaEvent| {
+        app.key_map
+            .key(event, view)
+            .map_or_else(|| "".to_string(), |k| k.to_string())
+    };
+    format!(
+        "[{}/{}] close [{}/{}] scroll",
+        key(&StravaEvent::Help),
+        key(&StravaEvent::Escape),
+        key(&StravaEvent::Down),
+        key(&StravaEvent::Up)
+    )
+}
+
+pub fn draw(app: &mut App, f: &mut Buffer, area: Rect, events: Vec<StravaEvent>) {
+    let lines = help_lines(app, events);
+    let rect = area.inner(Margin {
+        vertical: 1,
+        horizontal: 2,
+    });
+    // keep the last line at the bottom when scrolled past the end
+    let max_scroll = (lines.len() as u16).saturating_sub(rect.height.saturating_sub(2));
+    app.help_scroll = app.help_scroll.min(max_scroll);
+
+    Clear.render(rect, f);
+    Paragraph::new(lines)
+        .scroll((app.help_scroll, 0))
+        .block(
+            Block::default()
+                .title(format!("Help: {}", app.active_page().name()))
+                .title_bottom(footer(app))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.dialog)),
+        )
+        .render(rect, f);
+}
@@ -1,4 +1,6 @@
 pub mod color;
+pub mod help;
+pub mod status_bar;
 
 use crate::{app::App, component::View, event::keymap::StravaEvent};
 use crossterm::event::KeyCode;
@@ -6,12 +8,10 @@ use tui::{
     layout::{Constraint, Layout, Rect},
     prelude::Buffer,
     style::Style,
-    text::{Line, Span, Text},
+    text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Widget},
 };
 
-use self::color::ColorTheme;
-
 pub fn draw(app: &mut App, f: &mut Buffer, area: Rect, view: &mut dyn View) {
     let rows = Layout::default()
         .margin(0)
@@ -25,31 +25,33 @@ pub fn draw(app: &mut App, f: &mut Buffer, area: Rect, view: &mut dyn View) {
         )
         .split(area);
 
-    header(app, view.mapped_events(app)).render(rows[0], f);
+    let mut mapped_events = view.mapped_events(app);
+    header(app, mapped_events.clone()).render(rows[0], f);
 
     view.draw(app, f, rows[1]);
 
-    status_bar(app).render(rows[2], f);
+    if app.help_visible {
+        mapped_events.push(StravaEvent::Help);
+        help::draw(app, f, rows[1], mapped_events);
+    }
+
+    status_bar::draw(app, f, rows[2]);
 }
 
 fn header<'a>(app: &'a mut App, mapped_events: Vec<StravaEvent>) -> Paragraph<'a> {
-    let strava = ColorTheme::Orange.to_color();
+    let strava = app.theme.accent;
     let mut hints: Vec<Span> = vec![];
-    for event in mapped_events {
-        match app.key_map.key(&event) {
-            Some(k) => match k {
-                KeyCode::Char(c) => {
+    for event in [StravaEvent::Help].into_iter().chain(mapped_events) {
+        match app.key_map.key(&event, app.active_page().name()) {
+            Some(chord) => match chord.code {
+                KeyCode::Enter | KeyCode::Esc if chord.modifiers.is_empty() => (),
+                _ => {
                     hints.push(Span::styled(
-                        format!("[{}]", c),
+                        format!("[{}]", chord),
                         Style::default().fg(strava),
                     ));
                     hints.push(Span::raw(format!("{} ", StravaEvent::describe(&event))));
                 }
-                KeyCode::Tab => {
-                    hints.push(Span::styled("[tab]", Style::default().fg(strava)));
-                    hints.push(Span::raw(format!("{} ", StravaEvent::describe(&event))));
-                }
-                _ => (),
             },
             None => continue,
         }
@@ -63,39 +65,6 @@ fn header<'a>(app: &'a mut App, mapped_events: Vec<StravaEvent>) -> Paragraph<'a
     )
 }
 
-fn status_bar<'a>(app: &'a mut App) -> Paragraph<'a> {
-    let mut status: Vec<String> = Vec::new();
-    if let Some(message) = &app.info_message {
-        status.push(message.to_string());
-    } else {
-        if app.filters.filter != *"" {
-            status.push(format!("filtered by \"{}\"", app.filters.filter))
-        }
-        status.push(format!("{} activities", app.activities().len()));
-        status.push(format!(
-            "sorted by {} {}",
-            app.filters.sort_by, app.filters.sort_order
-        ));
-        status.push(format!(
-            "ranked by {} {}",
-            app.ranking.rank_by, app.ranking.rank_order
-        ));
-        status.push(format!("{} units", app.unit_formatter.system));
-        if let Some(anchored) = &app.activity_anchored {
-            status.push(format!(
-                "anchored to \"{}\" ± {:.3}",
-                anchored.title, app.filters.anchor_tolerance
-            ));
-        }
-    }
-
-    if let Some(message) = &app.error_message {
-        status.push(format!("\n{}", message));
-    }
-
-    Paragraph::new(Text::from(status.join(", ")))
-}
-
 // borrowed from https://github.com/extrawurst/gitui
 pub fn centered_rect_absolute(width: u16, height: u16, r: Rect) -> Rect {
     Rect::new(
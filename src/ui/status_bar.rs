@@ -0,0 +1,69 @@
+use tui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    prelude::Buffer,
+    style::Style,
+    text::{Span, Text},
+    widgets::{Paragraph, Widget},
+};
+
+use crate::{app::App, sync::status::SyncPhase};
+
+pub fn draw(app: &mut App, f: &mut Buffer, area: Rect) {
+    let sync = sync_status(app);
+    let columns = Layout::horizontal([
+        Constraint::Min(0),
+        Constraint::Length(sync.width() as u16 + 1),
+    ])
+    .split(area);
+
+    status(app).render(columns[0], f);
+    Paragraph::new(sync)
+        .alignment(Alignment::Right)
+        .render(columns[1], f);
+}
+
+fn status(app: &App) -> Paragraph<'static> {
+    let mut status: Vec<String> = Vec::new();
+    if let Some(message) = &app.info_message {
+        status.push(message.to_string());
+    } else {
+        if app.filters.filter != *"" {
+            status.push(format!("filtered by \"{}\"", app.filters.filter))
+        }
+        status.push(format!("{} activities", app.activities.len()));
+        status.push(format!(
+            "sorted by {} {}",
+            app.filters.sort_by, app.filters.sort_order
+        ));
+        status.push(format!(
+            "ranked by {} {}",
+            app.ranking.rank_by, app.ranking.rank_order
+        ));
+        status.push(format!("{} units", app.unit_formatter.system));
+        if let Some(anchored) = &app.activity_anchored {
+            status.push(format!(
+                "anchored to \"{}\" ± {:.3}",
+                anchored.title, app.filters.anchor_tolerance
+            ));
+        }
+        if let Some(marked) = &app.activity_marked {
+            status.push(format!("marked \"{}\"", marked.title));
+        }
+    }
+
+    if let Some(message) = &app.error_message {
+        status.push(format!("\n{}", message));
+    }
+
+    Paragraph::new(Text::from(status.join(", ")))
+}
+
+fn sync_status(app: &App) -> Span<'static> {
+    let status = &app.sync_status;
+    let color = match (&status.phase, status.errors) {
+        (SyncPhase::Idle, 0) => app.theme.muted,
+        (_, 0) => app.theme.info,
+        _ => app.theme.bad,
+    };
+    Span::styled(status.summary(), Style::default().fg(color))
+}
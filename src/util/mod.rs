@@ -1 +0,0 @@
-pub mod time_format;